@@ -0,0 +1,235 @@
+// @generated by bevy_ldtk_asset::codegen. Do not edit by hand!
+
+/// World identifiers in the LDtk project.
+pub mod worlds {
+    pub const WORLD: &str = "World";
+}
+
+/// Level identifiers in the LDtk project.
+pub mod levels {
+    pub const LEVEL_0: &str = "Level_0";
+}
+
+/// Layer identifiers in the LDtk project.
+pub mod layers {
+    pub const OBJECTS: &str = "Objects";
+    pub const TERRAIN: &str = "Terrain";
+    pub const THINGS: &str = "Things";
+}
+
+/// Enums defined in the LDtk project.
+pub mod enums {
+    /// The LDtk enum `Terrain_Type`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ::bevy_ldtk_asset::ldtk_enum::LdtkEnum)]
+    #[ldtk(rename = "Terrain_Type")]
+    pub enum TerrainType {
+        #[ldtk(rename = "Sand")]
+        Sand,
+        #[ldtk(rename = "Dirt")]
+        Dirt,
+        #[ldtk(rename = "Grass")]
+        Grass,
+        #[ldtk(rename = "Gravel")]
+        Gravel,
+        #[ldtk(rename = "Water")]
+        Water,
+        #[ldtk(rename = "Objects")]
+        Objects,
+        #[ldtk(rename = "Water_0")]
+        Water0,
+        #[ldtk(rename = "Water_1")]
+        Water1,
+        #[ldtk(rename = "Water_2")]
+        Water2,
+        #[ldtk(rename = "Water_3")]
+        Water3,
+        #[ldtk(rename = "Water_4")]
+        Water4,
+    }
+
+    impl TerrainType {
+        pub const IDENTIFIER: &'static str = "Terrain_Type";
+
+        pub const fn as_str(&self) -> &'static str {
+            match self {
+                Self::Sand => "Sand",
+                Self::Dirt => "Dirt",
+                Self::Grass => "Grass",
+                Self::Gravel => "Gravel",
+                Self::Water => "Water",
+                Self::Objects => "Objects",
+                Self::Water0 => "Water_0",
+                Self::Water1 => "Water_1",
+                Self::Water2 => "Water_2",
+                Self::Water3 => "Water_3",
+                Self::Water4 => "Water_4",
+            }
+        }
+    }
+
+    impl ::core::str::FromStr for TerrainType {
+        type Err = ::bevy_ldtk_asset::error::LdtkError;
+
+        fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+            match value {
+                "Sand" => ::core::result::Result::Ok(Self::Sand),
+                "Dirt" => ::core::result::Result::Ok(Self::Dirt),
+                "Grass" => ::core::result::Result::Ok(Self::Grass),
+                "Gravel" => ::core::result::Result::Ok(Self::Gravel),
+                "Water" => ::core::result::Result::Ok(Self::Water),
+                "Objects" => ::core::result::Result::Ok(Self::Objects),
+                "Water_0" => ::core::result::Result::Ok(Self::Water0),
+                "Water_1" => ::core::result::Result::Ok(Self::Water1),
+                "Water_2" => ::core::result::Result::Ok(Self::Water2),
+                "Water_3" => ::core::result::Result::Ok(Self::Water3),
+                "Water_4" => ::core::result::Result::Ok(Self::Water4),
+                _ => ::core::result::Result::Err(
+                    ::bevy_ldtk_asset::error::LdtkError::EnumValueNotFound {
+                        identifier: Self::IDENTIFIER,
+                        value: ::std::string::ToString::to_string(value),
+                    },
+                ),
+            }
+        }
+    }
+
+    /// The LDtk enum `self`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ::bevy_ldtk_asset::ldtk_enum::LdtkEnum)]
+    #[ldtk(rename = "self")]
+    pub enum Self_ {
+        #[ldtk(rename = "crate")]
+        Crate,
+        #[ldtk(rename = "super")]
+        Super,
+        #[ldtk(rename = "self")]
+        Self_,
+        #[ldtk(rename = "type")]
+        Type,
+    }
+
+    impl Self_ {
+        pub const IDENTIFIER: &'static str = "self";
+
+        pub const fn as_str(&self) -> &'static str {
+            match self {
+                Self::Crate => "crate",
+                Self::Super => "super",
+                Self::Self_ => "self",
+                Self::Type => "type",
+            }
+        }
+    }
+
+    impl ::core::str::FromStr for Self_ {
+        type Err = ::bevy_ldtk_asset::error::LdtkError;
+
+        fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+            match value {
+                "crate" => ::core::result::Result::Ok(Self::Crate),
+                "super" => ::core::result::Result::Ok(Self::Super),
+                "self" => ::core::result::Result::Ok(Self::Self_),
+                "type" => ::core::result::Result::Ok(Self::Type),
+                _ => ::core::result::Result::Err(
+                    ::bevy_ldtk_asset::error::LdtkError::EnumValueNotFound {
+                        identifier: Self::IDENTIFIER,
+                        value: ::std::string::ToString::to_string(value),
+                    },
+                ),
+            }
+        }
+    }
+
+    /// The LDtk enum `Empty`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ::bevy_ldtk_asset::ldtk_enum::LdtkEnum)]
+    #[ldtk(rename = "Empty")]
+    pub enum Empty {}
+
+    impl Empty {
+        pub const IDENTIFIER: &'static str = "Empty";
+    }
+
+    impl ::core::str::FromStr for Empty {
+        type Err = ::bevy_ldtk_asset::error::LdtkError;
+
+        fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+            ::core::result::Result::Err(
+                ::bevy_ldtk_asset::error::LdtkError::EnumValueNotFound {
+                    identifier: Self::IDENTIFIER,
+                    value: ::std::string::ToString::to_string(value),
+                },
+            )
+        }
+    }
+}
+
+/// Entities defined in the LDtk project.
+pub mod entities {
+    /// The LDtk entity `Door`.
+    #[derive(Clone, Debug, ::bevy_ldtk_asset::from_field_instance::LdtkFields)]
+    pub struct Door {
+        #[ldtk(rename = "Integer")]
+        pub integer: i64,
+        #[ldtk(rename = "Float")]
+        pub float: f64,
+        #[ldtk(rename = "Bool")]
+        pub bool: bool,
+        #[ldtk(rename = "String")]
+        pub string: ::std::string::String,
+        #[ldtk(rename = "Multilines")]
+        pub multilines: ::std::string::String,
+        #[ldtk(rename = "Color")]
+        pub color: ::bevy_ldtk_asset::__private::Color,
+        #[ldtk(rename = "Enum")]
+        pub r#enum: super::enums::TerrainType,
+        #[ldtk(rename = "FilePath")]
+        pub file_path: ::std::path::PathBuf,
+        #[ldtk(rename = "Tile")]
+        pub tile: ::bevy_ldtk_asset::tileset_rectangle::TilesetRectangle,
+        #[ldtk(rename = "EntityRef")]
+        pub entity_ref: ::bevy_ldtk_asset::field_instance::EntityRef,
+        #[ldtk(rename = "Point")]
+        pub point: ::bevy_ldtk_asset::__private::I64Vec2,
+        #[ldtk(rename = "ArrayInt")]
+        pub array_int: ::std::vec::Vec<i64>,
+        #[ldtk(rename = "NullableArrayInt")]
+        pub nullable_array_int: ::std::vec::Vec<::core::option::Option<i64>>,
+        #[ldtk(rename = "ArrayFloat")]
+        pub array_float: ::std::vec::Vec<f64>,
+        #[ldtk(rename = "ArrayBool")]
+        pub array_bool: ::std::vec::Vec<bool>,
+        #[ldtk(rename = "ArrayString")]
+        pub array_string: ::std::vec::Vec<::std::string::String>,
+        #[ldtk(rename = "ArrayMultilines")]
+        pub array_multilines: ::std::vec::Vec<::std::string::String>,
+        #[ldtk(rename = "ArrayColor")]
+        pub array_color: ::std::vec::Vec<::bevy_ldtk_asset::__private::Color>,
+        #[ldtk(rename = "ArrayEnum")]
+        pub array_enum: ::std::vec::Vec<super::enums::TerrainType>,
+        #[ldtk(rename = "ArrayFilePath")]
+        pub array_file_path: ::std::vec::Vec<::std::path::PathBuf>,
+        #[ldtk(rename = "ArrayTile")]
+        pub array_tile: ::std::vec::Vec<::bevy_ldtk_asset::tileset_rectangle::TilesetRectangle>,
+        #[ldtk(rename = "ArrayEntityRef")]
+        pub array_entity_ref: ::std::vec::Vec<::bevy_ldtk_asset::field_instance::EntityRef>,
+        #[ldtk(rename = "ArrayPoint")]
+        pub array_point: ::std::vec::Vec<::bevy_ldtk_asset::__private::I64Vec2>,
+        #[ldtk(rename = "NullInt")]
+        pub null_int: ::core::option::Option<i64>,
+        #[ldtk(rename = "NullEnum")]
+        pub null_enum: ::core::option::Option<super::enums::TerrainType>,
+        #[ldtk(rename = "DefaultInt")]
+        pub default_int: ::core::option::Option<i64>,
+        #[ldtk(rename = "Self")]
+        pub self_: i64,
+        #[ldtk(rename = "DefaultEnum")]
+        pub default_enum: ::core::option::Option<super::enums::TerrainType>,
+        #[ldtk(rename = "Config")]
+        pub config: ::std::string::String,
+        #[ldtk(rename = "Configs")]
+        pub configs: ::std::vec::Vec<::std::string::String>,
+    }
+
+    impl Door {
+        pub const IDENTIFIER: &'static str = "Door";
+    }
+}
@@ -0,0 +1,30 @@
+use syn::{Attribute, LitStr};
+
+/// The parsed contents of any `#[ldtk(...)]` attributes on a field or variant.
+#[derive(Default)]
+pub struct LdtkAttributes {
+    pub rename: Option<String>,
+}
+
+impl LdtkAttributes {
+    pub fn parse(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut ldtk_attributes = Self::default();
+
+        for attribute in attributes
+            .iter()
+            .filter(|attr| attr.path().is_ident("ldtk"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let rename: LitStr = meta.value()?.parse()?;
+                    ldtk_attributes.rename = Some(rename.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown ldtk attribute"))
+                }
+            })?;
+        }
+
+        Ok(ldtk_attributes)
+    }
+}
@@ -0,0 +1,116 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attributes::LdtkAttributes;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data_enum) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "LdtkEnum can only be derived for enums",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let identifier = LdtkAttributes::parse(&input.attrs)?
+        .rename
+        .unwrap_or_else(|| name.to_string());
+
+    let variants = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "LdtkEnum can only be derived for enums with unit variants",
+                ));
+            }
+
+            let value = LdtkAttributes::parse(&variant.attrs)?
+                .rename
+                .unwrap_or_else(|| variant.ident.to_string());
+
+            Ok((&variant.ident, value))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let values = variants.iter().map(|(_, value)| value);
+    let from_value_arms = variants
+        .iter()
+        .map(|(ident, value)| quote! { #value => ::core::option::Option::Some(Self::#ident) });
+    let to_value_arms = variants
+        .iter()
+        .map(|(ident, value)| quote! { Self::#ident => #value });
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_ldtk_asset::ldtk_enum::LdtkEnum
+            for #name #type_generics #where_clause
+        {
+            const IDENTIFIER: &'static str = #identifier;
+
+            const VARIANTS: &'static [&'static str] = &[#(#values),*];
+
+            fn from_ldtk_value(value: &str) -> ::core::option::Option<Self> {
+                match value {
+                    #(#from_value_arms,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn ldtk_value(&self) -> &'static str {
+                match *self {
+                    #(#to_value_arms,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::bevy_ldtk_asset::from_field_instance::FromFieldInstance
+            for #name #type_generics #where_clause
+        {
+            fn from_field_instance(
+                field_instance: &::bevy_ldtk_asset::field_instance::FieldInstance,
+            ) -> ::bevy_ldtk_asset::result::LdtkResult<Self> {
+                let enum_value = <::bevy_ldtk_asset::field_instance::EnumValue
+                    as ::bevy_ldtk_asset::from_field_instance::FromFieldInstance>::from_field_instance(
+                    field_instance,
+                )?;
+
+                <Self as ::bevy_ldtk_asset::ldtk_enum::LdtkEnum>::from_enum_value(&enum_value)
+            }
+
+            fn from_default_value(
+                default_value: &::bevy_ldtk_asset::field_definition::FieldDefaultValue,
+            ) -> ::core::option::Option<::bevy_ldtk_asset::result::LdtkResult<Self>> {
+                let ::bevy_ldtk_asset::field_definition::FieldDefaultValue::String(value) =
+                    default_value
+                else {
+                    return ::core::option::Option::None;
+                };
+
+                ::core::option::Option::Some(
+                    <Self as ::bevy_ldtk_asset::ldtk_enum::LdtkEnum>::from_ldtk_value(value)
+                        .ok_or_else(|| ::bevy_ldtk_asset::error::LdtkError::EnumValueNotFound {
+                            identifier: <Self as ::bevy_ldtk_asset::ldtk_enum::LdtkEnum>::IDENTIFIER,
+                            value: ::std::string::ToString::to_string(value),
+                        }),
+                )
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&::bevy_ldtk_asset::field_instance::EnumValue>
+            for #name #type_generics #where_clause
+        {
+            type Error = ::bevy_ldtk_asset::error::LdtkError;
+
+            fn try_from(
+                value: &::bevy_ldtk_asset::field_instance::EnumValue,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::bevy_ldtk_asset::ldtk_enum::LdtkEnum>::from_enum_value(value)
+            }
+        }
+    })
+}
@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attributes::LdtkAttributes;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "LdtkFields can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "LdtkFields can only be derived for structs with named fields",
+        ));
+    };
+
+    let field_initializers = fields
+        .named
+        .iter()
+        .map(|field| {
+            let attributes = LdtkAttributes::parse(&field.attrs)?;
+
+            let ident = field
+                .ident
+                .as_ref()
+                .ok_or_else(|| syn::Error::new_spanned(field, "expected a named field"))?;
+
+            let identifier = attributes.rename.unwrap_or_else(|| {
+                let ident = ident.to_string();
+                ident
+                    .strip_prefix("r#")
+                    .map(str::to_string)
+                    .unwrap_or(ident)
+            });
+
+            Ok(quote! {
+                #ident: ::bevy_ldtk_asset::from_field_instance::extract_field(
+                    #identifier,
+                    asset.get_field_instance(#identifier),
+                    asset.get_field_instance_definition(#identifier),
+                )?
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_ldtk_asset::from_field_instance::LdtkFields
+            for #name #type_generics #where_clause
+        {
+            fn from_field_instances(
+                asset: &impl ::bevy_ldtk_asset::ldtk_asset_trait::LdtkAssetWithFieldInstances,
+            ) -> ::bevy_ldtk_asset::result::LdtkResult<Self> {
+                ::core::result::Result::Ok(Self {
+                    #(#field_initializers,)*
+                })
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&::bevy_ldtk_asset::entity::EntityInstance>
+            for #name #type_generics #where_clause
+        {
+            type Error = ::bevy_ldtk_asset::error::LdtkError;
+
+            fn try_from(
+                value: &::bevy_ldtk_asset::entity::EntityInstance,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::bevy_ldtk_asset::from_field_instance::LdtkFields>::from_field_instances(
+                    value,
+                )
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&::bevy_ldtk_asset::level::Level>
+            for #name #type_generics #where_clause
+        {
+            type Error = ::bevy_ldtk_asset::error::LdtkError;
+
+            fn try_from(
+                value: &::bevy_ldtk_asset::level::Level,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::bevy_ldtk_asset::from_field_instance::LdtkFields>::from_field_instances(
+                    value,
+                )
+            }
+        }
+    })
+}
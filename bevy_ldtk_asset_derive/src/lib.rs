@@ -0,0 +1,39 @@
+//! Derive macros for [bevy_ldtk_asset](https://crates.io/crates/bevy_ldtk_asset).
+//!
+//! These are re-exported by `bevy_ldtk_asset` itself, and should not need to be depended on
+//! directly.
+
+mod attributes;
+mod ldtk_enum;
+mod ldtk_fields;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+/// Implements `LdtkFields`, along with `TryFrom<&EntityInstance>` and `TryFrom<&Level>`, for a
+/// struct with named fields.
+///
+/// Each struct field is read from the LDtk field instance with the same identifier, which can be
+/// changed with `#[ldtk(rename = "...")]`. The field's type must implement `FromFieldInstance`.
+#[proc_macro_derive(LdtkFields, attributes(ldtk))]
+pub fn derive_ldtk_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    ldtk_fields::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `LdtkEnum`, along with `FromFieldInstance` and `TryFrom<&EnumValue>`, for an enum
+/// with unit variants.
+///
+/// The LDtk enum identifier defaults to the name of the Rust enum, and each LDtk value defaults
+/// to the name of its variant. Both can be changed with `#[ldtk(rename = "...")]`.
+#[proc_macro_derive(LdtkEnum, attributes(ldtk))]
+pub fn derive_ldtk_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    ldtk_enum::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -22,3 +22,10 @@ pub fn bevy_anchor_from_ldtk(pivot: &[f64]) -> LdtkResult<Anchor> {
             ldtk_import_error!("Unable to parse pivot input to bevy Anchor! given: {pivot:?}",)
         })
 }
+
+// The inverse of [bevy_anchor_from_ldtk].
+pub fn ldtk_pivot_from_bevy_anchor(anchor: &Anchor) -> [f32; 2] {
+    let anchor = anchor.as_vec();
+
+    [anchor.x + 0.5, 0.5 - anchor.y]
+}
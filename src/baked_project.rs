@@ -0,0 +1,253 @@
+//! A compact binary form of LDtk projects, produced by the Bevy asset processor.
+//!
+//! Parsing a large LDtk project's JSON on every startup can be slow. When the app runs with
+//! [bevy_asset::AssetMode::Processed], the [ProjectBaker] processor converts each `.ldtk` file
+//! into a binary ([MessagePack](https://msgpack.org)) encoding of the same data, which the
+//! [crate::project_loader::ProjectLoader] reads back directly. Likewise, the [LevelBaker]
+//! converts each external `.ldtkl` level file, for the [crate::level_loader::LevelLoader]. The
+//! processors are registered by the plugin, but must be chosen for `.ldtk` and `.ldtkl` files,
+//! either in their `.meta` files or as the default:
+//!
+//! ```ignore
+//! app.add_plugins((
+//!     DefaultPlugins.set(AssetPlugin {
+//!         mode: AssetMode::Processed,
+//!         ..default()
+//!     }),
+//!     BevyLdtkAssetPlugin,
+//! ))
+//! .set_default_asset_processor::<ProjectBaker>("ldtk")
+//! .set_default_asset_processor::<LevelBaker>("ldtkl");
+//! ```
+//!
+//! Baking also migrates projects and levels saved by older versions of LDtk. External levels
+//! stay in their own files, so a baked project's levels keep the same asset paths and labels,
+//! such as `project/Level_0.ldtkl`, and are still hot reloaded one at a time. A `.ldtkl` file
+//! which was not baked is read as JSON, as usual.
+//!
+//! Images are not baked, and remain dependencies of the project.
+
+use bevy_asset::io::{Reader, Writer};
+use bevy_asset::processor::LoadTransformAndSave;
+use bevy_asset::saver::{AssetSaver, SavedAsset};
+use bevy_asset::transformer::IdentityAssetTransformer;
+use bevy_asset::{Asset, AssetLoader, LoadContext};
+use bevy_reflect::TypePath;
+use futures::AsyncWriteExt;
+use serde::Serialize;
+
+use crate::ldtk;
+use crate::ldtk::migration::{self, JsonVersion};
+use crate::ldtk_import_error;
+use crate::level_loader::{LevelLoader, LevelLoaderSettings, project_path_for_level};
+use crate::project_loader::{ProjectLoader, ProjectLoaderSettings};
+use crate::result::LdtkResult;
+
+// Identifies a baked project or level, followed by the version of the baked format.
+const MAGIC: &[u8; 8] = b"LDTKBAKE";
+const LEVEL_MAGIC: &[u8; 8] = b"LDTKLVLB";
+const FORMAT_VERSION: u32 = 1;
+
+/// The [AssetProcessor](bevy_asset::processor::AssetProcessor) which bakes `.ldtk` files.
+pub type ProjectBaker = LoadTransformAndSave<
+    ProjectJsonLoader,
+    IdentityAssetTransformer<ProjectJson>,
+    BakedProjectSaver,
+>;
+
+/// The [AssetProcessor](bevy_asset::processor::AssetProcessor) which bakes `.ldtkl` files.
+pub type LevelBaker =
+    LoadTransformAndSave<LevelJsonLoader, IdentityAssetTransformer<LevelJson>, BakedLevelSaver>;
+
+/// The parsed JSON of an LDtk project.
+///
+/// This is only used as an intermediate step by the [ProjectBaker].
+#[derive(Asset, TypePath)]
+pub struct ProjectJson {
+    pub(crate) project: ldtk::LdtkProject,
+}
+
+/// Loads a `.ldtk` file as a [ProjectJson], for the [ProjectBaker].
+///
+/// This loader is not associated with any extension, so it is only used by the processor.
+#[derive(Default, TypePath)]
+pub struct ProjectJsonLoader;
+
+impl AssetLoader for ProjectJsonLoader {
+    type Asset = ProjectJson;
+    type Settings = ();
+    type Error = crate::error::LdtkError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> LdtkResult<Self::Asset> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let project = migration::parse_project(&bytes)?;
+
+        Ok(ProjectJson { project })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+/// Saves a [ProjectJson] as a baked project, which the
+/// [crate::project_loader::ProjectLoader] can load.
+///
+/// The settings are passed along to the loader.
+#[derive(Default, TypePath)]
+pub struct BakedProjectSaver;
+
+impl AssetSaver for BakedProjectSaver {
+    type Asset = ProjectJson;
+    type Settings = ProjectLoaderSettings;
+    type OutputLoader = ProjectLoader;
+    type Error = crate::error::LdtkError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        settings: &Self::Settings,
+    ) -> LdtkResult<ProjectLoaderSettings> {
+        let bytes = bake(&asset.project)?;
+        writer.write_all(&bytes).await?;
+
+        Ok(settings.clone())
+    }
+}
+
+/// The parsed JSON of an LDtk external level.
+///
+/// This is only used as an intermediate step by the [LevelBaker].
+#[derive(Asset, TypePath)]
+pub struct LevelJson {
+    pub(crate) level: ldtk::Level,
+}
+
+/// Loads a `.ldtkl` file as a [LevelJson], for the [LevelBaker].
+///
+/// A level does not record which version of LDtk wrote it, so its project is read for that. Only
+/// [LevelLoaderSettings::project_path] is used.
+///
+/// This loader is not associated with any extension, so it is only used by the processor.
+#[derive(Default, TypePath)]
+pub struct LevelJsonLoader;
+
+impl AssetLoader for LevelJsonLoader {
+    type Asset = LevelJson;
+    type Settings = LevelLoaderSettings;
+    type Error = crate::error::LdtkError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> LdtkResult<Self::Asset> {
+        let project_path = match &settings.project_path {
+            Some(project_path) => project_path.clone(),
+            None => project_path_for_level(load_context.path())?,
+        };
+
+        // Reading the project makes it a dependency of the baked level, so the level is baked
+        // again whenever the project changes.
+        let project_bytes = load_context.read_asset_bytes(&project_path).await?;
+        let json_version: JsonVersion =
+            parse_project_bytes(&project_bytes)?.json_version.parse()?;
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level = parse_level_bytes(&bytes, json_version)?;
+
+        Ok(LevelJson { level })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+/// Saves a [LevelJson] as a baked level, which the [crate::level_loader::LevelLoader] can load.
+///
+/// The settings are passed along to the loader.
+#[derive(Default, TypePath)]
+pub struct BakedLevelSaver;
+
+impl AssetSaver for BakedLevelSaver {
+    type Asset = LevelJson;
+    type Settings = LevelLoaderSettings;
+    type OutputLoader = LevelLoader;
+    type Error = crate::error::LdtkError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        settings: &Self::Settings,
+    ) -> LdtkResult<LevelLoaderSettings> {
+        let bytes = bake_level(&asset.level)?;
+        writer.write_all(&bytes).await?;
+
+        Ok(settings.clone())
+    }
+}
+
+pub(crate) fn bake(project: &ldtk::LdtkProject) -> LdtkResult<Vec<u8>> {
+    bake_with_magic(MAGIC, project)
+}
+
+pub(crate) fn bake_level(level: &ldtk::Level) -> LdtkResult<Vec<u8>> {
+    bake_with_magic(LEVEL_MAGIC, level)
+}
+
+fn bake_with_magic(magic: &[u8; 8], value: &impl Serialize) -> LdtkResult<Vec<u8>> {
+    let mut bytes = magic.to_vec();
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    rmp_serde::encode::write(&mut bytes, value)?;
+
+    Ok(bytes)
+}
+
+/// Parses either a baked project, or an LDtk project's JSON.
+pub(crate) fn parse_project_bytes(bytes: &[u8]) -> LdtkResult<ldtk::LdtkProject> {
+    match strip_magic(MAGIC, bytes)? {
+        Some(baked) => Ok(rmp_serde::from_slice(baked)?),
+        None => migration::parse_project(bytes),
+    }
+}
+
+/// Parses either a baked level, or an LDtk external level's JSON written by the given version.
+pub(crate) fn parse_level_bytes(bytes: &[u8], version: JsonVersion) -> LdtkResult<ldtk::Level> {
+    match strip_magic(LEVEL_MAGIC, bytes)? {
+        Some(baked) => Ok(rmp_serde::from_slice(baked)?),
+        None => migration::parse_level(bytes, version),
+    }
+}
+
+// The encoded data following the magic and format version, or `None` if the bytes were not
+// baked.
+fn strip_magic<'a>(magic: &[u8; 8], bytes: &'a [u8]) -> LdtkResult<Option<&'a [u8]>> {
+    let Some(baked) = bytes.strip_prefix(magic) else {
+        return Ok(None);
+    };
+
+    let (format_version, baked) = baked
+        .split_first_chunk::<4>()
+        .ok_or_else(|| ldtk_import_error!("Truncated baked LDtk file!"))?;
+    let format_version = u32::from_le_bytes(*format_version);
+
+    if format_version != FORMAT_VERSION {
+        return Err(ldtk_import_error!(
+            "Bad baked LDtk file version! expected: {FORMAT_VERSION} given: {format_version}. \
+             The file needs to be processed again."
+        ));
+    }
+
+    Ok(Some(baked))
+}
@@ -0,0 +1,491 @@
+//! Generation of Rust source code from an LDtk project, intended for use from a `build.rs`.
+//!
+//! The generated source contains:
+//! * One module each for the world, level and layer identifiers in the project, holding a string
+//!   constant per identifier.
+//! * An `enums` module, with one Rust enum per LDtk enum definition. Each enum derives
+//!   [crate::ldtk_enum::LdtkEnum], and also has an `IDENTIFIER` constant, an `as_str` method and
+//!   a [std::str::FromStr] implementation.
+//! * An `entities` module, with one struct per LDtk entity definition. Each struct derives
+//!   [crate::from_field_instance::LdtkFields], with a field for each of the entity's field
+//!   definitions.
+//!
+//! A typical `build.rs` would look like:
+//!
+//! ```ignore
+//! fn main() {
+//!     println!("cargo::rerun-if-changed=assets/project.ldtk");
+//!
+//!     let source = bevy_ldtk_asset::codegen::generate_from_path("assets/project.ldtk").unwrap();
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     std::fs::write(std::path::Path::new(&out_dir).join("project.rs"), source).unwrap();
+//! }
+//! ```
+//!
+//! and then the generated source can be included in the crate with
+//! `include!(concat!(env!("OUT_DIR"), "/project.rs"));`.
+//!
+//! LDtk identifiers are converted into Rust's naming conventions, so different identifiers can
+//! generate the same name, such as `my_enum` and `MyEnum`. Rather than generating source which
+//! doesn't compile, this is reported as an [LdtkError::CodegenNameCollision] naming both
+//! identifiers.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use bevy_platform::collections::HashMap;
+
+use crate::error::LdtkError;
+use crate::ldtk;
+use crate::result::LdtkResult;
+
+/// Reads an LDtk project from the given path, and generates Rust source from it.
+pub fn generate_from_path(path: impl AsRef<Path>) -> LdtkResult<String> {
+    let bytes = std::fs::read(path)?;
+
+    generate_from_bytes(&bytes)
+}
+
+/// Parses an LDtk project from the given bytes, and generates Rust source from it.
+pub fn generate_from_bytes(bytes: &[u8]) -> LdtkResult<String> {
+    let project_json = ldtk::migration::parse_project(bytes)?;
+
+    generate(&project_json)
+}
+
+pub(crate) fn generate(project_json: &ldtk::LdtkProject) -> LdtkResult<String> {
+    let mut source = String::new();
+
+    writeln!(
+        source,
+        "// @generated by bevy_ldtk_asset::codegen. Do not edit by hand!\n"
+    )?;
+
+    let (world_identifiers, level_identifiers): (Vec<_>, Vec<_>) = if project_json.worlds.is_empty()
+    {
+        (
+            vec!["World"],
+            project_json
+                .levels
+                .iter()
+                .map(|level| level.identifier.as_str())
+                .collect(),
+        )
+    } else {
+        (
+            project_json
+                .worlds
+                .iter()
+                .map(|world| world.identifier.as_str())
+                .collect(),
+            project_json
+                .worlds
+                .iter()
+                .flat_map(|world| world.levels.iter())
+                .map(|level| level.identifier.as_str())
+                .collect(),
+        )
+    };
+
+    let layer_identifiers = project_json
+        .defs
+        .layers
+        .iter()
+        .map(|layer| layer.identifier.as_str());
+
+    write_identifier_module(&mut source, "worlds", "World", world_identifiers)?;
+    write_identifier_module(&mut source, "levels", "Level", level_identifiers)?;
+    write_identifier_module(&mut source, "layers", "Layer", layer_identifiers)?;
+
+    let enum_definitions = || {
+        project_json
+            .defs
+            .enums
+            .iter()
+            .chain(project_json.defs.external_enums.iter())
+    };
+    check_unique_names(enum_definitions().map(|enum_definition| {
+        (
+            pascal_case(&enum_definition.identifier),
+            enum_definition.identifier.as_str(),
+        )
+    }))?;
+    check_unique_names(project_json.defs.entities.iter().map(|entity_definition| {
+        (
+            pascal_case(&entity_definition.identifier),
+            entity_definition.identifier.as_str(),
+        )
+    }))?;
+
+    writeln!(source, "/// Enums defined in the LDtk project.")?;
+    writeln!(source, "pub mod enums {{")?;
+    enum_definitions()
+        .enumerate()
+        .try_for_each(|(index, enum_definition)| {
+            if index > 0 {
+                writeln!(source)?;
+            }
+            write_enum(&mut source, enum_definition)
+        })?;
+    writeln!(source, "}}\n")?;
+
+    writeln!(source, "/// Entities defined in the LDtk project.")?;
+    writeln!(source, "pub mod entities {{")?;
+    project_json
+        .defs
+        .entities
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, entity_definition)| {
+            if index > 0 {
+                writeln!(source)?;
+            }
+            write_fields_struct(
+                &mut source,
+                &pascal_case(&entity_definition.identifier),
+                &entity_definition.identifier,
+                &entity_definition.field_defs,
+            )
+        })?;
+    writeln!(source, "}}")?;
+
+    Ok(source)
+}
+
+fn write_identifier_module<'a>(
+    source: &mut String,
+    module: &str,
+    kind: &str,
+    identifiers: impl IntoIterator<Item = &'a str>,
+) -> LdtkResult<()> {
+    writeln!(source, "/// {kind} identifiers in the LDtk project.")?;
+    writeln!(source, "pub mod {module} {{")?;
+
+    let mut identifiers: Vec<_> = identifiers.into_iter().collect();
+    identifiers.sort_unstable();
+    // The same identifier can appear more than once, such as levels of different worlds.
+    identifiers.dedup();
+    check_unique_names(
+        identifiers
+            .iter()
+            .map(|identifier| (screaming_snake_case(identifier), *identifier)),
+    )?;
+
+    identifiers.into_iter().try_for_each(|identifier| {
+        writeln!(
+            source,
+            "    pub const {}: &str = {identifier:?};",
+            screaming_snake_case(identifier)
+        )
+    })?;
+
+    writeln!(source, "}}\n")?;
+
+    Ok(())
+}
+
+fn write_enum(source: &mut String, enum_definition: &ldtk::EnumDefinition) -> LdtkResult<()> {
+    let name = pascal_case(&enum_definition.identifier);
+    let identifier = &enum_definition.identifier;
+    let variants: Vec<_> = enum_definition
+        .values
+        .iter()
+        .map(|value| (pascal_case(&value.id), value.id.as_str()))
+        .collect();
+    check_unique_names(variants.iter().map(|(variant, id)| (variant.clone(), *id)))?;
+
+    writeln!(source, "    /// The LDtk enum `{identifier}`.")?;
+    writeln!(
+        source,
+        "    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ::bevy_ldtk_asset::ldtk_enum::LdtkEnum)]"
+    )?;
+    writeln!(source, "    #[ldtk(rename = {identifier:?})]")?;
+    if variants.is_empty() {
+        writeln!(source, "    pub enum {name} {{}}\n")?;
+    } else {
+        writeln!(source, "    pub enum {name} {{")?;
+        variants.iter().try_for_each(|(variant, id)| {
+            writeln!(source, "        #[ldtk(rename = {id:?})]")?;
+            writeln!(source, "        {variant},")
+        })?;
+        writeln!(source, "    }}\n")?;
+    }
+
+    writeln!(source, "    impl {name} {{")?;
+    writeln!(
+        source,
+        "        pub const IDENTIFIER: &'static str = {identifier:?};"
+    )?;
+    // An empty enum has no values to name, and can't be matched on by reference.
+    if !variants.is_empty() {
+        writeln!(source)?;
+        writeln!(
+            source,
+            "        pub const fn as_str(&self) -> &'static str {{"
+        )?;
+        writeln!(source, "            match self {{")?;
+        variants.iter().try_for_each(|(variant, id)| {
+            writeln!(source, "                Self::{variant} => {id:?},")
+        })?;
+        writeln!(source, "            }}")?;
+        writeln!(source, "        }}")?;
+    }
+    writeln!(source, "    }}\n")?;
+
+    writeln!(source, "    impl ::core::str::FromStr for {name} {{")?;
+    writeln!(
+        source,
+        "        type Err = ::bevy_ldtk_asset::error::LdtkError;\n"
+    )?;
+    writeln!(
+        source,
+        "        fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {{"
+    )?;
+    let not_found = |indent: &str| {
+        format!(
+            "{indent}::core::result::Result::Err(\n\
+             {indent}    ::bevy_ldtk_asset::error::LdtkError::EnumValueNotFound {{\n\
+             {indent}        identifier: Self::IDENTIFIER,\n\
+             {indent}        value: ::std::string::ToString::to_string(value),\n\
+             {indent}    }},\n\
+             {indent})"
+        )
+    };
+    if variants.is_empty() {
+        writeln!(source, "{}", not_found("            "))?;
+    } else {
+        writeln!(source, "            match value {{")?;
+        variants.iter().try_for_each(|(variant, id)| {
+            writeln!(
+                source,
+                "                {id:?} => ::core::result::Result::Ok(Self::{variant}),"
+            )
+        })?;
+        writeln!(
+            source,
+            "                _ => {},",
+            not_found("                ").trim_start()
+        )?;
+        writeln!(source, "            }}")?;
+    }
+    writeln!(source, "        }}")?;
+    writeln!(source, "    }}")?;
+
+    Ok(())
+}
+
+fn write_fields_struct(
+    source: &mut String,
+    name: &str,
+    identifier: &str,
+    field_definitions: &[ldtk::FieldDefinition],
+) -> LdtkResult<()> {
+    check_unique_names(
+        field_definitions
+            .iter()
+            .filter(|field_definition| field_type_to_rust(field_definition).is_some())
+            .map(|field_definition| {
+                (
+                    snake_case(&field_definition.identifier),
+                    field_definition.identifier.as_str(),
+                )
+            }),
+    )?;
+
+    writeln!(source, "    /// The LDtk entity `{identifier}`.")?;
+    writeln!(
+        source,
+        "    #[derive(Clone, Debug, ::bevy_ldtk_asset::from_field_instance::LdtkFields)]"
+    )?;
+    writeln!(source, "    pub struct {name} {{")?;
+    field_definitions.iter().try_for_each(|field_definition| {
+        let field_identifier = &field_definition.identifier;
+        let field_type = &field_definition.field_definition_type;
+        match field_type_to_rust(field_definition) {
+            Some(rust_type) => {
+                writeln!(source, "        #[ldtk(rename = {field_identifier:?})]")?;
+                writeln!(
+                    source,
+                    "        pub {}: {rust_type},",
+                    snake_case(field_identifier)
+                )
+            }
+            None => writeln!(
+                source,
+                "        // Field {field_identifier:?} of type {field_type:?} is not supported."
+            ),
+        }
+    })?;
+    writeln!(source, "    }}\n")?;
+
+    writeln!(source, "    impl {name} {{")?;
+    writeln!(
+        source,
+        "        pub const IDENTIFIER: &'static str = {identifier:?};"
+    )?;
+    writeln!(source, "    }}")?;
+
+    Ok(())
+}
+
+// Checks that no two LDtk identifiers generate the same Rust name, given as (name, identifier)
+// pairs, since the generated source wouldn't compile.
+fn check_unique_names<'a>(names: impl IntoIterator<Item = (String, &'a str)>) -> LdtkResult<()> {
+    let mut seen = HashMap::<String, &str>::default();
+
+    names.into_iter().try_for_each(|(name, identifier)| {
+        seen.insert(name.clone(), identifier)
+            .map_or(Ok(()), |first| {
+                Err(LdtkError::CodegenNameCollision {
+                    name,
+                    first: first.to_string(),
+                    second: identifier.to_string(),
+                })
+            })
+    })
+}
+
+fn field_type_to_rust(field_definition: &ldtk::FieldDefinition) -> Option<String> {
+    let field_type = field_definition.field_definition_type.as_str();
+
+    if let Some(element_type) = field_type
+        .strip_prefix("Array<")
+        .and_then(|rest| rest.strip_suffix('>'))
+    {
+        let element_type = rust_type(element_type)?;
+
+        // For arrays, `can_be_null` means the elements can be null, rather than the array.
+        return if field_definition.can_be_null {
+            Some(format!(
+                "::std::vec::Vec<::core::option::Option<{element_type}>>"
+            ))
+        } else {
+            Some(format!("::std::vec::Vec<{element_type}>"))
+        };
+    }
+
+    let rust_type = rust_type(field_type)?;
+
+    if field_definition.can_be_null {
+        Some(format!("::core::option::Option<{rust_type}>"))
+    } else {
+        Some(rust_type)
+    }
+}
+
+fn rust_type(field_type: &str) -> Option<String> {
+    if let Some(enum_identifier) = field_type
+        .strip_prefix("LocalEnum.")
+        .or_else(|| field_type.strip_prefix("ExternEnum."))
+    {
+        return Some(format!("super::enums::{}", pascal_case(enum_identifier)));
+    }
+
+    let rust_type = match field_type {
+        "Int" => "i64",
+        "Float" => "f64",
+        "Bool" => "bool",
+        "String" | "Multilines" => "::std::string::String",
+        "Color" => "::bevy_ldtk_asset::__private::Color",
+        "Point" => "::bevy_ldtk_asset::__private::I64Vec2",
+        "FilePath" => "::std::path::PathBuf",
+        "Tile" => "::bevy_ldtk_asset::tileset_rectangle::TilesetRectangle",
+        "EntityRef" => "::bevy_ldtk_asset::field_instance::EntityRef",
+        _ => return None,
+    };
+
+    Some(rust_type.to_string())
+}
+
+// Splits an LDtk identifier into words, on underscores and on lower to upper case transitions.
+fn words(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+
+    identifier.chars().for_each(|c| {
+        if c == '_' || c == '-' || c == ' ' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lowercase = false;
+            return;
+        }
+
+        if c.is_uppercase() && previous_lowercase && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    });
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn make_ident(ident: String) -> String {
+    // These can't be raw identifiers, so are given a suffix instead.
+    const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+        "priv", "try", "typeof", "unsized", "virtual", "yield",
+    ];
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else if PATH_KEYWORDS.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+fn pascal_case(identifier: &str) -> String {
+    make_ident(
+        words(identifier)
+            .into_iter()
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| {
+                        first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                    })
+                    .into_iter()
+                    .flatten()
+                    .collect::<String>()
+            })
+            .collect(),
+    )
+}
+
+fn snake_case(identifier: &str) -> String {
+    make_ident(
+        words(identifier)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+    )
+}
+
+fn screaming_snake_case(identifier: &str) -> String {
+    make_ident(
+        words(identifier)
+            .into_iter()
+            .map(|word| word.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+    )
+}
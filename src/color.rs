@@ -1,4 +1,4 @@
-use bevy_color::Color;
+use bevy_color::{Color, ColorToPacked};
 
 use crate::ldtk_import_error;
 use crate::result::LdtkResult;
@@ -40,3 +40,17 @@ pub const fn bevy_color_from_ldtk_int(color: i64) -> Color {
 
     Color::srgb_u8(r, g, b)
 }
+
+// The inverse of [bevy_color_from_ldtk_string], giving "#RRGGBB".
+pub fn ldtk_string_from_bevy_color(color: Color) -> String {
+    let [r, g, b] = color.to_srgba().to_u8_array_no_alpha();
+
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+// The inverse of [bevy_color_from_ldtk_int].
+pub fn ldtk_int_from_bevy_color(color: Color) -> i64 {
+    let [r, g, b] = color.to_srgba().to_u8_array_no_alpha();
+
+    (i64::from(r) << 16) | (i64::from(g) << 8) | i64::from(b)
+}
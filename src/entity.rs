@@ -70,23 +70,27 @@ pub struct EntityInstance {
     pub size: I64Vec2,
     /// The entity's location in the space defined by its containing [crate::layer::LayerInstance].
     pub location: I64Vec2,
+    /// Index from 0 to (number of entities - 1), in the order LDtk lists the entities of the
+    /// containing [crate::layer::LayerInstance].
+    pub index: usize,
 }
 
 impl EntityInstance {
     pub(crate) async fn new(
         entity_instance_json: ldtk::EntityInstance,
+        index: usize,
         project_context: Arc<RwLock<ProjectContext<'_>>>,
     ) -> LdtkResult<Self> {
+        let grid = Self::parse_grid(&entity_instance_json)?;
+
+        let location = Self::parse_location(&entity_instance_json)?;
+
+        let world_location = Self::parse_world_location(&entity_instance_json)?;
+
         let identifier = entity_instance_json.identifier;
 
         let iid = Iid::from_str(&entity_instance_json.iid)?;
 
-        let grid = (entity_instance_json.grid.len() == 2)
-            .then(|| (entity_instance_json.grid[0], entity_instance_json.grid[1]).into())
-            .ok_or_else(|| {
-                ldtk_import_error!("Bad value for grid! given: {:?}", entity_instance_json.grid)
-            })?;
-
         let anchor = bevy_anchor_from_ldtk(&entity_instance_json.pivot)?;
 
         let smart_color = bevy_color_from_ldtk_string(&entity_instance_json.smart_color)?;
@@ -98,17 +102,6 @@ impl EntityInstance {
             .map(|value| TilesetRectangle::new(value, &project_context.read()?.tileset_definitions))
             .transpose()?;
 
-        let world_location = match (entity_instance_json.world_x, entity_instance_json.world_y) {
-            (None, None) => Ok(None),
-            (None, Some(y)) => Err(ldtk_import_error!(
-                "When constructing an entity, world_x was None but world_y was Some({y})!",
-            )),
-            (Some(x), None) => Err(ldtk_import_error!(
-                "When constructing an entity, world_x was Some({x}) but world_y was None!",
-            )),
-            (Some(x), Some(y)) => Ok(Some((x, y).into())),
-        }?;
-
         let entity_definition = project_context
             .read()?
             .entity_definitions
@@ -124,7 +117,6 @@ impl EntityInstance {
         let field_instances_iter = entity_instance_json
             .field_instances
             .into_iter()
-            .filter(|value| value.value.is_some())
             .map(|value| {
                 let project_context = project_context.clone();
                 async {
@@ -142,15 +134,6 @@ impl EntityInstance {
 
         let size = (entity_instance_json.width, entity_instance_json.height).into();
 
-        let location = (entity_instance_json.px.len() == 2)
-            .then(|| (entity_instance_json.px[0], entity_instance_json.px[1]).into())
-            .ok_or_else(|| {
-                ldtk_import_error!(
-                    "Unable to parse I64Vec2 from entity px field! given: {:?}",
-                    entity_instance_json.grid
-                )
-            })?;
-
         Ok(Self {
             identifier,
             iid,
@@ -164,8 +147,47 @@ impl EntityInstance {
             field_instances,
             size,
             location,
+            index,
         })
     }
+
+    // The parse_* functions are shared with [crate::ldtk_data], so that both check the JSON in
+    // the same way.
+    pub(crate) fn parse_grid(entity_instance_json: &ldtk::EntityInstance) -> LdtkResult<I64Vec2> {
+        (entity_instance_json.grid.len() == 2)
+            .then(|| (entity_instance_json.grid[0], entity_instance_json.grid[1]).into())
+            .ok_or_else(|| {
+                ldtk_import_error!("Bad value for grid! given: {:?}", entity_instance_json.grid)
+            })
+    }
+
+    pub(crate) fn parse_location(
+        entity_instance_json: &ldtk::EntityInstance,
+    ) -> LdtkResult<I64Vec2> {
+        (entity_instance_json.px.len() == 2)
+            .then(|| (entity_instance_json.px[0], entity_instance_json.px[1]).into())
+            .ok_or_else(|| {
+                ldtk_import_error!(
+                    "Unable to parse I64Vec2 from entity px field! given: {:?}",
+                    entity_instance_json.px
+                )
+            })
+    }
+
+    pub(crate) fn parse_world_location(
+        entity_instance_json: &ldtk::EntityInstance,
+    ) -> LdtkResult<Option<I64Vec2>> {
+        match (entity_instance_json.world_x, entity_instance_json.world_y) {
+            (None, None) => Ok(None),
+            (None, Some(y)) => Err(ldtk_import_error!(
+                "When constructing an entity, world_x was None but world_y was Some({y})!",
+            )),
+            (Some(x), None) => Err(ldtk_import_error!(
+                "When constructing an entity, world_x was Some({x}) but world_y was None!",
+            )),
+            (Some(x), Some(y)) => Ok(Some((x, y).into())),
+        }
+    }
 }
 
 impl LdtkAsset for EntityInstance {
@@ -13,10 +13,12 @@ use bevy_sprite::Anchor;
 
 use crate::anchor::bevy_anchor_from_ldtk;
 use crate::color::bevy_color_from_ldtk_string;
+use crate::field_definition::FieldDefinition;
+use crate::ldtk_asset_trait::LdtkAssetWithFieldDefinitions;
 use crate::result::LdtkResult;
 use crate::tileset_definition::TilesetDefinition;
 use crate::tileset_rectangle::TilesetRectangle;
-use crate::uid::UidMap;
+use crate::uid::{Uid, UidMap};
 use crate::{ldtk, ldtk_import_error};
 
 /// A nine-slice pattern.
@@ -129,6 +131,8 @@ pub struct EntityDefinition {
     ///
     /// Unlike [crate::entity::EntityInstance] instances, the identifier for the definition is unique.
     pub identifier: String,
+    /// The unique identifier of this definition, referenced by entity instances.
+    pub uid: Uid,
     /// Base color for the entity.
     pub color: Color,
     /// Size of the region for this entity.
@@ -145,6 +149,9 @@ pub struct EntityDefinition {
     pub ui_tile: Option<TilesetRectangle>,
     /// The render mode for an [crate::entity::EntityInstance] visualization.
     pub render_mode: TileRenderMode,
+    /// The definitions of the fields of an [crate::entity::EntityInstance], in the order they
+    /// are declared in LDtk.
+    pub field_definitions: Vec<FieldDefinition>,
 }
 
 impl EntityDefinition {
@@ -154,6 +161,8 @@ impl EntityDefinition {
     ) -> LdtkResult<Self> {
         let identifier = value.identifier;
 
+        let uid = value.uid;
+
         let color = bevy_color_from_ldtk_string(&value.color)?;
 
         let size = (value.width, value.height).into();
@@ -173,16 +182,30 @@ impl EntityDefinition {
         let render_mode =
             TileRenderMode::new(&value.tile_render_mode, value.nine_slice_borders.as_slice())?;
 
+        let field_definitions = value
+            .field_defs
+            .into_iter()
+            .map(FieldDefinition::new)
+            .collect::<LdtkResult<_>>()?;
+
         let asset = Self {
             identifier,
+            uid,
             color,
             size,
             anchor,
             tile,
             ui_tile,
             render_mode,
+            field_definitions,
         };
 
         Ok(asset)
     }
 }
+
+impl LdtkAssetWithFieldDefinitions for EntityDefinition {
+    fn get_field_definitions(&self) -> &[FieldDefinition] {
+        &self.field_definitions
+    }
+}
@@ -0,0 +1,141 @@
+//! A registry of user supplied constructors for spawned LDtk entities.
+//!
+//! When the [crate::spawn] subsystem spawns a Bevy entity for an [EntityInstance], every
+//! constructor registered for that instance's identifier, and then every constructor registered
+//! for each of its tags, is called. The bundles they return are inserted onto the spawned entity.
+
+use bevy_app::App;
+use bevy_asset::Handle;
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::resource::Resource;
+use bevy_ecs::system::EntityCommands;
+use bevy_platform::collections::HashMap;
+
+use crate::entity::EntityInstance;
+use crate::layer::LayerInstance;
+
+/// Extra information handed to a constructor alongside the [EntityInstance].
+#[derive(Debug)]
+pub struct LdtkEntityContext<'a> {
+    /// The Bevy entity which was spawned for this [EntityInstance].
+    pub entity: Entity,
+    /// The handle of the [EntityInstance] being spawned.
+    pub handle: &'a Handle<EntityInstance>,
+    /// The [LayerInstance] which contains the [EntityInstance].
+    pub layer: &'a LayerInstance,
+}
+
+type LdtkEntityConstructor =
+    Box<dyn Fn(&EntityInstance, &LdtkEntityContext, &mut EntityCommands) + Send + Sync>;
+
+/// Constructors to be called when an LDtk entity is spawned, indexed either by the identifier or
+/// by a tag of the [EntityInstance].
+#[derive(Default, Resource)]
+pub struct LdtkEntityRegistry {
+    by_identifier: HashMap<String, Vec<LdtkEntityConstructor>>,
+    by_tag: HashMap<String, Vec<LdtkEntityConstructor>>,
+}
+
+impl LdtkEntityRegistry {
+    /// Registers a constructor to be called for every [EntityInstance] with the given identifier.
+    pub fn register_identifier<B: Bundle>(
+        &mut self,
+        identifier: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) {
+        self.by_identifier
+            .entry(identifier.into())
+            .or_default()
+            .push(Self::erase(constructor));
+    }
+
+    /// Registers a constructor to be called for every [EntityInstance] with the given tag.
+    pub fn register_tag<B: Bundle>(
+        &mut self,
+        tag: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) {
+        self.by_tag
+            .entry(tag.into())
+            .or_default()
+            .push(Self::erase(constructor));
+    }
+
+    /// Returns `true` if no constructors have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.by_identifier.is_empty() && self.by_tag.is_empty()
+    }
+
+    pub(crate) fn construct(
+        &self,
+        entity_instance: &EntityInstance,
+        context: &LdtkEntityContext,
+        entity_commands: &mut EntityCommands,
+    ) {
+        let by_identifier = self
+            .by_identifier
+            .get(&entity_instance.identifier)
+            .into_iter()
+            .flatten();
+
+        let by_tag = entity_instance
+            .tags
+            .iter()
+            .filter_map(|tag| self.by_tag.get(tag))
+            .flatten();
+
+        by_identifier
+            .chain(by_tag)
+            .for_each(|constructor| constructor(entity_instance, context, entity_commands));
+    }
+
+    fn erase<B: Bundle>(
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) -> LdtkEntityConstructor {
+        Box::new(move |entity_instance, context, entity_commands| {
+            entity_commands.insert(constructor(entity_instance, context));
+        })
+    }
+}
+
+/// Extension trait for registering LDtk entity constructors on an [App].
+pub trait LdtkEntityAppExt {
+    /// See [LdtkEntityRegistry::register_identifier].
+    fn register_ldtk_entity<B: Bundle>(
+        &mut self,
+        identifier: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// See [LdtkEntityRegistry::register_tag].
+    fn register_ldtk_entity_tag<B: Bundle>(
+        &mut self,
+        tag: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl LdtkEntityAppExt for App {
+    fn register_ldtk_entity<B: Bundle>(
+        &mut self,
+        identifier: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<LdtkEntityRegistry>()
+            .register_identifier(identifier, constructor);
+        self
+    }
+
+    fn register_ldtk_entity_tag<B: Bundle>(
+        &mut self,
+        tag: impl Into<String>,
+        constructor: impl Fn(&EntityInstance, &LdtkEntityContext) -> B + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<LdtkEntityRegistry>()
+            .register_tag(tag, constructor);
+        self
+    }
+}
@@ -13,12 +13,27 @@ pub enum LdtkError {
     #[error(transparent)]
     ParseIntError(#[from] std::num::ParseIntError),
 
+    #[error(transparent)]
+    TryFromIntError(#[from] std::num::TryFromIntError),
+
     #[error(transparent)]
     UuidError(#[from] uuid::Error),
 
     #[error(transparent)]
     ReadAssetBytesError(#[from] bevy_asset::ReadAssetBytesError),
 
+    #[error(transparent)]
+    FmtError(#[from] std::fmt::Error),
+
+    #[error(transparent)]
+    RmpEncodeError(#[from] rmp_serde::encode::Error),
+
+    #[error(transparent)]
+    RmpDecodeError(#[from] rmp_serde::decode::Error),
+
+    #[error(transparent)]
+    TextureAccessError(#[from] bevy_image::TextureAccessError),
+
     // TODO: can this be improved?
     #[error("poison error!")]
     PoisonError,
@@ -26,8 +41,68 @@ pub enum LdtkError {
     #[error("Failure importing ldtk file! {0}")]
     LdtkImportError(String),
 
+    #[error("Failure exporting ldtk file! {0}")]
+    LdtkExportError(String),
+
     #[error("Duplicate Iid error! {0}")]
     DuplicateIidError(crate::iid::Iid),
+
+    #[error("EntityRef to an entity which does not exist! entity iid: {0}")]
+    DanglingEntityRef(crate::iid::Iid),
+
+    #[error("Layer is not a tiles layer! identifier: {0}")]
+    NotATilesLayer(String),
+
+    #[error(
+        "Tile chunks need a positive size! chunk size: {chunk_size} grid cell size: {grid_cell_size}"
+    )]
+    BadTileChunkLayout {
+        chunk_size: bevy_math::I64Vec2,
+        grid_cell_size: i64,
+    },
+
+    #[error("Asset is not loaded! {0}")]
+    AssetNotLoaded(String),
+
+    #[error("Field instance not found! identifier: {0}")]
+    FieldInstanceNotFound(String),
+
+    #[error("Bad field instance type! expected: {expected} given: {given}")]
+    FieldInstanceTypeError {
+        expected: &'static str,
+        given: &'static str,
+    },
+
+    #[error("Enum value belongs to the wrong enum! expected: {expected} given: {given}")]
+    EnumIdentifierMismatch {
+        expected: &'static str,
+        given: String,
+    },
+
+    #[error("Enum value not found in {identifier}! given: {value}")]
+    EnumValueNotFound {
+        identifier: &'static str,
+        value: String,
+    },
+
+    #[error("Enum variants missing from the enum definition {identifier}! missing: {missing:?}")]
+    EnumVariantsMissing {
+        identifier: String,
+        missing: Vec<&'static str>,
+    },
+
+    #[error("Generated name collision! {first} and {second} both generate the name {name}")]
+    CodegenNameCollision {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Could not extract field instance {identifier}! {source}")]
+    FieldInstanceExtractionError {
+        identifier: String,
+        source: Box<Self>,
+    },
 }
 
 impl<T> From<PoisonError<T>> for LdtkError {
@@ -42,3 +117,10 @@ macro_rules! ldtk_import_error {
         $crate::error::LdtkError::LdtkImportError(format!($($args)*))
     };
 }
+
+#[macro_export]
+macro_rules! ldtk_export_error {
+    ($($args:tt)*) => {
+        $crate::error::LdtkError::LdtkExportError(format!($($args)*))
+    };
+}
@@ -0,0 +1,180 @@
+//! The LDtk definition for a field.
+//!
+//! Field definitions describe the declared schema of the [crate::field_instance::FieldInstance]s
+//! found on entities and levels, including their type, whether they can be null, their default
+//! value and any constraints placed on them in the editor.
+//!
+//! This is an import of an LDtk
+//! [FieldDefinition](https://ldtk.io/json/#ldtk-FieldDefJson).
+
+use std::sync::Arc;
+
+use bevy_reflect::Reflect;
+
+use crate::result::LdtkResult;
+use crate::uid::{Uid, UidMap};
+use crate::{ldtk, ldtk_import_error};
+
+/// Which entities a field of type `EntityRef` is allowed to reference.
+///
+/// See [allowedRefs](https://ldtk.io/json/#ldtk-FieldDefJson;allowedRefs)
+/// from the LDtk documentation.
+#[derive(Clone, Debug, Reflect)]
+pub enum AllowedRefs {
+    /// Any entity can be referenced.
+    Any,
+    /// Only entities sharing the same definition as the referencing entity.
+    OnlySame,
+    /// Only entities with the given definition uid.
+    OnlySpecificEntity(Uid),
+    /// Only entities with at least one of the given tags.
+    OnlyTags(Vec<String>),
+}
+
+impl AllowedRefs {
+    pub(crate) fn new(value: &ldtk::FieldDefinition) -> LdtkResult<Self> {
+        match value.allowed_refs {
+            ldtk::AllowedRefs::Any => Ok(Self::Any),
+            ldtk::AllowedRefs::OnlySame => Ok(Self::OnlySame),
+            ldtk::AllowedRefs::OnlySpecificEntity => value
+                .allowed_refs_entity_uid
+                .map(Self::OnlySpecificEntity)
+                .ok_or_else(|| {
+                    ldtk_import_error!(
+                        "OnlySpecificEntity allowed refs without an entity uid! field: {}",
+                        value.identifier
+                    )
+                }),
+            ldtk::AllowedRefs::OnlyTags => Ok(Self::OnlyTags(value.allowed_ref_tags.clone())),
+        }
+    }
+}
+
+/// The default value of a field, used when a field instance is null or invalid.
+///
+/// See [defaultOverride](https://ldtk.io/json/#ldtk-FieldDefJson;defaultOverride)
+/// from the LDtk documentation.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Reflect)]
+pub enum FieldDefaultValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl FieldDefaultValue {
+    pub(crate) fn new(value: &serde_json::Value) -> LdtkResult<Self> {
+        let id = value
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| ldtk_import_error!("Default override without an id! {value}"))?;
+
+        let param = value
+            .get("params")
+            .and_then(serde_json::Value::as_array)
+            .and_then(|params| params.first())
+            .ok_or_else(|| ldtk_import_error!("Default override without params! {value}"))?;
+
+        let bad_param = || ldtk_import_error!("Bad param for default override {id}! {param}");
+
+        match id {
+            "V_Int" => Ok(Self::Int(param.as_i64().ok_or_else(bad_param)?)),
+            "V_Float" => Ok(Self::Float(param.as_f64().ok_or_else(bad_param)?)),
+            "V_Bool" => Ok(Self::Bool(param.as_bool().ok_or_else(bad_param)?)),
+            "V_String" => Ok(Self::String(
+                param.as_str().ok_or_else(bad_param)?.to_string(),
+            )),
+            _ => Err(ldtk_import_error!("Unknown default override id! {id}")),
+        }
+    }
+}
+
+/// The declared schema of a field on an entity or level.
+#[derive(Clone, Debug, Reflect)]
+pub struct FieldDefinition {
+    /// The user defined identifier, matching the key of the field instances built from this
+    /// definition.
+    pub identifier: String,
+    /// The unique identifier of this definition, referenced by
+    /// [crate::field_instance::FieldInstance::def_uid].
+    pub uid: Uid,
+    /// The LDtk type of the field, such as `Int`, `LocalEnum.SomeEnum` or `Array<Point>`.
+    pub field_type: String,
+    /// True if the field holds an array of values.
+    pub is_array: bool,
+    /// True if the value can be null. For arrays, this means the array can contain null values.
+    pub can_be_null: bool,
+    /// The default value of the field, which LDtk writes into new field instances.
+    ///
+    /// It is also used when extracting a null field instance into a type other than `Option<T>`,
+    /// see [crate::from_field_instance::extract_field].
+    pub default_override: Option<FieldDefaultValue>,
+    /// The minimum value allowed, if applicable.
+    pub min: Option<f64>,
+    /// The maximum value allowed, if applicable.
+    pub max: Option<f64>,
+    /// An optional regular expression which values must match, in the form `/some_reg_ex/g`.
+    pub regex: Option<String>,
+    /// The minimum length of an array field.
+    pub array_min_length: Option<i64>,
+    /// The maximum length of an array field.
+    pub array_max_length: Option<i64>,
+    /// Which entities can be referenced, for fields of type `EntityRef`.
+    pub allowed_refs: AllowedRefs,
+    /// True if an `EntityRef` field can reference an entity in another level.
+    pub allow_out_of_level_ref: bool,
+    /// The accepted file extensions for fields of type `FilePath`, including the dot.
+    pub accept_file_types: Option<Vec<String>>,
+    /// User supplied documentation for this field.
+    pub doc: Option<String>,
+}
+
+impl FieldDefinition {
+    pub(crate) fn new(value: ldtk::FieldDefinition) -> LdtkResult<Self> {
+        let allowed_refs = AllowedRefs::new(&value)?;
+
+        let default_override = value
+            .default_override
+            .as_ref()
+            .filter(|default_override| !default_override.is_null())
+            .map(FieldDefaultValue::new)
+            .transpose()?;
+
+        Ok(Self {
+            identifier: value.identifier,
+            uid: value.uid,
+            field_type: value.field_definition_type,
+            is_array: value.is_array,
+            can_be_null: value.can_be_null,
+            default_override,
+            min: value.min,
+            max: value.max,
+            regex: value.regex,
+            array_min_length: value.array_min_length,
+            array_max_length: value.array_max_length,
+            allowed_refs,
+            allow_out_of_level_ref: value.allow_out_of_level_ref,
+            accept_file_types: value.accept_file_types,
+            doc: value.doc,
+        })
+    }
+}
+
+// Every field definition in the project, of both entities and levels, indexed by uid. Field
+// instances keep the definition they were built from, see
+// [crate::field_instance::FieldInstance::field_definition].
+pub(crate) fn construct_field_definitions(
+    definitions_json: &ldtk::Definitions,
+) -> LdtkResult<UidMap<Arc<FieldDefinition>>> {
+    definitions_json
+        .entities
+        .iter()
+        .flat_map(|entity_definition| entity_definition.field_defs.iter())
+        .chain(definitions_json.level_fields.iter())
+        .map(|field_definition_json| {
+            let field_definition = FieldDefinition::new(field_definition_json.clone())?;
+            Ok((field_definition.uid, Arc::new(field_definition)))
+        })
+        .collect()
+}
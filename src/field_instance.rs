@@ -12,48 +12,111 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::RwLock;
 
-use bevy_asset::Handle;
+use bevy_asset::{AssetId, Handle};
 use bevy_color::Color;
+use bevy_log::warn;
 use bevy_math::I64Vec2;
 use bevy_platform::collections::HashMap;
 use bevy_reflect::Reflect;
 
 use crate::color::bevy_color_from_ldtk_string;
+use crate::entity::EntityInstance;
 use crate::enum_definition::EnumDefinition;
+use crate::error::LdtkError;
+use crate::field_definition::FieldDefinition;
 use crate::iid::Iid;
+use crate::layer::LayerInstance;
 use crate::ldtk;
 use crate::ldtk_import_error;
 use crate::ldtk_path::ldtk_path_to_bevy_path;
+use crate::level::Level;
 use crate::project::ProjectContext;
+use crate::project_loader::DanglingEntityRefs;
 use crate::result::LdtkResult;
 use crate::tileset_rectangle::TilesetRectangle;
 use crate::uid::Uid;
+use crate::world::World;
+
+/// The [AssetId]s of the assets referenced by an [EntityRef], resolved when the project is loaded.
+///
+/// These are ids rather than handles, so that entities referencing each other do not keep their
+/// levels loaded forever. Look them up with [bevy_asset::Assets::get] as usual.
+///
+/// NOTE: The ids are weak. They are only valid while the referenced assets are alive, which the
+/// [EntityRef] does nothing to ensure. Once an external level is unloaded, such as by
+/// [crate::streaming], or a sub asset is dropped because no handle to it remains, the lookup
+/// returns `None`. Loading the asset again gives it a new id, so hold on to the level's
+/// [bevy_asset::Handle], or resolve the reference again from [EntityRef::entity_iid].
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct EntityRefIds {
+    pub entity: AssetId<EntityInstance>,
+    pub layer: AssetId<LayerInstance>,
+    pub level: AssetId<Level>,
+    pub world: AssetId<World>,
+}
 
 /// The internal value of a field instance of type [FieldInstanceType::EntityRef]
 #[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, Reflect)]
+#[derive(Clone, Debug, PartialEq, Eq, Reflect)]
 pub struct EntityRef {
     pub entity_iid: Iid,
     pub layer_iid: Iid,
     pub level_iid: Iid,
     pub world_iid: Iid,
+    /// The ids of the referenced entity and its parents.
+    ///
+    /// This is `None` when the referenced entity was not loaded because of the
+    /// [crate::project_loader::ProjectLoaderSettings], or when it does not exist in the project and
+    /// the project was loaded with [DanglingEntityRefs::Warn].
+    pub ids: Option<EntityRefIds>,
 }
 
-#[allow(missing_docs)]
+/// The internal value of a field instance of type [FieldInstanceType::Enum]
+///
+/// See [crate::ldtk_enum::LdtkEnum] for converting this into a Rust enum.
 #[derive(Clone, Debug, Reflect)]
 pub struct EnumValue {
     value: String,
+    enum_identifier: String,
     enum_definition: Handle<EnumDefinition>,
 }
 
+impl EnumValue {
+    /// The chosen value, matching the id of one of the [EnumDefinition]'s values.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The identifier of the [EnumDefinition] this value belongs to.
+    pub fn enum_identifier(&self) -> &str {
+        &self.enum_identifier
+    }
+
+    /// A handle to the [EnumDefinition] this value belongs to.
+    pub const fn enum_definition(&self) -> &Handle<EnumDefinition> {
+        &self.enum_definition
+    }
+}
+
+/// The value of a field instance.
+///
+/// Elements of an array are `None` where they were left null in LDtk, which is only allowed for
+/// fields with [crate::field_definition::FieldDefinition::can_be_null] set.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Reflect)]
 pub enum FieldInstanceType {
-    ArrayInt(Vec<i64>),
-    ArrayEnum(Vec<EnumValue>),
-    ArrayString(Vec<String>),
-    ArrayPoint(Vec<I64Vec2>),
-    ArrayTile(Vec<TilesetRectangle>),
+    ArrayBool(Vec<Option<bool>>),
+    ArrayColor(Vec<Option<Color>>),
+    ArrayEntityRef(Vec<Option<EntityRef>>),
+    ArrayEnum(Vec<Option<EnumValue>>),
+    ArrayFilePath(Vec<Option<PathBuf>>),
+    ArrayFloat(Vec<Option<f64>>),
+    ArrayInt(Vec<Option<i64>>),
+    ArrayMultilines(Vec<Option<String>>),
+    ArrayPoint(Vec<Option<I64Vec2>>),
+    ArrayString(Vec<Option<String>>),
+    ArrayTile(Vec<Option<TilesetRectangle>>),
     Bool(bool),
     Color(Color),
     EntityRef(EntityRef),
@@ -61,6 +124,10 @@ pub enum FieldInstanceType {
     FilePath(PathBuf),
     Float(f64),
     Int(i64),
+    Multilines(String),
+    /// A field which was left null in LDtk, holding its declared type (e.g. `Int` or
+    /// `LocalEnum.SomeEnum`).
+    Null(String),
     Point(I64Vec2),
     String(String),
     Tile(TilesetRectangle),
@@ -90,132 +157,200 @@ impl FieldInstanceType {
         value: Option<&serde_json::Value>,
         project_context: Arc<RwLock<ProjectContext<'_>>>,
     ) -> LdtkResult<Self> {
-        let value = value.ok_or_else(|| ldtk_import_error!("Field instance value is None!"))?;
+        let Some(value) = value.filter(|value| !value.is_null()) else {
+            return Ok(Self::Null(field_instance_type.to_string()));
+        };
+        let project_context = project_context.read()?;
         match field_instance_type {
-            "Array<Int>" => Ok(Self::ArrayInt(
-                value
-                    .as_array()
-                    .ok_or_else(|| {
-                        ldtk_import_error!(
-                            "Field Instance with type Array<Int>, value not an array! {:?}",
-                            value
-                        )
-                    })?
-                    .iter()
-                    .map(|value| serde_json::from_value::<i64>(value.clone()).map_err(|e| e.into()))
-                    .collect::<LdtkResult<Vec<_>>>()?,
-            )),
-            "Array<String>" => Ok(Self::ArrayString(
-                value
-                    .as_array()
-                    .ok_or_else(|| {
-                        ldtk_import_error!(
-                            "Field Instance with type Array<Multilines>, value not an array! {:?}",
-                            value
-                        )
-                    })?
-                    .iter()
-                    .map(|value| {
-                        serde_json::from_value::<String>(value.clone()).map_err(|e| e.into())
-                    })
-                    .collect::<LdtkResult<Vec<_>>>()?,
-            )),
-            "Array<Point>" => Ok(Self::ArrayPoint(
-                value
-                    .as_array()
-                    .ok_or_else(|| {
-                        ldtk_import_error!(
-                            "Field Instance with type Array<Point>, value not an array! {:?}",
-                            value
-                        )
-                    })?
-                    .iter()
-                    .map(|value| {
-                        let cx = field_instance_map_get!(value, "cx", "Point", as_i64);
-                        let cy = field_instance_map_get!(value, "cy", "Point", as_i64);
-                        Ok((cx, cy).into())
-                    })
-                    .collect::<LdtkResult<Vec<_>>>()?,
-            )),
-            "Array<Tile>" => Ok(Self::ArrayTile(
-                value
-                    .as_array()
-                    .ok_or_else(|| {
-                        ldtk_import_error!(
-                            "Field Instance with type Array<Tile>, value not an array! {:?}",
-                            value
-                        )
-                    })?
-                    .iter()
-                    .map(|value| {
-                        let value =
-                            serde_json::from_value::<ldtk::TilesetRectangle>(value.clone())?;
-                        TilesetRectangle::new(value, &project_context.read()?.tileset_definitions)
-                    })
-                    .collect::<LdtkResult<Vec<_>>>()?,
-            )),
-            "Bool" => Ok(Self::Bool(serde_json::from_value::<bool>(value.clone())?)),
-            "Color" => Ok(Self::Color({
-                let value = serde_json::from_value::<String>(value.clone())?;
-                bevy_color_from_ldtk_string(&value)?
-            })),
-            "EntityRef" => Ok(Self::EntityRef({
-                let entity_iid = field_instance_map_get!(value, "entityIid", "EntityRef", as_str);
-                let entity_iid = Iid::from_str(entity_iid)?;
-
-                let layer_iid = field_instance_map_get!(value, "layerIid", "EntityRef", as_str);
-                let layer_iid = Iid::from_str(layer_iid)?;
-
-                let level_iid = field_instance_map_get!(value, "levelIid", "EntityRef", as_str);
-                let level_iid = Iid::from_str(level_iid)?;
-
-                let world_iid = field_instance_map_get!(value, "worldIid", "EntityRef", as_str);
-                let world_iid = Iid::from_str(world_iid)?;
-
-                EntityRef {
-                    entity_iid,
-                    layer_iid,
-                    level_iid,
-                    world_iid,
-                }
-            })),
-            "FilePath" => Ok(Self::FilePath(ldtk_path_to_bevy_path(
-                &project_context.read()?.project_directory,
-                serde_json::from_value::<String>(value.clone())?,
-            ))),
-            "Float" => Ok(Self::Float(serde_json::from_value::<f64>(value.clone())?)),
-            "Int" => Ok(Self::Int(serde_json::from_value::<i64>(value.clone())?)),
-            "Point" => Ok(Self::Point(
-                (
-                    field_instance_map_get!(value, "cx", "Point", as_i64),
-                    field_instance_map_get!(value, "cy", "Point", as_i64),
-                )
-                    .into(),
-            )),
-            "String" => Ok(Self::String(serde_json::from_value::<String>(
-                value.clone(),
+            "Array<Bool>" => Ok(Self::ArrayBool(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_bool,
             )?)),
-            "Tile" => Ok(Self::Tile({
-                let value = serde_json::from_value::<ldtk::TilesetRectangle>(value.clone())?;
-                TilesetRectangle::new(value, &project_context.read()?.tileset_definitions)?
-            })),
+            "Array<Color>" => Ok(Self::ArrayColor(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_color,
+            )?)),
+            "Array<EntityRef>" => Ok(Self::ArrayEntityRef(Self::parse_array(
+                field_instance_type,
+                value,
+                |value| Self::parse_entity_ref(value, &project_context),
+            )?)),
+            "Array<FilePath>" => Ok(Self::ArrayFilePath(Self::parse_array(
+                field_instance_type,
+                value,
+                |value| Self::parse_file_path(value, &project_context),
+            )?)),
+            "Array<Float>" => Ok(Self::ArrayFloat(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_float,
+            )?)),
+            "Array<Int>" => Ok(Self::ArrayInt(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_int,
+            )?)),
+            "Array<Multilines>" => Ok(Self::ArrayMultilines(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_string,
+            )?)),
+            "Array<Point>" => Ok(Self::ArrayPoint(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_point,
+            )?)),
+            "Array<String>" => Ok(Self::ArrayString(Self::parse_array(
+                field_instance_type,
+                value,
+                Self::parse_string,
+            )?)),
+            "Array<Tile>" => Ok(Self::ArrayTile(Self::parse_array(
+                field_instance_type,
+                value,
+                |value| Self::parse_tile(value, &project_context),
+            )?)),
+            "Bool" => Ok(Self::Bool(Self::parse_bool(value)?)),
+            "Color" => Ok(Self::Color(Self::parse_color(value)?)),
+            "EntityRef" => Ok(Self::EntityRef(Self::parse_entity_ref(
+                value,
+                &project_context,
+            )?)),
+            "FilePath" => Ok(Self::FilePath(Self::parse_file_path(
+                value,
+                &project_context,
+            )?)),
+            "Float" => Ok(Self::Float(Self::parse_float(value)?)),
+            "Int" => Ok(Self::Int(Self::parse_int(value)?)),
+            "Multilines" => Ok(Self::Multilines(Self::parse_string(value)?)),
+            "Point" => Ok(Self::Point(Self::parse_point(value)?)),
+            "String" => Ok(Self::String(Self::parse_string(value)?)),
+            "Tile" => Ok(Self::Tile(Self::parse_tile(value, &project_context)?)),
             _ => {
                 // try to parse as an enum
                 Self::parse_non_obvious_field_instance_type(
                     field_instance_type,
                     value,
-                    &project_context.read()?.enum_definitions,
+                    &project_context.enum_definitions,
                 )
             }
         }
     }
 
-    fn parse_non_obvious_field_instance_type(
+    pub(crate) fn parse_array<T>(
         field_instance_type: &str,
         value: &serde_json::Value,
-        enum_definitions: &HashMap<String, Handle<EnumDefinition>>,
-    ) -> LdtkResult<Self> {
-        // If we make it here, we should have one of four things:
+        parse: impl Fn(&serde_json::Value) -> LdtkResult<T>,
+    ) -> LdtkResult<Vec<Option<T>>> {
+        value
+            .as_array()
+            .ok_or_else(|| {
+                ldtk_import_error!(
+                    "Field Instance with type {}, value not an array! {:?}",
+                    field_instance_type,
+                    value
+                )
+            })?
+            .iter()
+            .map(|value| (!value.is_null()).then(|| parse(value)).transpose())
+            .collect()
+    }
+
+    pub(crate) fn parse_bool(value: &serde_json::Value) -> LdtkResult<bool> {
+        Ok(serde_json::from_value::<bool>(value.clone())?)
+    }
+
+    pub(crate) fn parse_color(value: &serde_json::Value) -> LdtkResult<Color> {
+        let value = serde_json::from_value::<String>(value.clone())?;
+        bevy_color_from_ldtk_string(&value)
+    }
+
+    fn parse_entity_ref(
+        value: &serde_json::Value,
+        project_context: &ProjectContext<'_>,
+    ) -> LdtkResult<EntityRef> {
+        let entity_iid = field_instance_map_get!(value, "entityIid", "EntityRef", as_str);
+        let entity_iid = Iid::from_str(entity_iid)?;
+
+        let layer_iid = field_instance_map_get!(value, "layerIid", "EntityRef", as_str);
+        let layer_iid = Iid::from_str(layer_iid)?;
+
+        let level_iid = field_instance_map_get!(value, "levelIid", "EntityRef", as_str);
+        let level_iid = Iid::from_str(level_iid)?;
+
+        let world_iid = field_instance_map_get!(value, "worldIid", "EntityRef", as_str);
+        let world_iid = Iid::from_str(world_iid)?;
+
+        // The target exists, but was left out by the loader settings.
+        let excluded = !project_context.load_entities
+            || project_context.excluded_level_iids.contains(&level_iid);
+
+        let ids = match (
+            project_context.entity_ref_ids.get(&entity_iid),
+            project_context.dangling_entity_refs,
+        ) {
+            (Some(ids), _) => Some(*ids),
+            (None, _) if excluded => None,
+            (None, DanglingEntityRefs::Error) => {
+                return Err(LdtkError::DanglingEntityRef(entity_iid));
+            }
+            (None, DanglingEntityRefs::Warn) => {
+                warn!("EntityRef to an entity which does not exist! entity iid: {entity_iid}");
+                None
+            }
+        };
+
+        Ok(EntityRef {
+            entity_iid,
+            layer_iid,
+            level_iid,
+            world_iid,
+            ids,
+        })
+    }
+
+    fn parse_file_path(
+        value: &serde_json::Value,
+        project_context: &ProjectContext<'_>,
+    ) -> LdtkResult<PathBuf> {
+        Ok(ldtk_path_to_bevy_path(
+            &project_context.project_directory,
+            serde_json::from_value::<String>(value.clone())?,
+        ))
+    }
+
+    pub(crate) fn parse_float(value: &serde_json::Value) -> LdtkResult<f64> {
+        Ok(serde_json::from_value::<f64>(value.clone())?)
+    }
+
+    pub(crate) fn parse_int(value: &serde_json::Value) -> LdtkResult<i64> {
+        Ok(serde_json::from_value::<i64>(value.clone())?)
+    }
+
+    pub(crate) fn parse_point(value: &serde_json::Value) -> LdtkResult<I64Vec2> {
+        let cx = field_instance_map_get!(value, "cx", "Point", as_i64);
+        let cy = field_instance_map_get!(value, "cy", "Point", as_i64);
+        Ok((cx, cy).into())
+    }
+
+    pub(crate) fn parse_string(value: &serde_json::Value) -> LdtkResult<String> {
+        Ok(serde_json::from_value::<String>(value.clone())?)
+    }
+
+    fn parse_tile(
+        value: &serde_json::Value,
+        project_context: &ProjectContext<'_>,
+    ) -> LdtkResult<TilesetRectangle> {
+        let value = serde_json::from_value::<ldtk::TilesetRectangle>(value.clone())?;
+        TilesetRectangle::new(value, &project_context.tileset_definitions)
+    }
+
+    // Splits an enum field type such as `Array<LocalEnum.SomeEnum>` into the enum's identifier,
+    // and whether it is an array.
+    pub(crate) fn split_enum_field_type(field_instance_type: &str) -> LdtkResult<(&str, bool)> {
+        // We should have one of four things:
         // * "LocalEnum.{Enum Group Name}"
         // * "ExternEnum.{Enum Group Name}"
         // * "Array<LocalEnum.{Enum Group Name}>"
@@ -241,7 +376,7 @@ impl FieldInstanceType {
             ));
         };
 
-        let (enum_name, is_array) = if lhs.contains('<') {
+        if lhs.contains('<') {
             let mut around_the_carat = lhs.split('<');
             let (Some(array_lhs), Some(array_rhs), None) = (
                 around_the_carat.next(),
@@ -278,7 +413,7 @@ impl FieldInstanceType {
                 ));
             }
 
-            (&rhs[..rhs.len() - 1], true)
+            Ok((&rhs[..rhs.len() - 1], true))
         } else {
             if !(lhs == "LocalEnum" || lhs == "ExternEnum") {
                 return Err(ldtk_import_error!(
@@ -286,31 +421,31 @@ impl FieldInstanceType {
                 ));
             }
 
-            (rhs, false)
-        };
+            Ok((rhs, false))
+        }
+    }
+
+    fn parse_non_obvious_field_instance_type(
+        field_instance_type: &str,
+        value: &serde_json::Value,
+        enum_definitions: &HashMap<String, Handle<EnumDefinition>>,
+    ) -> LdtkResult<Self> {
+        let (enum_name, is_array) = Self::split_enum_field_type(field_instance_type)?;
 
         if is_array {
-            let array_enum = value
-                .as_array()
-                .ok_or_else(|| {
-                    ldtk_import_error!(
-                        "Field Instance with type Array<*Enum.*>, value not an array!"
-                    )
-                })?
-                .iter()
-                .map(|value| {
-                    let value = serde_json::from_value::<String>(value.clone())?;
-                    let enum_definition = enum_definitions
-                        .get(enum_name)
-                        .ok_or_else(|| ldtk_import_error!("bad enum identifier! {}", enum_name))?
-                        .clone();
-
-                    Ok(EnumValue {
-                        value,
-                        enum_definition,
-                    })
+            let array_enum = Self::parse_array(field_instance_type, value, |value| {
+                let value = serde_json::from_value::<String>(value.clone())?;
+                let enum_definition = enum_definitions
+                    .get(enum_name)
+                    .ok_or_else(|| ldtk_import_error!("bad enum identifier! {}", enum_name))?
+                    .clone();
+
+                Ok(EnumValue {
+                    value,
+                    enum_identifier: enum_name.to_string(),
+                    enum_definition,
                 })
-                .collect::<LdtkResult<Vec<_>>>()?;
+            })?;
 
             Ok(Self::ArrayEnum(array_enum))
         } else {
@@ -322,28 +457,103 @@ impl FieldInstanceType {
 
             Ok(Self::Enum(EnumValue {
                 value,
+                enum_identifier: enum_name.to_string(),
                 enum_definition,
             }))
         }
     }
 }
 
+impl FieldInstanceType {
+    // Splits an array value into one value per element, or returns `None` if this is not an
+    // array. Null elements become [Self::Null], holding `element_type`. See
+    // [FieldInstance::array_elements].
+    pub(crate) fn split_array(&self, element_type: &str) -> Option<Vec<Self>> {
+        fn split<T: Clone>(
+            values: &[Option<T>],
+            element: impl Fn(T) -> FieldInstanceType,
+            element_type: &str,
+        ) -> Option<Vec<FieldInstanceType>> {
+            Some(
+                values
+                    .iter()
+                    .cloned()
+                    .map(|value| {
+                        value.map_or_else(
+                            || FieldInstanceType::Null(element_type.to_string()),
+                            &element,
+                        )
+                    })
+                    .collect(),
+            )
+        }
+
+        match self {
+            Self::ArrayBool(values) => split(values, Self::Bool, element_type),
+            Self::ArrayColor(values) => split(values, Self::Color, element_type),
+            Self::ArrayEntityRef(values) => split(values, Self::EntityRef, element_type),
+            Self::ArrayEnum(values) => split(values, Self::Enum, element_type),
+            Self::ArrayFilePath(values) => split(values, Self::FilePath, element_type),
+            Self::ArrayFloat(values) => split(values, Self::Float, element_type),
+            Self::ArrayInt(values) => split(values, Self::Int, element_type),
+            Self::ArrayMultilines(values) => split(values, Self::Multilines, element_type),
+            Self::ArrayPoint(values) => split(values, Self::Point, element_type),
+            Self::ArrayString(values) => split(values, Self::String, element_type),
+            Self::ArrayTile(values) => split(values, Self::Tile, element_type),
+            _ => None,
+        }
+    }
+
+    /// A human readable name for the type of this value, following LDtk's naming.
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::ArrayBool(_) => "Array<Bool>",
+            Self::ArrayColor(_) => "Array<Color>",
+            Self::ArrayEntityRef(_) => "Array<EntityRef>",
+            Self::ArrayEnum(_) => "Array<Enum>",
+            Self::ArrayFilePath(_) => "Array<FilePath>",
+            Self::ArrayFloat(_) => "Array<Float>",
+            Self::ArrayInt(_) => "Array<Int>",
+            Self::ArrayMultilines(_) => "Array<Multilines>",
+            Self::ArrayPoint(_) => "Array<Point>",
+            Self::ArrayString(_) => "Array<String>",
+            Self::ArrayTile(_) => "Array<Tile>",
+            Self::Bool(_) => "Bool",
+            Self::Color(_) => "Color",
+            Self::EntityRef(_) => "EntityRef",
+            Self::Enum(_) => "Enum",
+            Self::FilePath(_) => "FilePath",
+            Self::Float(_) => "Float",
+            Self::Int(_) => "Int",
+            Self::Multilines(_) => "Multilines",
+            Self::Null(_) => "Null",
+            Self::Point(_) => "Point",
+            Self::String(_) => "String",
+            Self::Tile(_) => "Tile",
+        }
+    }
+}
+
 /// An individual field instance value.
 ///
 /// Typically, this will be stored in a collection in either an
 /// [crate::entity::EntityInstance] or a [crate::level::Level], and be associated
 /// with that particular asset.
 ///
-/// Note: Optional field instance values which have not been given a value are not exported by
-/// this plugin. If the field is required by LDtk, but not supplied, then the LDtk editor will
-/// flash a red border around the entity warning the user to fill in the field. If the field is
-/// allowed to be null, then we simply will not export it.
+/// Note: Field instances which have not been given a value are exported with a
+/// [FieldInstanceType::Null] value, holding the declared type of the field. If the field is
+/// required by LDtk, but not supplied, then the LDtk editor will flash a red border around the
+/// entity warning the user to fill in the field.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Reflect)]
 pub struct FieldInstance {
     pub tileset_rectangle: Option<TilesetRectangle>,
     pub field_instance_type: FieldInstanceType,
     pub def_uid: Uid,
+    /// The definition of this field, shared by every instance of it. Its
+    /// [FieldDefinition::default_override] is used when extracting a null value, see
+    /// [crate::from_field_instance::extract_field].
+    pub field_definition: Arc<FieldDefinition>,
 }
 
 impl FieldInstance {
@@ -356,6 +566,20 @@ impl FieldInstance {
             .map(|value| TilesetRectangle::new(value, &project_context.read()?.tileset_definitions))
             .transpose()?;
 
+        let def_uid = field_instance_json.def_uid;
+
+        let field_definition = project_context
+            .read()?
+            .field_definitions
+            .get(&def_uid)
+            .ok_or_else(|| {
+                ldtk_import_error!(
+                    "Bad field definition uid {def_uid} for field instance {}!",
+                    field_instance_json.identifier
+                )
+            })?
+            .clone();
+
         let field_instance_type = FieldInstanceType::new(
             &field_instance_json.field_instance_type,
             field_instance_json.value.as_ref(),
@@ -363,16 +587,33 @@ impl FieldInstance {
         )
         .await?;
 
-        let def_uid = field_instance_json.def_uid;
-
         Ok(Self {
             tileset_rectangle,
             field_instance_type,
             def_uid,
+            field_definition,
         })
     }
 }
 
+impl FieldInstance {
+    /// Splits an array field into one value per element, or returns `None` if this is not an
+    /// array.
+    ///
+    /// Null elements become [FieldInstanceType::Null], holding the declared element type (e.g.
+    /// `Int` or `LocalEnum.Foo`).
+    pub fn array_elements(&self) -> Option<Vec<FieldInstanceType>> {
+        let element_type = self
+            .field_definition
+            .field_type
+            .strip_prefix("Array<")
+            .and_then(|element_type| element_type.strip_suffix('>'))
+            .unwrap_or(&self.field_definition.field_type);
+
+        self.field_instance_type.split_array(element_type)
+    }
+}
+
 macro_rules! is_type {
     ($self:expr, $field_instance_type:path) => {
         matches!($self.field_instance_type, $field_instance_type(_))
@@ -381,22 +622,46 @@ macro_rules! is_type {
 
 #[allow(missing_docs)]
 impl FieldInstance {
-    pub const fn is_array_int(&self) -> bool {
-        is_type!(self, FieldInstanceType::ArrayInt)
+    pub const fn is_array_bool(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayBool)
+    }
+
+    pub const fn is_array_color(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayColor)
+    }
+
+    pub const fn is_array_entity_ref(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayEntityRef)
     }
 
     pub const fn is_array_enum(&self) -> bool {
         is_type!(self, FieldInstanceType::ArrayEnum)
     }
 
-    pub const fn is_array_string(&self) -> bool {
-        is_type!(self, FieldInstanceType::ArrayString)
+    pub const fn is_array_file_path(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayFilePath)
+    }
+
+    pub const fn is_array_float(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayFloat)
+    }
+
+    pub const fn is_array_int(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayInt)
+    }
+
+    pub const fn is_array_multilines(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayMultilines)
     }
 
     pub const fn is_array_point(&self) -> bool {
         is_type!(self, FieldInstanceType::ArrayPoint)
     }
 
+    pub const fn is_array_string(&self) -> bool {
+        is_type!(self, FieldInstanceType::ArrayString)
+    }
+
     pub const fn is_array_tile(&self) -> bool {
         is_type!(self, FieldInstanceType::ArrayTile)
     }
@@ -429,6 +694,14 @@ impl FieldInstance {
         is_type!(self, FieldInstanceType::Int)
     }
 
+    pub const fn is_multilines(&self) -> bool {
+        is_type!(self, FieldInstanceType::Multilines)
+    }
+
+    pub const fn is_null(&self) -> bool {
+        is_type!(self, FieldInstanceType::Null)
+    }
+
     pub const fn is_point(&self) -> bool {
         is_type!(self, FieldInstanceType::Point)
     }
@@ -457,18 +730,26 @@ macro_rules! get_by_type {
 #[rustfmt::skip::macros(get_by_type)]
 #[allow(missing_docs)]
 impl FieldInstance {
-    get_by_type!(get_array_int, FieldInstanceType::ArrayInt, Vec<i64>);
-    get_by_type!(get_array_enum, FieldInstanceType::ArrayEnum, Vec<EnumValue>);
-    get_by_type!(get_array_string, FieldInstanceType::ArrayString, Vec<String>);
-    get_by_type!(get_array_point, FieldInstanceType::ArrayPoint, Vec<I64Vec2>);
-    get_by_type!(get_array_tile, FieldInstanceType::ArrayTile, Vec<TilesetRectangle>);
+    get_by_type!(get_array_bool, FieldInstanceType::ArrayBool, Vec<Option<bool>>);
+    get_by_type!(get_array_color, FieldInstanceType::ArrayColor, Vec<Option<Color>>);
+    get_by_type!(get_array_entity_ref, FieldInstanceType::ArrayEntityRef, Vec<Option<EntityRef>>);
+    get_by_type!(get_array_enum, FieldInstanceType::ArrayEnum, Vec<Option<EnumValue>>);
+    get_by_type!(get_array_file_path, FieldInstanceType::ArrayFilePath, Vec<Option<PathBuf>>);
+    get_by_type!(get_array_float, FieldInstanceType::ArrayFloat, Vec<Option<f64>>);
+    get_by_type!(get_array_int, FieldInstanceType::ArrayInt, Vec<Option<i64>>);
+    get_by_type!(get_array_multilines, FieldInstanceType::ArrayMultilines, Vec<Option<String>>);
+    get_by_type!(get_array_point, FieldInstanceType::ArrayPoint, Vec<Option<I64Vec2>>);
+    get_by_type!(get_array_string, FieldInstanceType::ArrayString, Vec<Option<String>>);
+    get_by_type!(get_array_tile, FieldInstanceType::ArrayTile, Vec<Option<TilesetRectangle>>);
     get_by_type!(get_bool, FieldInstanceType::Bool, bool);
     get_by_type!(get_color, FieldInstanceType::Color, Color);
-    get_by_type!(get_array_entity_ref,FieldInstanceType::EntityRef, EntityRef);
+    get_by_type!(get_entity_ref, FieldInstanceType::EntityRef, EntityRef);
     get_by_type!(get_enum, FieldInstanceType::Enum, EnumValue);
     get_by_type!(get_file_path, FieldInstanceType::FilePath, PathBuf);
     get_by_type!(get_float, FieldInstanceType::Float, f64);
     get_by_type!(get_int, FieldInstanceType::Int, i64);
+    get_by_type!(get_multilines, FieldInstanceType::Multilines, String);
+    get_by_type!(get_null, FieldInstanceType::Null, String);
     get_by_type!(get_point, FieldInstanceType::Point, I64Vec2);
     get_by_type!(get_string, FieldInstanceType::String, String);
     get_by_type!(get_tile, FieldInstanceType::Tile, TilesetRectangle);
@@ -0,0 +1,235 @@
+//! Conversions from [FieldInstance]s into Rust types.
+//!
+//! [FromFieldInstance] describes how a single field instance is converted, and [LdtkFields]
+//! describes how a whole struct is built from the field instances of an asset. [LdtkFields] is
+//! normally implemented with `#[derive(LdtkFields)]`, which also implements
+//! `TryFrom<&EntityInstance>` and `TryFrom<&Level>`.
+//!
+//! * A struct field of type `T` requires that the field instance is present and of the matching
+//!   type.
+//! * A struct field of type `Option<T>` will be `None` if the field instance is not present, or
+//!   was left null in LDtk.
+//! * A field instance left null in LDtk is converted into a struct field of type `T` from the
+//!   [FieldDefinition::default_override] of its definition, if it has one and `T` supports it, see
+//!   [FromFieldInstance::from_default_value]. LDtk writes a field's default into its value, so a
+//!   null means the user chose null, and an `Option<T>` is always `None`, whatever the default.
+//! * Array field instances are converted into a `Vec<T>`, converting each element with `T`'s
+//!   [FromFieldInstance] implementation. Arrays which may hold null elements should be converted
+//!   into a `Vec<Option<T>>`.
+//! * `#[ldtk(rename = "...")]` selects a field instance whose identifier differs from the struct
+//!   field's name.
+//!
+//! Single fields can also be extracted directly with
+//! [LdtkAssetWithFieldInstances::get_field].
+//!
+//! [FromFieldInstance] can be implemented for user types, typically by first extracting one of
+//! the types provided here and then converting it. For example, a configuration struct could be
+//! parsed from a JSON payload stored in a `String` or `Multilines` field:
+//!
+//! ```ignore
+//! impl FromFieldInstance for DoorConfig {
+//!     fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+//!         let payload = String::from_field_instance(field_instance)?;
+//!         Ok(serde_json::from_str(&payload)?)
+//!     }
+//! }
+//!
+//! let config: DoorConfig = entity_instance.get_field("Config")?;
+//! let configs: Vec<DoorConfig> = entity_instance.get_field("Configs")?;
+//! ```
+//!
+//! [EntityInstance]: crate::entity::EntityInstance
+//! [Level]: crate::level::Level
+
+use std::path::PathBuf;
+
+use bevy_color::Color;
+use bevy_math::I64Vec2;
+
+use crate::color::bevy_color_from_ldtk_int;
+use crate::error::LdtkError;
+use crate::field_definition::{FieldDefaultValue, FieldDefinition};
+use crate::field_instance::{EntityRef, EnumValue, FieldInstance, FieldInstanceType};
+use crate::ldtk_asset_trait::LdtkAssetWithFieldInstances;
+use crate::result::LdtkResult;
+use crate::tileset_rectangle::TilesetRectangle;
+
+pub use bevy_ldtk_asset_derive::LdtkFields;
+
+/// A type which can be built from the field instances of an LDtk asset.
+///
+/// See the [module level documentation](self).
+pub trait LdtkFields: Sized {
+    /// Builds `Self` from the field instances of the given asset.
+    fn from_field_instances(asset: &impl LdtkAssetWithFieldInstances) -> LdtkResult<Self>;
+}
+
+/// A type which can be converted from a single [FieldInstance].
+pub trait FromFieldInstance: Sized {
+    /// Attempt the conversion, failing if the field instance holds an incompatible type.
+    fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self>;
+
+    /// The value to use when no field instance with the requested identifier exists.
+    ///
+    /// Returns `None` by default, which is treated as an error.
+    fn from_missing_field_instance() -> Option<Self> {
+        None
+    }
+
+    /// Attempt the conversion from a [FieldDefinition::default_override], used in place of a
+    /// field instance left null in LDtk.
+    ///
+    /// Returns `None` by default, in which case the null field instance is converted with
+    /// [Self::from_field_instance] as usual.
+    fn from_default_value(_default_value: &FieldDefaultValue) -> Option<LdtkResult<Self>> {
+        None
+    }
+}
+
+/// Converts an optional [FieldInstance] using [FromFieldInstance], naming the field's identifier
+/// in any error produced.
+///
+/// When the field instance is null, and the given [FieldDefinition] has a
+/// [FieldDefinition::default_override], the default is converted with
+/// [FromFieldInstance::from_default_value] instead. `Option<T>` doesn't convert defaults, so a
+/// null is `None` for it.
+pub fn extract_field<T: FromFieldInstance>(
+    identifier: &str,
+    field_instance: Option<&FieldInstance>,
+    field_definition: Option<&FieldDefinition>,
+) -> LdtkResult<T> {
+    field_instance.map_or_else(
+        || {
+            T::from_missing_field_instance()
+                .ok_or_else(|| LdtkError::FieldInstanceNotFound(identifier.to_string()))
+        },
+        |field_instance| {
+            field_definition
+                .and_then(|field_definition| field_definition.default_override.as_ref())
+                .filter(|_| field_instance.is_null())
+                .and_then(T::from_default_value)
+                .unwrap_or_else(|| T::from_field_instance(field_instance))
+                .map_err(|source| LdtkError::FieldInstanceExtractionError {
+                    identifier: identifier.to_string(),
+                    source: Box::new(source),
+                })
+        },
+    )
+}
+
+impl<T: FromFieldInstance> FromFieldInstance for Vec<T> {
+    fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+        field_instance
+            .array_elements()
+            .ok_or_else(|| LdtkError::FieldInstanceTypeError {
+                expected: "Array",
+                given: field_instance.field_instance_type.type_name(),
+            })?
+            .into_iter()
+            .map(|field_instance_type| {
+                T::from_field_instance(&FieldInstance {
+                    tileset_rectangle: field_instance.tileset_rectangle.clone(),
+                    field_instance_type,
+                    def_uid: field_instance.def_uid,
+                    field_definition: field_instance.field_definition.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T: FromFieldInstance> FromFieldInstance for Option<T> {
+    fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+        if field_instance.is_null() {
+            Ok(None)
+        } else {
+            T::from_field_instance(field_instance).map(Some)
+        }
+    }
+
+    fn from_missing_field_instance() -> Option<Self> {
+        Some(None)
+    }
+
+    // No from_default_value: a null field instance was explicitly left null, so it stays `None`
+    // rather than taking the definition's default.
+}
+
+macro_rules! from_field_instance_type {
+    ($ty:ty, $($variant:ident)|+, $expected:literal) => {
+        from_field_instance_type!($ty, $($variant)|+, $expected, _default_value => None);
+    };
+    ($ty:ty, $($variant:ident)|+, $expected:literal, $default_value:pat => $from_default:expr) => {
+        impl FromFieldInstance for $ty {
+            fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+                match &field_instance.field_instance_type {
+                    $(FieldInstanceType::$variant(value))|+ => Ok(value.clone()),
+                    other => Err(LdtkError::FieldInstanceTypeError {
+                        expected: $expected,
+                        given: other.type_name(),
+                    }),
+                }
+            }
+
+            fn from_default_value(default_value: &FieldDefaultValue) -> Option<LdtkResult<Self>> {
+                match default_value {
+                    $default_value => $from_default,
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+from_field_instance_type!(i64, Int, "Int", FieldDefaultValue::Int(value) => Some(Ok(*value)));
+from_field_instance_type!(f64, Float, "Float", FieldDefaultValue::Float(value) => Some(Ok(*value)));
+from_field_instance_type!(bool, Bool, "Bool", FieldDefaultValue::Bool(value) => Some(Ok(*value)));
+from_field_instance_type!(
+    String,
+    String | Multilines,
+    "String",
+    FieldDefaultValue::String(value) => Some(Ok(value.clone()))
+);
+from_field_instance_type!(PathBuf, FilePath, "FilePath");
+from_field_instance_type!(
+    Color,
+    Color,
+    "Color",
+    FieldDefaultValue::Int(value) => Some(Ok(bevy_color_from_ldtk_int(*value)))
+);
+from_field_instance_type!(I64Vec2, Point, "Point");
+from_field_instance_type!(TilesetRectangle, Tile, "Tile");
+from_field_instance_type!(EntityRef, EntityRef, "EntityRef");
+from_field_instance_type!(EnumValue, Enum, "Enum");
+
+macro_rules! from_field_instance_int {
+    ($($ty:ty),+) => {
+        $(
+            impl FromFieldInstance for $ty {
+                fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+                    Ok(i64::from_field_instance(field_instance)?.try_into()?)
+                }
+
+                fn from_default_value(
+                    default_value: &FieldDefaultValue,
+                ) -> Option<LdtkResult<Self>> {
+                    i64::from_default_value(default_value)
+                        .map(|value| Ok(value?.try_into()?))
+                }
+            }
+        )+
+    };
+}
+
+from_field_instance_int!(i8, i16, i32, isize, u8, u16, u32, u64, usize);
+
+impl FromFieldInstance for f32 {
+    fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+        Ok(f64::from_field_instance(field_instance)? as Self)
+    }
+
+    fn from_default_value(default_value: &FieldDefaultValue) -> Option<LdtkResult<Self>> {
+        f64::from_default_value(default_value).map(|value| Ok(value? as Self))
+    }
+}
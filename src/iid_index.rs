@@ -0,0 +1,98 @@
+//! An index from LDtk [Iid]s to the Bevy entities spawned for them.
+//!
+//! The [crate::spawn] subsystem inserts an [LdtkIid] component onto every entity it spawns for a
+//! [crate::world::World], [crate::level::Level], [crate::layer::LayerInstance] and
+//! [crate::entity::EntityInstance]. Component hooks on [LdtkIid] keep the [LdtkIidIndex] resource
+//! up to date as these entities are spawned and despawned.
+//!
+//! This allows an [EntityRef] field to be resolved to the live Bevy entity directly:
+//!
+//! ```ignore
+//! fn open_doors(doors: Query<&Door>, iid_index: Res<LdtkIidIndex>) {
+//!     for door in &doors {
+//!         if let Some(switch) = iid_index.get_entity_ref(&door.switch) {
+//!             // ...
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! If the same [crate::project::Project] is spawned more than once, the index points at the most
+//! recently spawned entity for each [Iid].
+
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::lifecycle::HookContext;
+use bevy_ecs::resource::Resource;
+use bevy_ecs::world::DeferredWorld;
+use bevy_reflect::Reflect;
+
+use crate::field_instance::EntityRef;
+use crate::iid::{Iid, IidMap};
+
+/// The [Iid] of the LDtk asset a spawned entity was created from.
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq, Reflect)]
+#[component(on_insert = on_insert_ldtk_iid, on_replace = on_replace_ldtk_iid)]
+pub struct LdtkIid(pub Iid);
+
+/// Maps the [Iid] of every spawned LDtk asset to its Bevy [Entity].
+#[derive(Debug, Default, Resource)]
+pub struct LdtkIidIndex {
+    entities: IidMap<Entity>,
+}
+
+impl LdtkIidIndex {
+    /// Returns the entity spawned for the given [Iid], if there is one.
+    pub fn get(&self, iid: Iid) -> Option<Entity> {
+        self.entities.get(&iid).copied()
+    }
+
+    /// Returns the entity spawned for the target of the given [EntityRef], if there is one.
+    pub fn get_entity_ref(&self, entity_ref: &EntityRef) -> Option<Entity> {
+        self.get(entity_ref.entity_iid)
+    }
+
+    /// Returns `true` if an entity is currently spawned for the given [Iid].
+    pub fn contains(&self, iid: Iid) -> bool {
+        self.entities.contains_key(&iid)
+    }
+
+    /// Iterates over every indexed [Iid] and its entity.
+    pub fn iter(&self) -> impl Iterator<Item = (Iid, Entity)> + '_ {
+        self.entities.iter().map(|(iid, entity)| (*iid, *entity))
+    }
+
+    /// The number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if no entities are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+fn on_insert_ldtk_iid(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    let Some(&LdtkIid(iid)) = world.get::<LdtkIid>(entity) else {
+        return;
+    };
+
+    if let Some(mut iid_index) = world.get_resource_mut::<LdtkIidIndex>() {
+        iid_index.entities.insert(iid, entity);
+    }
+}
+
+fn on_replace_ldtk_iid(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    let Some(&LdtkIid(iid)) = world.get::<LdtkIid>(entity) else {
+        return;
+    };
+
+    // Only remove the entry if it still points at this entity, as a newer spawn of the same asset
+    // may have replaced it.
+    if let Some(mut iid_index) = world.get_resource_mut::<LdtkIidIndex>()
+        && iid_index.entities.get(&iid) == Some(&entity)
+    {
+        iid_index.entities.remove(&iid);
+    }
+}
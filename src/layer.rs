@@ -35,6 +35,10 @@ use crate::uid::Uid;
 pub struct EntitiesLayer {
     /// Handles pointing to the [crate::entity::EntityInstance] instances which
     /// belong to this layer.
+    ///
+    /// NOTE: There is no meaning to the order of the objects within this field. If the order of
+    /// the entities is needed, the [crate::entity::EntityInstance::index] field represents the
+    /// order of the entity within the layer.
     pub entities: IidMap<Handle<EntityInstance>>,
 }
 
@@ -45,30 +49,20 @@ impl EntitiesLayer {
         project_context: Arc<RwLock<ProjectContext<'_>>>,
         load_context: Arc<Mutex<&mut LoadContext<'_>>>,
     ) -> LdtkResult<Self> {
-        macro_rules! should_be {
-            ($field:ident, $discr:ident) => {
-                (entities_layer_json.$field.$discr())
-                    .then(|| ())
-                    .ok_or(ldtk_import_error!(
-                        "Entity Layer with values in {}?",
-                        stringify!($field)
-                    ))
-            };
-        }
+        Self::check_json(&entities_layer_json)?;
 
-        should_be!(int_grid_csv, is_empty)?;
-        should_be!(grid_tiles, is_empty)?;
-        should_be!(tileset_rel_path, is_none)?;
-        should_be!(tileset_def_uid, is_none)?;
+        let load_entities = project_context.read()?.load_entities;
 
         let entity_handles_iter = entities_layer_json
             .entity_instances
             .into_iter()
-            .map(|value| {
+            .enumerate()
+            .filter(|_| load_entities)
+            .map(|(index, value)| {
                 let project_context = project_context.clone();
                 let load_context = load_context.clone();
                 async move {
-                    let entity = EntityInstance::new(value, project_context).await?;
+                    let entity = EntityInstance::new(value, index, project_context).await?;
 
                     let iid = entity.iid;
 
@@ -91,6 +85,27 @@ impl EntitiesLayer {
 
         Ok(Self { entities })
     }
+
+    // Shared with [crate::ldtk_data].
+    pub(crate) fn check_json(entities_layer_json: &ldtk::LayerInstance) -> LdtkResult<()> {
+        macro_rules! should_be {
+            ($field:ident, $discr:ident) => {
+                (entities_layer_json.$field.$discr())
+                    .then(|| ())
+                    .ok_or(ldtk_import_error!(
+                        "Entity Layer with values in {}?",
+                        stringify!($field)
+                    ))
+            };
+        }
+
+        should_be!(int_grid_csv, is_empty)?;
+        should_be!(grid_tiles, is_empty)?;
+        should_be!(tileset_rel_path, is_none)?;
+        should_be!(tileset_def_uid, is_none)?;
+
+        Ok(())
+    }
 }
 
 /// A layer which can optionally contain either [TileInstance]s and/or Int Grid values.
@@ -121,47 +136,21 @@ pub struct TilesLayer {
     pub tileset_definition: Option<Handle<TilesetDefinition>>,
     /// The image that the instances of [TileInstance] in the field [TilesLayer::tiles] are
     /// referring to.
+    ///
+    /// This is always `None` if the project was loaded with
+    /// [crate::project_loader::ProjectLoaderSettings::load_images] disabled.
     pub tileset_image: Option<Handle<Image>>,
 }
 
 impl TilesLayer {
     async fn new(
-        layer_instance_json: ldtk::LayerInstance,
+        mut layer_instance_json: ldtk::LayerInstance,
         project_context: Arc<RwLock<ProjectContext<'_>>>,
         load_context: Arc<Mutex<&mut LoadContext<'_>>>,
     ) -> LdtkResult<Self> {
-        // Check that the entities array is empty.
-        if !layer_instance_json.entity_instances.is_empty() {
-            Err(ldtk_import_error!("Entities layer with Tile data!"))?;
-        }
-
         let int_grid = layer_instance_json.int_grid_csv.clone();
 
-        let layer_instance_type = layer_instance_json.layer_instance_type.as_str();
-
-        let tiles = match (
-            layer_instance_type,
-            layer_instance_json.grid_tiles.len(),
-            layer_instance_json.auto_layer_tiles.len(),
-        ) {
-            // Failure cases.
-            ("Tiles", _, a) if a != 0 => {
-                Err(ldtk_import_error!("auto layer tiles in a Tiles layer?"))?
-            }
-            ("AutoLayer" | "IntGrid", g, _) if g != 0 => Err(ldtk_import_error!(
-                "grid tiles in a {} layer?",
-                layer_instance_type
-            ))?,
-
-            // Good cases.
-            ("Tiles", _, _) => layer_instance_json.grid_tiles.into_iter(),
-            ("AutoLayer" | "IntGrid", _, _) => layer_instance_json.auto_layer_tiles.into_iter(),
-
-            // Anything else should have been filtered by this point, so panic.
-            _ => unreachable!(),
-        }
-        .map(TileInstance::new)
-        .collect::<LdtkResult<_>>()?;
+        let tiles = Self::parse_tiles(&mut layer_instance_json)?;
 
         let tileset_definition = layer_instance_json
             .tileset_def_uid
@@ -177,15 +166,17 @@ impl TilesLayer {
             })
             .transpose()?;
 
-        let tileset_image = if let Some(path) = layer_instance_json.tileset_rel_path {
-            let path = ldtk_path_to_bevy_path(&project_context.read()?.project_directory, path);
+        let load_images = project_context.read()?.load_images;
+        let tileset_image =
+            if let Some(path) = layer_instance_json.tileset_rel_path.filter(|_| load_images) {
+                let path = ldtk_path_to_bevy_path(&project_context.read()?.project_directory, path);
 
-            let handle = load_context.lock().await.load(path);
+                let handle = load_context.lock().await.load(path);
 
-            Some(handle)
-        } else {
-            None
-        };
+                Some(handle)
+            } else {
+                None
+            };
 
         Ok(Self {
             int_grid,
@@ -194,6 +185,46 @@ impl TilesLayer {
             tileset_image,
         })
     }
+
+    // Takes the tiles out of a tiles layer's JSON, checking that it holds no entities, and only
+    // the kind of tiles its layer type should. Shared with [crate::ldtk_data].
+    pub(crate) fn parse_tiles(
+        layer_instance_json: &mut ldtk::LayerInstance,
+    ) -> LdtkResult<Vec<TileInstance>> {
+        // Check that the entities array is empty.
+        if !layer_instance_json.entity_instances.is_empty() {
+            Err(ldtk_import_error!("Entities layer with Tile data!"))?;
+        }
+
+        let layer_instance_type = layer_instance_json.layer_instance_type.as_str();
+
+        match (
+            layer_instance_type,
+            layer_instance_json.grid_tiles.len(),
+            layer_instance_json.auto_layer_tiles.len(),
+        ) {
+            // Failure cases.
+            ("Tiles", _, a) if a != 0 => {
+                Err(ldtk_import_error!("auto layer tiles in a Tiles layer?"))?
+            }
+            ("AutoLayer" | "IntGrid", g, _) if g != 0 => Err(ldtk_import_error!(
+                "grid tiles in a {} layer?",
+                layer_instance_type
+            ))?,
+
+            // Good cases.
+            ("Tiles", _, _) => std::mem::take(&mut layer_instance_json.grid_tiles),
+            ("AutoLayer" | "IntGrid", _, _) => {
+                std::mem::take(&mut layer_instance_json.auto_layer_tiles)
+            }
+
+            // Anything else should have been filtered by this point, so panic.
+            _ => unreachable!(),
+        }
+        .into_iter()
+        .map(TileInstance::new)
+        .collect()
+    }
 }
 
 /// The type of the [LayerInstance], defining what it contains.
@@ -280,6 +311,17 @@ impl LayerType {
     }
 }
 
+// The parts of a layer's JSON which [LayerInstance] does not represent, kept so that
+// [crate::project_saver] can write them back.
+#[derive(Clone, Debug)]
+pub(crate) struct LayerSource {
+    pub(crate) seed: i64,
+    pub(crate) visible: bool,
+    pub(crate) optional_rules: Vec<i64>,
+    // The uid of the rule which placed each auto layer tile, in the order of [TilesLayer::tiles].
+    pub(crate) auto_tile_rules: Vec<Uid>,
+}
+
 /// An asset representing an [LDtk Layer Instance](https://ldtk.io/json/#ldtk-LayerInstanceJson).
 #[derive(Debug, Asset, Reflect)]
 pub struct LayerInstance {
@@ -318,6 +360,9 @@ pub struct LayerInstance {
     /// Index from 0 to (number of layers - 1), in ascending order. When developing a
     /// visualization, higher index values should be above lower ones.
     pub index: usize,
+    // `None` for layers which were not loaded from an LDtk file.
+    #[reflect(ignore)]
+    pub(crate) source: Option<LayerSource>,
 }
 
 impl LayerInstance {
@@ -330,6 +375,17 @@ impl LayerInstance {
     ) -> LdtkResult<Self> {
         let identifier = layer_instance_json.identifier.clone();
 
+        let source = Some(LayerSource {
+            seed: layer_instance_json.seed,
+            visible: layer_instance_json.visible,
+            optional_rules: layer_instance_json.optional_rules.clone(),
+            auto_tile_rules: layer_instance_json
+                .auto_layer_tiles
+                .iter()
+                .filter_map(|tile| tile.d.first().copied())
+                .collect(),
+        });
+
         let grid_size: I64Vec2 = (layer_instance_json.c_wid, layer_instance_json.c_hei).into();
 
         let grid_cell_size = layer_instance_json.grid_size;
@@ -358,14 +414,7 @@ impl LayerInstance {
             })?
             .clone();
 
-        // Sanity check to guarantee that the int_grid size makes sense
-        let int_grid_len = layer_instance_json.int_grid_csv.len();
-        let total_grids = (grid_size.x * grid_size.y) as usize;
-        if int_grid_len != 0 && int_grid_len != total_grids {
-            return Err(ldtk_import_error!(
-                "Bad length for int_grid_csv in layer {identifier}! length:{int_grid_len}"
-            ));
-        }
+        Self::check_int_grid(&layer_instance_json)?;
 
         let layer_type = LayerType::new(
             layer_instance_json,
@@ -386,8 +435,24 @@ impl LayerInstance {
             layer_definition,
             layer_type,
             index,
+            source,
         })
     }
+
+    // Sanity check to guarantee that the int_grid size makes sense. Shared with
+    // [crate::ldtk_data].
+    pub(crate) fn check_int_grid(layer_instance_json: &ldtk::LayerInstance) -> LdtkResult<()> {
+        let int_grid_len = layer_instance_json.int_grid_csv.len();
+        let total_grids = (layer_instance_json.c_wid * layer_instance_json.c_hei) as usize;
+        if int_grid_len != 0 && int_grid_len != total_grids {
+            return Err(ldtk_import_error!(
+                "Bad length for int_grid_csv in layer {}! length:{int_grid_len}",
+                layer_instance_json.identifier
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl LdtkAsset for LayerInstance {
@@ -30,7 +30,7 @@ pub enum LayerDefinitionType {
 }
 
 impl LayerDefinitionType {
-    pub(crate) async fn new(ldtk_type: &str) -> LdtkResult<Self> {
+    pub(crate) fn new(ldtk_type: &str) -> LdtkResult<Self> {
         Ok(match ldtk_type {
             "IntGrid" => Self::IntGrid,
             "Entities" => Self::Entities,
@@ -61,6 +61,8 @@ pub struct LayerDefinition {
     pub grid_cell_size: i64,
     /// User defined unique identifier.
     pub identifier: String,
+    /// The unique identifier of this definition, referenced by layer instances.
+    pub uid: Uid,
     /// For IntGrid types, defines extra metadata for a given IntGrid value.
     pub int_grid_values: UidMap<IntGridValue>,
     /// Goup Information for IntGridValues.
@@ -91,7 +93,8 @@ impl LayerDefinition {
         tileset_definitions: &UidMap<Handle<TilesetDefinition>>,
     ) -> LdtkResult<Self> {
         let identifier = value.identifier;
-        let layer_definition_type = LayerDefinitionType::new(&value.layer_definition_type).await?;
+        let uid = value.uid;
+        let layer_definition_type = LayerDefinitionType::new(&value.layer_definition_type)?;
         let auto_source_layer_def_uid = value.auto_source_layer_def_uid;
         let display_opacity = value.display_opacity;
         let grid_cell_size = value.grid_size;
@@ -124,6 +127,7 @@ impl LayerDefinition {
             display_opacity,
             grid_cell_size,
             identifier,
+            uid,
             int_grid_values,
             int_grid_values_groups,
             parallax_factor,
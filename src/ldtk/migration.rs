@@ -0,0 +1,289 @@
+//! Parsing of LDtk JSON files written by other versions of LDtk than the one our schema was
+//! generated from.
+//!
+//! Files are first parsed into a [Value], and their `jsonVersion` checked against the range this
+//! crate accepts. Then every [Migration] for a version newer than the file is applied in order,
+//! upgrading the JSON in place into the shape expected by [LdtkProject] and [Level], before it is
+//! finally deserialized.
+//!
+//! Supporting an older schema is a matter of appending a [Migration] to [MIGRATIONS].
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use bevy_log::warn;
+use serde_json::{Map, Value};
+
+use crate::ldtk::{LdtkProject, Level};
+use crate::ldtk_import_error;
+use crate::result::LdtkResult;
+
+/// A `major.minor.patch` LDtk JSON version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsonVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl JsonVersion {
+    const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for JsonVersion {
+    type Err = crate::error::LdtkError;
+
+    fn from_str(s: &str) -> LdtkResult<Self> {
+        let mut parts = s.trim().split('.');
+
+        let mut next = || -> LdtkResult<u32> {
+            let part = parts
+                .next()
+                .ok_or_else(|| ldtk_import_error!("Bad LDtk JSON version! given: {s}"))?;
+            Ok(part.parse()?)
+        };
+
+        let version = Self::new(next()?, next()?, next()?);
+
+        if parts.next().is_some() {
+            return Err(ldtk_import_error!("Bad LDtk JSON version! given: {s}"));
+        }
+
+        Ok(version)
+    }
+}
+
+impl Display for JsonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The version our [LdtkProject] schema was generated from.
+pub const SCHEMA_VERSION: JsonVersion = JsonVersion::new(1, 5, 3);
+
+/// The `appBuildId` written by the LDtk release matching [SCHEMA_VERSION].
+pub const SCHEMA_APP_BUILD_ID: f64 = 473_703.0;
+
+/// The oldest version which can be migrated to [SCHEMA_VERSION].
+pub const MINIMUM_VERSION: JsonVersion = JsonVersion::new(1, 4, 0);
+
+/// Upgrades JSON written by a version of LDtk older than [Migration::introduced_in].
+struct Migration {
+    /// The first version of LDtk which writes the new shape.
+    introduced_in: JsonVersion,
+    /// Applied to the project file.
+    project: fn(&mut Value) -> LdtkResult<()>,
+    /// Applied to every level, whether embedded in the project or in an external `.ldtkl` file.
+    level: Option<fn(&mut Value) -> LdtkResult<()>>,
+}
+
+/// Every migration, ordered by [Migration::introduced_in].
+const MIGRATIONS: &[Migration] = &[Migration {
+    introduced_in: JsonVersion::new(1, 5, 0),
+    project: migrate_project_1_5_0,
+    level: None,
+}];
+
+/// Checks that a file written with the given JSON version can be read.
+///
+/// Versions older than [MINIMUM_VERSION], or with a newer major or minor version than
+/// [SCHEMA_VERSION], are rejected. A newer patch version is accepted with a warning, as patch
+/// releases of LDtk have not changed the schema in incompatible ways.
+pub fn check_json_version(json_version: &str) -> LdtkResult<JsonVersion> {
+    let version: JsonVersion = json_version.parse()?;
+
+    let newer_release =
+        (version.major, version.minor).cmp(&(SCHEMA_VERSION.major, SCHEMA_VERSION.minor));
+
+    if version < MINIMUM_VERSION || newer_release == Ordering::Greater {
+        return Err(ldtk_import_error!(
+            "Unsupported LDtk JSON version! expected: {MINIMUM_VERSION} to {}.{}.x given: {version}",
+            SCHEMA_VERSION.major,
+            SCHEMA_VERSION.minor,
+        ));
+    }
+
+    if version > SCHEMA_VERSION {
+        warn!(
+            "LDtk JSON version {version} is newer than {SCHEMA_VERSION}, which this crate was \
+             written against. Loading anyway!"
+        );
+    }
+
+    Ok(version)
+}
+
+/// Parses an LDtk project file, migrating it from older versions if needed.
+pub fn parse_project(bytes: &[u8]) -> LdtkResult<LdtkProject> {
+    let mut value: Value = serde_json::from_slice(bytes)?;
+
+    let json_version = value
+        .get("jsonVersion")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ldtk_import_error!("LDtk project is missing its jsonVersion!"))?;
+    let version = check_json_version(json_version)?;
+
+    migrations_for(version).try_for_each(|migration| {
+        (migration.project)(&mut value)?;
+
+        if let Some(migrate_level) = migration.level {
+            embedded_levels(&mut value).try_for_each(migrate_level)?;
+        }
+
+        Ok::<_, crate::error::LdtkError>(())
+    })?;
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Parses an external `.ldtkl` level file belonging to a project of the given version, migrating
+/// it from older versions if needed.
+pub fn parse_level(bytes: &[u8], version: JsonVersion) -> LdtkResult<Level> {
+    let mut value: Value = serde_json::from_slice(bytes)?;
+
+    migrations_for(version)
+        .filter_map(|migration| migration.level)
+        .try_for_each(|migrate_level| migrate_level(&mut value))?;
+
+    Ok(serde_json::from_value(value)?)
+}
+
+fn migrations_for(version: JsonVersion) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS
+        .iter()
+        .filter(move |migration| version < migration.introduced_in)
+}
+
+// Levels live in the project's `levels` array for single world projects, and in each world's
+// `levels` array for multi world projects.
+fn embedded_levels(project: &mut Value) -> impl Iterator<Item = &mut Value> {
+    project
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|project| project.iter_mut())
+        .flat_map(|(key, value)| -> Vec<&mut Value> {
+            match key.as_str() {
+                "levels" => value.as_array_mut().into_iter().flatten().collect(),
+                "worlds" => value
+                    .as_array_mut()
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|world| array_mut(world, "levels"))
+                    .collect(),
+                _ => Vec::new(),
+            }
+        })
+}
+
+fn insert_missing(object: &mut Value, key: &str, default: Value) {
+    if let Some(object) = object.as_object_mut() {
+        object.entry(key).or_insert(default);
+    }
+}
+
+fn array_mut<'a>(object: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    object
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+// 1.5.0 introduced biomes and random tile offsets for auto layer rules, replaced the tile ids of
+// auto layer rules with rectangles of tile ids, and replaced the table of contents `instances`
+// with `instancesData`, which also carries the location, size and exported fields of each
+// instance.
+fn migrate_project_1_5_0(project: &mut Value) -> LdtkResult<()> {
+    if let Some(defs) = project.get_mut("defs") {
+        array_mut(defs, "layers").try_for_each(|layer| {
+            insert_missing(layer, "biomeFieldUid", Value::Null);
+
+            array_mut(layer, "autoRuleGroups").try_for_each(|group| {
+                insert_missing(group, "biomeRequirementMode", Value::from(0));
+                insert_missing(group, "requiredBiomeValues", Value::Array(vec![]));
+
+                array_mut(group, "rules").try_for_each(migrate_auto_rule_1_5_0)
+            })
+        })?;
+    }
+
+    array_mut(project, "toc").try_for_each(|entry| {
+        if entry.get("instancesData").is_some() {
+            return Ok(());
+        }
+
+        // The location and size of each instance are unknown before 1.5.0.
+        let instances_data = array_mut(entry, "instances")
+            .map(|iids| {
+                let mut instance_data = Map::new();
+                instance_data.insert("iids".to_string(), iids.clone());
+                instance_data.insert("fields".to_string(), Value::Null);
+                ["worldX", "worldY", "widPx", "heiPx"]
+                    .into_iter()
+                    .for_each(|key| {
+                        instance_data.insert(key.to_string(), Value::from(0));
+                    });
+                Value::Object(instance_data)
+            })
+            .collect();
+
+        entry
+            .as_object_mut()
+            .ok_or_else(|| ldtk_import_error!("Table of contents entry is not an object!"))?
+            .insert("instancesData".to_string(), Value::Array(instances_data));
+
+        Ok(())
+    })
+}
+
+// Before 1.5.0, a rule held a flat list of tile ids. In `Single` mode one of them is picked at
+// random, so each becomes a rectangle of its own. In `Stamp` mode they are all drawn together, so
+// they become a single rectangle.
+fn migrate_auto_rule_1_5_0(rule: &mut Value) -> LdtkResult<()> {
+    [
+        "tileRandomXMin",
+        "tileRandomXMax",
+        "tileRandomYMin",
+        "tileRandomYMax",
+    ]
+    .into_iter()
+    .for_each(|key| insert_missing(rule, key, Value::from(0)));
+
+    if rule.get("tileRectsIds").is_some() {
+        return Ok(());
+    }
+
+    let tile_ids = rule
+        .get("tileIds")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let tile_rects_ids = match rule.get("tileMode").and_then(Value::as_str) {
+        Some("Single") => tile_ids
+            .into_iter()
+            .map(|tile_id| Value::Array(vec![tile_id]))
+            .collect(),
+        Some("Stamp") if tile_ids.is_empty() => Vec::new(),
+        Some("Stamp") => vec![Value::Array(tile_ids)],
+        tile_mode => {
+            return Err(ldtk_import_error!(
+                "Bad tile mode for auto layer rule! given: {tile_mode:?}"
+            ));
+        }
+    };
+
+    rule.as_object_mut()
+        .ok_or_else(|| ldtk_import_error!("Auto layer rule is not an object!"))?
+        .insert("tileRectsIds".to_string(), Value::Array(tile_rects_ids));
+
+    Ok(())
+}
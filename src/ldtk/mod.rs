@@ -4,3 +4,5 @@
 pub mod ldtk_json_1_5_3;
 
 pub use ldtk_json_1_5_3::*;
+
+pub mod migration;
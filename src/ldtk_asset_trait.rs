@@ -2,6 +2,10 @@
 use bevy_asset::{Asset, Handle};
 
 // use crate::field_instance::FieldInstance;
+use crate::field_definition::FieldDefinition;
+use crate::from_field_instance::{FromFieldInstance, extract_field};
+use crate::result::LdtkResult;
+use crate::uid::Uid;
 use crate::{field_instance::FieldInstance, iid::Iid};
 
 /// Trait representing the assets which would exist in an LDtk project world, such as:
@@ -31,6 +35,43 @@ pub trait LdtkAssetWithChildren<ChildAsset: LdtkAsset>: LdtkAsset {
 #[allow(missing_docs)]
 pub trait LdtkAssetWithFieldInstances: LdtkAsset {
     fn get_field_instance(&self, identifier: &str) -> Option<&FieldInstance>;
+
+    /// The [FieldDefinition] of the field instance with the given identifier.
+    fn get_field_instance_definition(&self, identifier: &str) -> Option<&FieldDefinition> {
+        self.get_field_instance(identifier)
+            .map(|field_instance| field_instance.field_definition.as_ref())
+    }
+
+    /// Extracts the field instance with the given identifier as a `T`.
+    ///
+    /// See [crate::from_field_instance] for the conversions available.
+    fn get_field<T: FromFieldInstance>(&self, identifier: &str) -> LdtkResult<T> {
+        extract_field(
+            identifier,
+            self.get_field_instance(identifier),
+            self.get_field_instance_definition(identifier),
+        )
+    }
+}
+
+/// Trait representing assets which declare field definitions. The following types use this:
+/// - [crate::entity_definition::EntityDefinition], for the fields of its entity instances
+/// - [crate::project::Project], for the fields of its levels
+pub trait LdtkAssetWithFieldDefinitions: Asset {
+    fn get_field_definitions(&self) -> &[FieldDefinition];
+
+    /// Resolves a [FieldInstance::def_uid] to its [FieldDefinition].
+    fn get_field_definition(&self, uid: Uid) -> Option<&FieldDefinition> {
+        self.get_field_definitions()
+            .iter()
+            .find(|field_definition| field_definition.uid == uid)
+    }
+
+    fn get_field_definition_by_identifier(&self, identifier: &str) -> Option<&FieldDefinition> {
+        self.get_field_definitions()
+            .iter()
+            .find(|field_definition| field_definition.identifier == identifier)
+    }
 }
 
 /// Trait representing assets with a `tags` field. The following types use this:
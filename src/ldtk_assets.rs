@@ -0,0 +1,68 @@
+//! A [SystemParam] for convenient access to the LDtk asset collections.
+
+use bevy_asset::Assets;
+use bevy_ecs::system::{Res, SystemParam};
+
+use crate::entity::EntityInstance;
+use crate::entity_definition::EntityDefinition;
+use crate::layer::LayerInstance;
+use crate::layer_definition::LayerDefinition;
+use crate::ldtk_asset_trait::LdtkAssetWithChildren;
+use crate::level::Level;
+use crate::project::Project;
+use crate::tileset_definition::TilesetDefinition;
+use crate::world::World;
+
+/// Read only access to every asset collection which makes up the LDtk world hierarchy.
+///
+/// [Project] -> [World] -> [Level] -> [LayerInstance] -> [EntityInstance]
+///
+/// Along with the definitions the instances refer to.
+#[allow(missing_docs)]
+#[derive(SystemParam)]
+pub struct LdtkAssets<'w> {
+    pub projects: Res<'w, Assets<Project>>,
+    pub worlds: Res<'w, Assets<World>>,
+    pub levels: Res<'w, Assets<Level>>,
+    pub layers: Res<'w, Assets<LayerInstance>>,
+    pub entities: Res<'w, Assets<EntityInstance>>,
+    pub tileset_definitions: Res<'w, Assets<TilesetDefinition>>,
+    pub layer_definitions: Res<'w, Assets<LayerDefinition>>,
+    pub entity_definitions: Res<'w, Assets<EntityDefinition>>,
+}
+
+impl LdtkAssets<'_> {
+    /// Returns `true` if every [World], [Level], [LayerInstance] and [EntityInstance] beneath the
+    /// given [Project] is present in its asset collection.
+    ///
+    /// Dependencies such as images are not considered.
+    pub fn is_project_loaded(&self, project: &Project) -> bool {
+        project.worlds.values().all(|handle| {
+            self.worlds
+                .get(handle.id())
+                .is_some_and(|world| self.is_world_loaded(world))
+        })
+    }
+
+    /// Returns `true` if every [Level], [LayerInstance] and [EntityInstance] beneath the given
+    /// [World] is present in its asset collection.
+    pub fn is_world_loaded(&self, world: &World) -> bool {
+        world.get_children().all(|handle| {
+            self.levels
+                .get(handle.id())
+                .is_some_and(|level| self.is_level_loaded(level))
+        })
+    }
+
+    /// Returns `true` if every [LayerInstance] and [EntityInstance] beneath the given [Level] is
+    /// present in its asset collection.
+    pub fn is_level_loaded(&self, level: &Level) -> bool {
+        level.get_children().all(|handle| {
+            self.layers.get(handle.id()).is_some_and(|layer| {
+                layer
+                    .get_children()
+                    .all(|handle| self.entities.contains(handle.id()))
+            })
+        })
+    }
+}
@@ -0,0 +1,299 @@
+use std::path::PathBuf;
+
+use bevy_color::Color;
+use bevy_math::{DVec2, I64Vec2};
+use bevy_platform::collections::HashMap;
+use bevy_sprite::Anchor;
+
+use crate::anchor::bevy_anchor_from_ldtk;
+use crate::color::{bevy_color_from_ldtk_int, bevy_color_from_ldtk_string};
+use crate::entity_definition::TileRenderMode;
+use crate::field_definition::FieldDefinition;
+use crate::layer_definition::{IntGridValuesGroup, LayerDefinitionType};
+use crate::ldtk;
+use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_bevy_path;
+use crate::result::LdtkResult;
+use crate::tileset_definition::TilesetDefinition;
+use crate::uid::{Uid, UidMap};
+
+use super::DataContext;
+
+/// The data equivalent of [crate::tileset_rectangle::TilesetRectangle].
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TilesetRectangleData {
+    pub corner: I64Vec2,
+    pub size: I64Vec2,
+    /// The [Uid] of the [TilesetDefinitionData] this region is taken from.
+    pub tileset_definition: Uid,
+}
+
+impl TilesetRectangleData {
+    pub(crate) fn new(
+        value: ldtk::TilesetRectangle,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        if !data_context.tileset_uids.contains(&value.tileset_uid) {
+            return Err(ldtk_import_error!(
+                "Bad tileset definition uid! given: {}",
+                value.tileset_uid
+            ));
+        }
+
+        Ok(Self {
+            corner: (value.x, value.y).into(),
+            size: (value.w, value.h).into(),
+            tileset_definition: value.tileset_uid,
+        })
+    }
+}
+
+/// The data equivalent of [crate::tileset_definition::TilesetDefinition].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct TilesetDefinitionData {
+    pub tile_grid_size: I64Vec2,
+    pub custom_data: HashMap<i64, String>,
+    pub enum_tags: HashMap<String, Vec<i64>>,
+    pub identifier: String,
+    pub uid: Uid,
+    pub padding: i64,
+    pub spacing: i64,
+    pub tileset_image_size: I64Vec2,
+    /// The path of the tileset's image, relative to the directory given to
+    /// [super::LdtkData::from_bytes].
+    ///
+    /// This is `None` for tilesets without an image, and for those using LDtk's internal atlas.
+    pub tileset_image_path: Option<PathBuf>,
+    /// `true` if this tileset uses LDtk's internal icon atlas.
+    pub embed_atlas: bool,
+    pub tags: Vec<String>,
+    pub tags_source_enum_uid: Option<Uid>,
+    pub tile_grid_pixel_size: i64,
+}
+
+impl TilesetDefinitionData {
+    pub(crate) fn new(
+        value: ldtk::TilesetDefinition,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let tileset_image_path = value
+            .rel_path
+            .filter(|_| value.embed_atlas.is_none())
+            .map(|rel_path| ldtk_path_to_bevy_path(&data_context.project_directory, rel_path));
+
+        Ok(Self {
+            tile_grid_size: (value.c_wid, value.c_hei).into(),
+            custom_data: TilesetDefinition::parse_custom_data(value.custom_data),
+            enum_tags: TilesetDefinition::parse_enum_tags(value.enum_tags),
+            identifier: value.identifier,
+            uid: value.uid,
+            padding: value.padding,
+            spacing: value.spacing,
+            tileset_image_size: (value.px_wid, value.px_hei).into(),
+            tileset_image_path,
+            embed_atlas: value.embed_atlas.is_some(),
+            tags: value.tags,
+            tags_source_enum_uid: value.tags_source_enum_uid,
+            tile_grid_pixel_size: value.tile_grid_size,
+        })
+    }
+}
+
+/// The data equivalent of [crate::layer_definition::IntGridValue].
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct IntGridValueData {
+    pub color: Color,
+    pub group_uid: Uid,
+    pub identifier: Option<String>,
+    pub tile: Option<TilesetRectangleData>,
+    pub value: i64,
+}
+
+/// The data equivalent of [crate::layer_definition::LayerDefinition].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct LayerDefinitionData {
+    pub layer_definition_type: LayerDefinitionType,
+    pub auto_source_layer_def_uid: Option<Uid>,
+    pub display_opacity: f64,
+    pub grid_cell_size: i64,
+    pub identifier: String,
+    pub uid: Uid,
+    pub int_grid_values: UidMap<IntGridValueData>,
+    pub int_grid_values_groups: UidMap<IntGridValuesGroup>,
+    pub parallax_factor: DVec2,
+    pub parallax_scaling: bool,
+    pub offset: I64Vec2,
+    /// The [Uid] of the [TilesetDefinitionData] assigned to this layer, if any.
+    pub tileset_definition: Option<Uid>,
+}
+
+impl LayerDefinitionData {
+    pub(crate) fn new(
+        value: ldtk::LayerDefinition,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let int_grid_values = value
+            .int_grid_values
+            .into_iter()
+            .map(|int_grid_value| {
+                let int_grid_value_data = IntGridValueData {
+                    color: bevy_color_from_ldtk_string(&int_grid_value.color)?,
+                    group_uid: int_grid_value.group_uid,
+                    identifier: int_grid_value.identifier,
+                    tile: int_grid_value
+                        .tile
+                        .map(|tile| TilesetRectangleData::new(tile, data_context))
+                        .transpose()?,
+                    value: int_grid_value.value,
+                };
+                Ok((int_grid_value.value, int_grid_value_data))
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        let int_grid_values_groups = value
+            .int_grid_values_groups
+            .into_iter()
+            .map(|value| Ok((value.uid, IntGridValuesGroup::new(value)?)))
+            .collect::<LdtkResult<_>>()?;
+
+        if let Some(uid) = value.tileset_def_uid
+            && !data_context.tileset_uids.contains(&uid)
+        {
+            return Err(ldtk_import_error!("Bad uid! {uid}"));
+        }
+
+        Ok(Self {
+            layer_definition_type: LayerDefinitionType::new(&value.layer_definition_type)?,
+            auto_source_layer_def_uid: value.auto_source_layer_def_uid,
+            display_opacity: value.display_opacity,
+            grid_cell_size: value.grid_size,
+            identifier: value.identifier,
+            uid: value.uid,
+            int_grid_values,
+            int_grid_values_groups,
+            parallax_factor: (value.parallax_factor_x, value.parallax_factor_y).into(),
+            parallax_scaling: value.parallax_scaling,
+            offset: (value.px_offset_x, value.px_offset_y).into(),
+            tileset_definition: value.tileset_def_uid,
+        })
+    }
+}
+
+/// The data equivalent of [crate::entity_definition::EntityDefinition].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct EntityDefinitionData {
+    pub identifier: String,
+    pub uid: Uid,
+    pub color: Color,
+    pub size: I64Vec2,
+    pub anchor: Anchor,
+    pub tile: Option<TilesetRectangleData>,
+    pub ui_tile: Option<TilesetRectangleData>,
+    pub render_mode: TileRenderMode,
+    pub field_definitions: Vec<FieldDefinition>,
+}
+
+impl EntityDefinitionData {
+    pub(crate) fn new(
+        value: ldtk::EntityDefinition,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        Ok(Self {
+            color: bevy_color_from_ldtk_string(&value.color)?,
+            size: (value.width, value.height).into(),
+            anchor: bevy_anchor_from_ldtk(&[value.pivot_x, value.pivot_y])?,
+            tile: value
+                .tile_rect
+                .map(|tile| TilesetRectangleData::new(tile, data_context))
+                .transpose()?,
+            ui_tile: value
+                .ui_tile_rect
+                .map(|tile| TilesetRectangleData::new(tile, data_context))
+                .transpose()?,
+            render_mode: TileRenderMode::new(
+                &value.tile_render_mode,
+                value.nine_slice_borders.as_slice(),
+            )?,
+            field_definitions: value
+                .field_defs
+                .into_iter()
+                .map(FieldDefinition::new)
+                .collect::<LdtkResult<_>>()?,
+            identifier: value.identifier,
+            uid: value.uid,
+        })
+    }
+}
+
+/// The data equivalent of [crate::enum_definition::EnumValueDefinition].
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct EnumValueDefinitionData {
+    pub color: Color,
+    pub id: String,
+    pub tile: Option<TilesetRectangleData>,
+}
+
+/// The data equivalent of [crate::enum_definition::EnumDefinition].
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct EnumDefinitionData {
+    pub identifier: String,
+    /// The path of the file this enum was imported from, relative to the directory given to
+    /// [super::LdtkData::from_bytes].
+    pub external_rel_path: Option<PathBuf>,
+    /// The [Uid] of the [TilesetDefinitionData] holding this enum's icons, if any.
+    pub icon_tileset_definition: Option<Uid>,
+    pub tags: Vec<String>,
+    pub values: HashMap<String, EnumValueDefinitionData>,
+}
+
+impl EnumDefinitionData {
+    pub(crate) fn new(
+        value: ldtk::EnumDefinition,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        if let Some(uid) = value.icon_tileset_uid
+            && !data_context.tileset_uids.contains(&uid)
+        {
+            return Err(ldtk_import_error!("bad tileset definition uid! {}", uid));
+        }
+
+        let values = value
+            .values
+            .into_iter()
+            .map(|value| {
+                let enum_value_definition = EnumValueDefinitionData {
+                    color: bevy_color_from_ldtk_int(value.color),
+                    tile: value
+                        .tile_rect
+                        .map(|tile| TilesetRectangleData::new(tile, data_context))
+                        .transpose()?,
+                    id: value.id.clone(),
+                };
+                Ok((value.id, enum_value_definition))
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        Ok(Self {
+            identifier: value.identifier,
+            external_rel_path: value
+                .external_rel_path
+                .map(|path| ldtk_path_to_bevy_path(&data_context.project_directory, path)),
+            icon_tileset_definition: value.icon_tileset_uid,
+            tags: value.tags,
+            values,
+        })
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use bevy_color::Color;
+use bevy_math::I64Vec2;
+
+use crate::field_instance::FieldInstanceType;
+use crate::iid::Iid;
+use crate::ldtk;
+use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_bevy_path;
+use crate::result::LdtkResult;
+use crate::uid::Uid;
+
+use super::DataContext;
+use super::definitions::TilesetRectangleData;
+
+/// The data equivalent of [crate::field_instance::EntityRef].
+///
+/// Look the entity up with [super::LdtkData::entity].
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntityRefData {
+    pub entity_iid: Iid,
+    pub layer_iid: Iid,
+    pub level_iid: Iid,
+    pub world_iid: Iid,
+}
+
+/// The data equivalent of [crate::field_instance::EnumValue].
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumValueData {
+    pub value: String,
+    pub enum_identifier: String,
+}
+
+/// The data equivalent of [FieldInstanceType].
+///
+/// As there, elements of an array are `None` where they were left null in LDtk.
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub enum FieldInstanceTypeData {
+    ArrayBool(Vec<Option<bool>>),
+    ArrayColor(Vec<Option<Color>>),
+    ArrayEntityRef(Vec<Option<EntityRefData>>),
+    ArrayEnum(Vec<Option<EnumValueData>>),
+    ArrayFilePath(Vec<Option<PathBuf>>),
+    ArrayFloat(Vec<Option<f64>>),
+    ArrayInt(Vec<Option<i64>>),
+    ArrayMultilines(Vec<Option<String>>),
+    ArrayPoint(Vec<Option<I64Vec2>>),
+    ArrayString(Vec<Option<String>>),
+    ArrayTile(Vec<Option<TilesetRectangleData>>),
+    Bool(bool),
+    Color(Color),
+    EntityRef(EntityRefData),
+    Enum(EnumValueData),
+    FilePath(PathBuf),
+    Float(f64),
+    Int(i64),
+    Multilines(String),
+    /// A field which was left null in LDtk, holding its declared type.
+    Null(String),
+    Point(I64Vec2),
+    String(String),
+    Tile(TilesetRectangleData),
+}
+
+impl FieldInstanceTypeData {
+    fn new(
+        field_instance_type: &str,
+        value: Option<&serde_json::Value>,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let Some(value) = value.filter(|value| !value.is_null()) else {
+            return Ok(Self::Null(field_instance_type.to_string()));
+        };
+
+        macro_rules! array {
+            ($parse:expr) => {
+                FieldInstanceType::parse_array(field_instance_type, value, $parse)?
+            };
+        }
+
+        Ok(match field_instance_type {
+            "Array<Bool>" => Self::ArrayBool(array!(FieldInstanceType::parse_bool)),
+            "Array<Color>" => Self::ArrayColor(array!(FieldInstanceType::parse_color)),
+            "Array<EntityRef>" => Self::ArrayEntityRef(array!(Self::parse_entity_ref)),
+            "Array<FilePath>" => Self::ArrayFilePath(array!(|value| {
+                Self::parse_file_path(value, data_context)
+            })),
+            "Array<Float>" => Self::ArrayFloat(array!(FieldInstanceType::parse_float)),
+            "Array<Int>" => Self::ArrayInt(array!(FieldInstanceType::parse_int)),
+            "Array<Multilines>" => Self::ArrayMultilines(array!(FieldInstanceType::parse_string)),
+            "Array<Point>" => Self::ArrayPoint(array!(FieldInstanceType::parse_point)),
+            "Array<String>" => Self::ArrayString(array!(FieldInstanceType::parse_string)),
+            "Array<Tile>" => Self::ArrayTile(array!(|value| Self::parse_tile(value, data_context))),
+            "Bool" => Self::Bool(FieldInstanceType::parse_bool(value)?),
+            "Color" => Self::Color(FieldInstanceType::parse_color(value)?),
+            "EntityRef" => Self::EntityRef(Self::parse_entity_ref(value)?),
+            "FilePath" => Self::FilePath(Self::parse_file_path(value, data_context)?),
+            "Float" => Self::Float(FieldInstanceType::parse_float(value)?),
+            "Int" => Self::Int(FieldInstanceType::parse_int(value)?),
+            "Multilines" => Self::Multilines(FieldInstanceType::parse_string(value)?),
+            "Point" => Self::Point(FieldInstanceType::parse_point(value)?),
+            "String" => Self::String(FieldInstanceType::parse_string(value)?),
+            "Tile" => Self::Tile(Self::parse_tile(value, data_context)?),
+            _ => {
+                let (enum_identifier, is_array) =
+                    FieldInstanceType::split_enum_field_type(field_instance_type)?;
+
+                if !data_context.enum_identifiers.contains(enum_identifier) {
+                    return Err(ldtk_import_error!(
+                        "bad enum identifier! {}",
+                        enum_identifier
+                    ));
+                }
+
+                let parse_enum = |value: &serde_json::Value| {
+                    LdtkResult::Ok(EnumValueData {
+                        value: FieldInstanceType::parse_string(value)?,
+                        enum_identifier: enum_identifier.to_string(),
+                    })
+                };
+
+                if is_array {
+                    Self::ArrayEnum(array!(parse_enum))
+                } else {
+                    Self::Enum(parse_enum(value)?)
+                }
+            }
+        })
+    }
+
+    fn parse_entity_ref(value: &serde_json::Value) -> LdtkResult<EntityRefData> {
+        let entity_ref =
+            serde_json::from_value::<ldtk::ReferenceToAnEntityInstance>(value.clone())?;
+
+        Ok(EntityRefData {
+            entity_iid: Iid::from_str(&entity_ref.entity_iid)?,
+            layer_iid: Iid::from_str(&entity_ref.layer_iid)?,
+            level_iid: Iid::from_str(&entity_ref.level_iid)?,
+            world_iid: Iid::from_str(&entity_ref.world_iid)?,
+        })
+    }
+
+    fn parse_file_path(
+        value: &serde_json::Value,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<PathBuf> {
+        Ok(ldtk_path_to_bevy_path(
+            &data_context.project_directory,
+            FieldInstanceType::parse_string(value)?,
+        ))
+    }
+
+    fn parse_tile(
+        value: &serde_json::Value,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<TilesetRectangleData> {
+        let value = serde_json::from_value::<ldtk::TilesetRectangle>(value.clone())?;
+        TilesetRectangleData::new(value, data_context)
+    }
+}
+
+/// The data equivalent of [crate::field_instance::FieldInstance].
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct FieldInstanceData {
+    pub tileset_rectangle: Option<TilesetRectangleData>,
+    pub field_instance_type: FieldInstanceTypeData,
+    pub def_uid: Uid,
+}
+
+impl FieldInstanceData {
+    pub(crate) fn new(
+        field_instance_json: ldtk::FieldInstance,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        Ok(Self {
+            tileset_rectangle: field_instance_json
+                .tile
+                .map(|tile| TilesetRectangleData::new(tile, data_context))
+                .transpose()?,
+            field_instance_type: FieldInstanceTypeData::new(
+                &field_instance_json.field_instance_type,
+                field_instance_json.value.as_ref(),
+                data_context,
+            )?,
+            def_uid: field_instance_json.def_uid,
+        })
+    }
+}
@@ -0,0 +1,375 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use bevy_color::Color;
+use bevy_math::{DVec2, I64Vec2};
+use bevy_platform::collections::HashMap;
+use bevy_sprite::Anchor;
+
+use crate::anchor::bevy_anchor_from_ldtk;
+use crate::color::bevy_color_from_ldtk_string;
+use crate::entity::EntityInstance;
+use crate::iid::Iid;
+use crate::layer::{EntitiesLayer, LayerInstance, TilesLayer};
+use crate::ldtk;
+use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_bevy_path;
+use crate::level::{Level, LevelBackground, Neighbour};
+use crate::result::LdtkResult;
+use crate::tile_instance::TileInstance;
+use crate::uid::Uid;
+use crate::world::WorldLayout;
+
+use super::DataContext;
+use super::definitions::TilesetRectangleData;
+use super::field_instance::FieldInstanceData;
+
+fn construct_field_instances(
+    field_instances_json: Vec<ldtk::FieldInstance>,
+    data_context: &DataContext<'_>,
+) -> LdtkResult<HashMap<String, FieldInstanceData>> {
+    field_instances_json
+        .into_iter()
+        .map(|value| {
+            let identifier = value.identifier.clone();
+            Ok((identifier, FieldInstanceData::new(value, data_context)?))
+        })
+        .collect()
+}
+
+/// The data equivalent of [crate::world::World].
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct WorldData {
+    pub identifier: String,
+    pub iid: Iid,
+    pub world_layout: WorldLayout,
+    /// The levels of this world, in the order they appear in LDtk.
+    pub levels: Vec<LevelData>,
+}
+
+impl WorldData {
+    pub(crate) fn new(world_json: ldtk::World, levels: Vec<LevelData>) -> LdtkResult<Self> {
+        Ok(Self {
+            identifier: world_json.identifier,
+            iid: Iid::from_str(&world_json.iid)?,
+            world_layout: WorldLayout::new(
+                &world_json.world_layout,
+                world_json.world_grid_width,
+                world_json.world_grid_height,
+            )?,
+            levels,
+        })
+    }
+}
+
+/// The data equivalent of [crate::level::LevelBackground].
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct LevelBackgroundData {
+    /// The path of the background image, relative to the directory given to
+    /// [super::LdtkData::from_bytes].
+    pub image_path: PathBuf,
+    pub crop_corner: DVec2,
+    pub crop_size: DVec2,
+    pub scale: DVec2,
+    pub corner: I64Vec2,
+}
+
+/// The data equivalent of [crate::level::Level].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct LevelData {
+    pub bg_color: Color,
+    pub neighbours: Vec<Neighbour>,
+    pub background: Option<LevelBackgroundData>,
+    pub field_instances: HashMap<String, FieldInstanceData>,
+    pub identifier: String,
+    pub iid: Iid,
+    pub size: I64Vec2,
+    pub uid: Uid,
+    pub world_depth: i64,
+    pub location: I64Vec2,
+    /// The layers of this level, from the topmost to the bottommost, as they appear in LDtk.
+    pub layers: Vec<LayerInstanceData>,
+    pub index: usize,
+    /// For projects with external levels, the path of this level's `.ldtkl` file, relative to the
+    /// directory given to [super::LdtkData::from_bytes].
+    pub external_path: Option<PathBuf>,
+}
+
+impl LevelData {
+    pub(crate) fn new(
+        mut level_json: ldtk::Level,
+        index: usize,
+        external_path: Option<PathBuf>,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let background = Level::take_background_json(&mut level_json)?
+            .map(|(bg_pos, bg_rel_path)| {
+                let background = LevelBackground::new(bg_pos, None)?;
+                LdtkResult::Ok(LevelBackgroundData {
+                    image_path: ldtk_path_to_bevy_path(
+                        &data_context.project_directory,
+                        bg_rel_path,
+                    ),
+                    crop_corner: background.crop_corner,
+                    crop_size: background.crop_size,
+                    scale: background.scale,
+                    corner: background.corner,
+                })
+            })
+            .transpose()?;
+
+        let layer_instances = Level::take_layer_instances(&mut level_json)?;
+
+        let num_layers = layer_instances.len();
+
+        let layers = layer_instances
+            .into_iter()
+            .enumerate()
+            .map(|(index, layer_instance_json)| {
+                LayerInstanceData::new(layer_instance_json, num_layers - index - 1, data_context)
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        Ok(Self {
+            bg_color: bevy_color_from_ldtk_string(&level_json.bg_color)?,
+            neighbours: level_json
+                .neighbours
+                .into_iter()
+                .map(Neighbour::new)
+                .collect::<LdtkResult<_>>()?,
+            background,
+            field_instances: construct_field_instances(level_json.field_instances, data_context)?,
+            identifier: level_json.identifier,
+            iid: Iid::from_str(&level_json.iid)?,
+            size: (level_json.px_wid, level_json.px_hei).into(),
+            uid: level_json.uid,
+            world_depth: level_json.world_depth,
+            location: (level_json.world_x, level_json.world_y).into(),
+            layers,
+            index,
+            external_path,
+        })
+    }
+}
+
+/// The data equivalent of [crate::layer::EntitiesLayer].
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct EntitiesLayerData {
+    pub entities: Vec<EntityInstanceData>,
+}
+
+/// The data equivalent of [crate::layer::TilesLayer].
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct TilesLayerData {
+    pub int_grid: Vec<i64>,
+    pub tiles: Vec<TileInstance>,
+    /// The [Uid] of the [super::TilesetDefinitionData] the tiles are taken from, if any.
+    pub tileset_definition: Option<Uid>,
+    /// The path of the image the tiles are taken from, relative to the directory given to
+    /// [super::LdtkData::from_bytes].
+    pub tileset_image_path: Option<PathBuf>,
+}
+
+/// The data equivalent of [crate::layer::LayerType].
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub enum LayerTypeData {
+    Entities(EntitiesLayerData),
+    Tiles(TilesLayerData),
+}
+
+impl LayerTypeData {
+    fn new(
+        mut layer_instance_json: ldtk::LayerInstance,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let layer_instance_type = layer_instance_json.layer_instance_type.as_str();
+        match layer_instance_type {
+            "Entities" => {
+                EntitiesLayer::check_json(&layer_instance_json)?;
+
+                let entities = layer_instance_json
+                    .entity_instances
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| EntityInstanceData::new(value, index, data_context))
+                    .collect::<LdtkResult<_>>()?;
+
+                Ok(Self::Entities(EntitiesLayerData { entities }))
+            }
+            "Tiles" | "AutoLayer" | "IntGrid" => {
+                let tiles = TilesLayer::parse_tiles(&mut layer_instance_json)?;
+
+                if let Some(uid) = layer_instance_json.tileset_def_uid
+                    && !data_context.tileset_uids.contains(&uid)
+                {
+                    return Err(ldtk_import_error!(
+                        "could not find a tileset_definition with uid {uid}!"
+                    ));
+                }
+
+                Ok(Self::Tiles(TilesLayerData {
+                    int_grid: layer_instance_json.int_grid_csv,
+                    tiles,
+                    tileset_definition: layer_instance_json.tileset_def_uid,
+                    tileset_image_path: layer_instance_json
+                        .tileset_rel_path
+                        .map(|path| ldtk_path_to_bevy_path(&data_context.project_directory, path)),
+                }))
+            }
+            unknown => Err(ldtk_import_error!("Unknown layer type! given: {unknown}")),
+        }
+    }
+
+    /// Returns `Some(TilesLayerData)` for [LayerTypeData::Tiles], or `None` otherwise.
+    pub const fn get_tiles_layer(&self) -> Option<&TilesLayerData> {
+        if let Self::Tiles(tiles_layer) = self {
+            Some(tiles_layer)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `Some(EntitiesLayerData)` for [LayerTypeData::Entities], or `None` otherwise.
+    pub const fn get_entities_layer(&self) -> Option<&EntitiesLayerData> {
+        if let Self::Entities(entities_layer) = self {
+            Some(entities_layer)
+        } else {
+            None
+        }
+    }
+}
+
+/// The data equivalent of [crate::layer::LayerInstance].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct LayerInstanceData {
+    pub grid_size: I64Vec2,
+    pub grid_cell_size: i64,
+    pub identifier: String,
+    pub opacity: f64,
+    pub iid: Iid,
+    pub level_id: Uid,
+    pub location: I64Vec2,
+    pub layer_type: LayerTypeData,
+    /// The [Uid] of this layer's [super::LayerDefinitionData].
+    pub layer_definition: Uid,
+    pub index: usize,
+}
+
+impl LayerInstanceData {
+    fn new(
+        layer_instance_json: ldtk::LayerInstance,
+        index: usize,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let identifier = layer_instance_json.identifier.clone();
+        let grid_size: I64Vec2 = (layer_instance_json.c_wid, layer_instance_json.c_hei).into();
+
+        if !data_context
+            .layer_uids
+            .contains(&layer_instance_json.layer_def_uid)
+        {
+            return Err(ldtk_import_error!(
+                "Bad layer definition uid! given: {}",
+                layer_instance_json.layer_def_uid
+            ));
+        }
+
+        LayerInstance::check_int_grid(&layer_instance_json)?;
+
+        Ok(Self {
+            grid_size,
+            grid_cell_size: layer_instance_json.grid_size,
+            identifier,
+            opacity: layer_instance_json.opacity,
+            iid: Iid::from_str(&layer_instance_json.iid)?,
+            level_id: layer_instance_json.level_id,
+            location: (
+                layer_instance_json.px_total_offset_x,
+                layer_instance_json.px_total_offset_y,
+            )
+                .into(),
+            layer_definition: layer_instance_json.layer_def_uid,
+            layer_type: LayerTypeData::new(layer_instance_json, data_context)?,
+            index,
+        })
+    }
+}
+
+/// The data equivalent of [crate::entity::EntityInstance].
+///
+/// See that type for a description of the fields.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct EntityInstanceData {
+    pub identifier: String,
+    pub iid: Iid,
+    pub grid: I64Vec2,
+    pub anchor: Anchor,
+    pub smart_color: Color,
+    pub tags: Vec<String>,
+    pub tile: Option<TilesetRectangleData>,
+    pub world_location: Option<I64Vec2>,
+    /// The [Uid] of this entity's [super::EntityDefinitionData].
+    pub entity_definition: Uid,
+    pub field_instances: HashMap<String, FieldInstanceData>,
+    pub size: I64Vec2,
+    pub location: I64Vec2,
+    pub index: usize,
+}
+
+impl EntityInstanceData {
+    fn new(
+        entity_instance_json: ldtk::EntityInstance,
+        index: usize,
+        data_context: &DataContext<'_>,
+    ) -> LdtkResult<Self> {
+        let grid = EntityInstance::parse_grid(&entity_instance_json)?;
+
+        let location = EntityInstance::parse_location(&entity_instance_json)?;
+
+        let world_location = EntityInstance::parse_world_location(&entity_instance_json)?;
+
+        if !data_context
+            .entity_uids
+            .contains(&entity_instance_json.def_uid)
+        {
+            return Err(ldtk_import_error!(
+                "bad entity definition uid! given: {}",
+                entity_instance_json.def_uid
+            ));
+        }
+
+        Ok(Self {
+            identifier: entity_instance_json.identifier,
+            iid: Iid::from_str(&entity_instance_json.iid)?,
+            grid,
+            anchor: bevy_anchor_from_ldtk(&entity_instance_json.pivot)?,
+            smart_color: bevy_color_from_ldtk_string(&entity_instance_json.smart_color)?,
+            tags: entity_instance_json.tags,
+            tile: entity_instance_json
+                .tile
+                .map(|tile| TilesetRectangleData::new(tile, data_context))
+                .transpose()?,
+            world_location,
+            entity_definition: entity_instance_json.def_uid,
+            field_instances: construct_field_instances(
+                entity_instance_json.field_instances,
+                data_context,
+            )?,
+            size: (entity_instance_json.width, entity_instance_json.height).into(),
+            location,
+            index,
+        })
+    }
+}
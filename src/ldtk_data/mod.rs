@@ -0,0 +1,294 @@
+//! A standalone representation of an LDtk project, for use outside of a Bevy app.
+//!
+//! The [crate::project::Project] asset and its sub assets can only be built by the asset loaders,
+//! which means running an [bevy_asset::AssetServer]. [LdtkData] is parsed directly from a
+//! project's bytes instead. It holds the same model, but refers to other parts of the project by
+//! their [crate::uid::Uid]s and [Iid]s rather than by asset handles, and to images by their paths.
+//!
+//! This makes it suitable for command line tools, build scripts and server side validators:
+//!
+//! ```no_run
+//! # use bevy_ldtk_asset::ldtk_data::LdtkData;
+//! let data = LdtkData::from_path("assets/ldtk/single_world.ldtk")?;
+//!
+//! for level in data.levels() {
+//!     println!("{}: {} layers", level.identifier, level.layers.len());
+//! }
+//! # Ok::<(), bevy_ldtk_asset::LdtkError>(())
+//! ```
+//!
+//! Much of the JSON is converted and checked by the same code as in the asset loaders, such as the
+//! layer, entity and level checks, the single world fallback and the parsing of field values. It
+//! is not a full replacement for loading a project, though:
+//!
+//! * Dangling entity references are not detected, whereas the loader rejects them by default, see
+//!   [crate::project_loader::DanglingEntityRefs].
+//! * Projects using LDtk's embedded icon atlas are accepted, with
+//!   [TilesetDefinitionData::embed_atlas] set, whereas the loader rejects them unless
+//!   [crate::project_loader::ProjectLoaderSettings::ldtk_icons] is given.
+//! * Field instances hold the [crate::uid::Uid] of their definition in
+//!   [FieldInstanceData::def_uid], which is not resolved or checked. Look it up in the
+//!   [FieldDefinition]s of the owning entity definition, or in [LdtkData::level_field_definitions].
+//! * Field values are held as [FieldInstanceTypeData], a separate type from
+//!   [crate::field_instance::FieldInstanceType], and there is no typed extraction like
+//!   [crate::ldtk_asset_trait::LdtkAssetWithFieldInstances::get_field].
+//!
+//! External levels are read through an [LdtkDataReader], so that projects can be parsed from an
+//! archive, over the network, or from memory. Any `Fn(&Path) -> LdtkResult<Vec<u8>>` closure is a
+//! reader.
+
+mod definitions;
+mod field_instance;
+mod instances;
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bevy_asset::AssetPath;
+use bevy_platform::collections::{HashMap, HashSet};
+
+use crate::baked_project::{parse_level_bytes, parse_project_bytes};
+use crate::field_definition::FieldDefinition;
+use crate::iid::Iid;
+use crate::ldtk;
+use crate::ldtk::migration::JsonVersion;
+use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_bevy_path;
+use crate::project::worlds_json;
+use crate::result::LdtkResult;
+use crate::uid::{UidMap, UidSet};
+
+pub use definitions::{
+    EntityDefinitionData, EnumDefinitionData, EnumValueDefinitionData, IntGridValueData,
+    LayerDefinitionData, TilesetDefinitionData, TilesetRectangleData,
+};
+pub use field_instance::{EntityRefData, EnumValueData, FieldInstanceData, FieldInstanceTypeData};
+pub use instances::{
+    EntitiesLayerData, EntityInstanceData, LayerInstanceData, LayerTypeData, LevelBackgroundData,
+    LevelData, TilesLayerData, WorldData,
+};
+
+/// Reads the files an LDtk project refers to, such as its external levels.
+pub trait LdtkDataReader {
+    /// Returns the contents of the file at `path`, which is the directory given to
+    /// [LdtkData::from_bytes] joined with the path stored in the project.
+    fn read(&self, path: &Path) -> LdtkResult<Vec<u8>>;
+}
+
+impl<F> LdtkDataReader for F
+where
+    F: Fn(&Path) -> LdtkResult<Vec<u8>>,
+{
+    fn read(&self, path: &Path) -> LdtkResult<Vec<u8>> {
+        self(path)
+    }
+}
+
+/// An [LdtkDataReader] which reads from the filesystem.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileSystemReader;
+
+impl LdtkDataReader for FileSystemReader {
+    fn read(&self, path: &Path) -> LdtkResult<Vec<u8>> {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+// What the parts of the project need to know about the rest of it while being parsed.
+pub(crate) struct DataContext<'a> {
+    pub(crate) project_directory: AssetPath<'a>,
+    pub(crate) tileset_uids: UidSet,
+    pub(crate) layer_uids: UidSet,
+    pub(crate) entity_uids: UidSet,
+    pub(crate) enum_identifiers: HashSet<String>,
+}
+
+/// An entire LDtk project, parsed without Bevy's asset system.
+///
+/// This is the data equivalent of [crate::project::Project], see the [module](self) docs.
+#[derive(Debug)]
+pub struct LdtkData {
+    /// A unique [Iid] representing this entire project.
+    pub iid: Iid,
+    /// The version of the LDtk tool used to create this project.
+    pub ldtk_version: String,
+    /// The worlds of this project, in the order they appear in LDtk.
+    ///
+    /// A project without multiple worlds has a single world named `World`, sharing the project's
+    /// [Iid], just as when it is loaded as an asset.
+    pub worlds: Vec<WorldData>,
+    /// The tileset definitions, indexed by their [crate::uid::Uid]s.
+    pub tileset_definitions: UidMap<TilesetDefinitionData>,
+    /// The layer definitions, indexed by their [crate::uid::Uid]s.
+    pub layer_definitions: UidMap<LayerDefinitionData>,
+    /// The entity definitions, indexed by their [crate::uid::Uid]s.
+    pub entity_definitions: UidMap<EntityDefinitionData>,
+    /// The enum definitions, indexed by their identifiers.
+    pub enum_definitions: HashMap<String, EnumDefinitionData>,
+    /// The definitions of the fields of every level, in the order they are declared in LDtk.
+    pub level_field_definitions: Vec<FieldDefinition>,
+}
+
+impl LdtkData {
+    /// Parses the project at `path` from the filesystem, along with its external levels.
+    ///
+    /// The paths in the returned data are relative to the current directory, in the same way as
+    /// `path` is.
+    pub fn from_path(path: impl AsRef<Path>) -> LdtkResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        let project_directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+        Self::from_bytes(&bytes, project_directory, &FileSystemReader)
+    }
+
+    /// Parses a project from its bytes, either LDtk JSON or a baked project. Its external levels
+    /// may likewise be either.
+    ///
+    /// `project_directory` is the directory the project would live in. The paths stored in the
+    /// project are joined to it, both for the paths in the returned data, and for the paths given
+    /// to `reader` when reading external levels.
+    pub fn from_bytes(
+        bytes: &[u8],
+        project_directory: impl AsRef<Path>,
+        reader: &impl LdtkDataReader,
+    ) -> LdtkResult<Self> {
+        let mut project_json = parse_project_bytes(bytes)?;
+
+        let json_version = JsonVersion::from_str(&project_json.json_version)?;
+
+        let defs = &mut project_json.defs;
+        let tilesets = std::mem::take(&mut defs.tilesets);
+        let layers = std::mem::take(&mut defs.layers);
+        let entities = std::mem::take(&mut defs.entities);
+        let enums = std::mem::take(&mut defs.enums);
+        let level_fields = std::mem::take(&mut defs.level_fields);
+
+        let data_context = DataContext {
+            project_directory: AssetPath::from_path(project_directory.as_ref()),
+            tileset_uids: tilesets.iter().map(|value| value.uid).collect(),
+            layer_uids: layers.iter().map(|value| value.uid).collect(),
+            entity_uids: entities.iter().map(|value| value.uid).collect(),
+            enum_identifiers: enums.iter().map(|value| value.identifier.clone()).collect(),
+        };
+
+        let tileset_definitions = tilesets
+            .into_iter()
+            .map(|value| Ok((value.uid, TilesetDefinitionData::new(value, &data_context)?)))
+            .collect::<LdtkResult<_>>()?;
+
+        let layer_definitions = layers
+            .into_iter()
+            .map(|value| Ok((value.uid, LayerDefinitionData::new(value, &data_context)?)))
+            .collect::<LdtkResult<_>>()?;
+
+        let entity_definitions = entities
+            .into_iter()
+            .map(|value| Ok((value.uid, EntityDefinitionData::new(value, &data_context)?)))
+            .collect::<LdtkResult<_>>()?;
+
+        let enum_definitions = enums
+            .into_iter()
+            .map(|value| {
+                let identifier = value.identifier.clone();
+                Ok((identifier, EnumDefinitionData::new(value, &data_context)?))
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        let level_field_definitions = level_fields
+            .into_iter()
+            .map(FieldDefinition::new)
+            .collect::<LdtkResult<_>>()?;
+
+        let external_levels = project_json.external_levels;
+
+        let worlds = worlds_json(&mut project_json)?
+            .into_iter()
+            .map(|mut world_json| {
+                let levels = std::mem::take(&mut world_json.levels)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, level_json)| {
+                        let (level_json, external_path) = if external_levels {
+                            read_external_level(
+                                &level_json,
+                                json_version,
+                                &data_context.project_directory,
+                                reader,
+                            )?
+                        } else {
+                            (level_json, None)
+                        };
+
+                        LevelData::new(level_json, index, external_path, &data_context)
+                    })
+                    .collect::<LdtkResult<_>>()?;
+
+                WorldData::new(world_json, levels)
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        Ok(Self {
+            iid: Iid::from_str(&project_json.iid)?,
+            ldtk_version: project_json.json_version,
+            worlds,
+            tileset_definitions,
+            layer_definitions,
+            entity_definitions,
+            enum_definitions,
+            level_field_definitions,
+        })
+    }
+
+    /// Iterates over the levels of every world, in order.
+    pub fn levels(&self) -> impl Iterator<Item = &LevelData> {
+        self.worlds.iter().flat_map(|world| world.levels.iter())
+    }
+
+    /// Returns the world with the given [Iid], if any.
+    pub fn world(&self, iid: Iid) -> Option<&WorldData> {
+        self.worlds.iter().find(|world| world.iid == iid)
+    }
+
+    /// Returns the level with the given [Iid], if any.
+    pub fn level(&self, iid: Iid) -> Option<&LevelData> {
+        self.levels().find(|level| level.iid == iid)
+    }
+
+    /// Returns the entity with the given [Iid], if any.
+    ///
+    /// This resolves the [EntityRefData::entity_iid] of an entity reference.
+    pub fn entity(&self, iid: Iid) -> Option<&EntityInstanceData> {
+        self.levels()
+            .flat_map(|level| level.layers.iter())
+            .filter_map(|layer| layer.layer_type.get_entities_layer())
+            .flat_map(|entities_layer| entities_layer.entities.iter())
+            .find(|entity| entity.iid == iid)
+    }
+}
+
+fn read_external_level(
+    level_json: &ldtk::Level,
+    json_version: JsonVersion,
+    project_directory: &AssetPath<'_>,
+    reader: &impl LdtkDataReader,
+) -> LdtkResult<(ldtk::Level, Option<PathBuf>)> {
+    let ldtk_path = level_json.external_rel_path.as_ref().ok_or_else(|| {
+        ldtk_import_error!("external_rel_path is `None` in an external_levels project?")
+    })?;
+    let path = ldtk_path_to_bevy_path(project_directory, ldtk_path);
+
+    let bytes = reader.read(&path)?;
+    let external_level_json = parse_level_bytes(&bytes, json_version)?;
+
+    if external_level_json.iid != level_json.iid {
+        return Err(ldtk_import_error!(
+            "External level {} does not match the project! expected iid: {} given: {}",
+            path.display(),
+            level_json.iid,
+            external_level_json.iid
+        ));
+    }
+
+    Ok((external_level_json, Some(path)))
+}
@@ -0,0 +1,130 @@
+//! Conversions from LDtk [EnumValue]s into Rust enums.
+//!
+//! [LdtkEnum] is normally implemented with `#[derive(LdtkEnum)]` on an enum with unit variants,
+//! which also implements [crate::from_field_instance::FromFieldInstance] and
+//! `TryFrom<&EnumValue>`. The derived enum can then be used directly as the type of a field in an
+//! [crate::from_field_instance::LdtkFields] struct, or with
+//! [crate::ldtk_asset_trait::LdtkAssetWithFieldInstances::get_field].
+//!
+//! * The LDtk enum identifier defaults to the name of the Rust enum.
+//! * Each LDtk value defaults to the name of its variant.
+//! * `#[ldtk(rename = "...")]`, on either the enum or a variant, selects a different identifier.
+//!
+//! ```ignore
+//! #[derive(LdtkEnum)]
+//! #[ldtk(rename = "Item_Kind")]
+//! enum ItemKind {
+//!     Sword,
+//!     #[ldtk(rename = "Health_Potion")]
+//!     HealthPotion,
+//! }
+//!
+//! app.register_ldtk_enum::<ItemKind>();
+//! ```
+//!
+//! Registering the enum with [LdtkEnumAppExt::register_ldtk_enum] checks every loaded
+//! [EnumDefinition] with a matching identifier, and logs an error if any variant of the Rust enum
+//! is missing from its values.
+
+use bevy_app::{App, Update};
+use bevy_asset::{AssetEvent, Assets};
+use bevy_ecs::message::MessageReader;
+use bevy_ecs::system::Res;
+use bevy_log::error;
+
+use crate::enum_definition::EnumDefinition;
+use crate::error::LdtkError;
+use crate::field_instance::EnumValue;
+use crate::result::LdtkResult;
+
+pub use bevy_ldtk_asset_derive::LdtkEnum;
+
+/// A Rust enum which mirrors an LDtk [EnumDefinition].
+///
+/// See the [module level documentation](self).
+pub trait LdtkEnum: Sized {
+    /// The identifier of the mirrored [EnumDefinition].
+    const IDENTIFIER: &'static str;
+
+    /// The LDtk value of every variant, in declaration order.
+    const VARIANTS: &'static [&'static str];
+
+    /// Returns the variant for the given LDtk value, if there is one.
+    fn from_ldtk_value(value: &str) -> Option<Self>;
+
+    /// Returns the LDtk value of this variant.
+    fn ldtk_value(&self) -> &'static str;
+
+    /// Converts an [EnumValue], failing if it belongs to a different enum or has no matching
+    /// variant.
+    fn from_enum_value(enum_value: &EnumValue) -> LdtkResult<Self> {
+        if enum_value.enum_identifier() != Self::IDENTIFIER {
+            return Err(LdtkError::EnumIdentifierMismatch {
+                expected: Self::IDENTIFIER,
+                given: enum_value.enum_identifier().to_string(),
+            });
+        }
+
+        Self::from_ldtk_value(enum_value.value()).ok_or_else(|| LdtkError::EnumValueNotFound {
+            identifier: Self::IDENTIFIER,
+            value: enum_value.value().to_string(),
+        })
+    }
+
+    /// Checks that the given [EnumDefinition] is the one mirrored by this enum, and that every
+    /// variant exists in its values.
+    fn validate(enum_definition: &EnumDefinition) -> LdtkResult<()> {
+        if enum_definition.identifier != Self::IDENTIFIER {
+            return Err(LdtkError::EnumIdentifierMismatch {
+                expected: Self::IDENTIFIER,
+                given: enum_definition.identifier.clone(),
+            });
+        }
+
+        let missing: Vec<_> = Self::VARIANTS
+            .iter()
+            .filter(|variant| !enum_definition.values.contains_key(**variant))
+            .copied()
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(LdtkError::EnumVariantsMissing {
+                identifier: enum_definition.identifier.clone(),
+                missing,
+            })
+        }
+    }
+}
+
+/// Extension trait for registering [LdtkEnum]s on an [App].
+pub trait LdtkEnumAppExt {
+    /// Validates the given [LdtkEnum] against every [EnumDefinition] with a matching identifier as
+    /// it is loaded, logging an error on a mismatch.
+    fn register_ldtk_enum<T: LdtkEnum + 'static>(&mut self) -> &mut Self;
+}
+
+impl LdtkEnumAppExt for App {
+    fn register_ldtk_enum<T: LdtkEnum + 'static>(&mut self) -> &mut Self {
+        self.add_systems(Update, validate_ldtk_enum::<T>)
+    }
+}
+
+fn validate_ldtk_enum<T: LdtkEnum>(
+    mut enum_definition_events: MessageReader<AssetEvent<EnumDefinition>>,
+    enum_definitions: Res<Assets<EnumDefinition>>,
+) {
+    enum_definition_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => enum_definitions.get(*id),
+            _ => None,
+        })
+        .filter(|enum_definition| enum_definition.identifier == T::IDENTIFIER)
+        .for_each(|enum_definition| {
+            if let Err(e) = T::validate(enum_definition) {
+                error!("{e}");
+            }
+        });
+}
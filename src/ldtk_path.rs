@@ -1,6 +1,6 @@
 use bevy_asset::AssetPath;
 use path_clean::PathClean;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 pub fn ldtk_path_to_bevy_path(
     base_directory: &AssetPath<'_>,
@@ -8,3 +8,47 @@ pub fn ldtk_path_to_bevy_path(
 ) -> PathBuf {
     base_directory.path().join(ldtk_path).clean()
 }
+
+// Like [ldtk_path_to_bevy_path], but keeping the asset source of the base directory. External
+// levels are always read from the same source as their project.
+pub fn ldtk_path_to_asset_path(
+    base_directory: &AssetPath<'_>,
+    ldtk_path: impl AsRef<Path>,
+) -> AssetPath<'static> {
+    AssetPath::from(ldtk_path_to_bevy_path(base_directory, ldtk_path))
+        .with_source(base_directory.source().clone_owned())
+}
+
+// The inverse of [ldtk_path_to_bevy_path], giving a path relative to the base directory with
+// forward slashes, as LDtk writes them.
+pub fn bevy_path_to_ldtk_path(
+    base_directory: &AssetPath<'_>,
+    bevy_path: impl AsRef<Path>,
+) -> String {
+    let base_directory = base_directory.path().clean();
+    let bevy_path = bevy_path.as_ref().clean();
+
+    let mut base_components = base_directory
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .peekable();
+    let mut path_components = bevy_path
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .peekable();
+
+    while let (Some(base), Some(path)) = (base_components.peek(), path_components.peek())
+        && base == path
+    {
+        base_components.next();
+        path_components.next();
+    }
+
+    base_components
+        .map(|_| "..".to_string())
+        .chain(
+            path_components.map(|component| component.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -63,6 +63,22 @@ impl NeighbourDir {
             )),
         }
     }
+
+    pub(crate) const fn ldtk_dir(&self) -> &'static str {
+        match self {
+            Self::North => "n",
+            Self::South => "s",
+            Self::West => "w",
+            Self::East => "e",
+            Self::Lower => "<",
+            Self::Greater => ">",
+            Self::Overlap => "o",
+            Self::NorthWest => "nw",
+            Self::NorthEast => "ne",
+            Self::SouthWest => "sw",
+            Self::SouthEast => "se",
+        }
+    }
 }
 
 /// An entry in the list of Neighbours, indicating the Iid of the adjacent level, and its relative
@@ -96,7 +112,9 @@ impl Neighbour {
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Reflect)]
 pub struct LevelBackground {
-    pub image: Handle<Image>,
+    /// `None` if the project was loaded with
+    /// [crate::project_loader::ProjectLoaderSettings::load_images] disabled.
+    pub image: Option<Handle<Image>>,
     pub crop_corner: DVec2,
     pub crop_size: DVec2,
     pub scale: DVec2,
@@ -106,7 +124,7 @@ pub struct LevelBackground {
 impl LevelBackground {
     pub(crate) fn new(
         value: ldtk::LevelBackgroundPosition,
-        image: Handle<Image>,
+        image: Option<Handle<Image>>,
     ) -> LdtkResult<Self> {
         let (crop_corner, crop_size) = (value.crop_rect.len() == 4)
             .then(|| {
@@ -138,6 +156,18 @@ impl LevelBackground {
     }
 }
 
+// The parts of a level's JSON which [Level] does not represent, kept so that
+// [crate::project_saver] can write them back.
+#[derive(Clone, Debug)]
+pub(crate) struct LevelSource {
+    pub(crate) smart_color: String,
+    pub(crate) level_bg_color: Option<String>,
+    pub(crate) bg_pivot: DVec2,
+    pub(crate) level_bg_pos: Option<ldtk::BgPos>,
+    pub(crate) bg_rel_path: Option<String>,
+    pub(crate) use_auto_identifier: bool,
+}
+
 /// A level as represented in an LDtk project.
 ///
 /// See [LevelInstance](https://ldtk.io/json/#ldtk-LevelInstanceJson).
@@ -184,18 +214,36 @@ pub struct Level {
     /// [crate::world::WorldLayout::LinearVertical] and
     /// [crate::world::WorldLayout::LinearHorizontal] world layouts.
     pub index: usize,
+    // `None` for levels which were not loaded from an LDtk file.
+    #[reflect(ignore)]
+    pub(crate) source: Option<LevelSource>,
 }
 
 impl Level {
     pub(crate) async fn new(
-        level_json: ldtk::Level,
+        mut level_json: ldtk::Level,
         index: usize,
         project_context: Arc<RwLock<ProjectContext<'_>>>,
         load_context: Arc<Mutex<&mut LoadContext<'_>>>,
         level_label: &str,
     ) -> LdtkResult<Self> {
+        let background_json = Self::take_background_json(&mut level_json)?;
+
+        let layer_instances = Self::take_layer_instances(&mut level_json)?;
+
         let identifier = level_json.identifier;
 
+        let source = Some(LevelSource {
+            smart_color: level_json.smart_color,
+            level_bg_color: level_json.level_bg_color,
+            bg_pivot: (level_json.bg_pivot_x, level_json.bg_pivot_y).into(),
+            level_bg_pos: level_json.level_bg_pos,
+            bg_rel_path: background_json
+                .as_ref()
+                .map(|(_, bg_rel_path)| bg_rel_path.clone()),
+            use_auto_identifier: level_json.use_auto_identifier,
+        });
+
         let bg_color = bevy_color_from_ldtk_string(&level_json.bg_color)?;
         let neighbours = level_json
             .neighbours
@@ -203,44 +251,29 @@ impl Level {
             .map(Neighbour::new)
             .collect::<LdtkResult<_>>()?;
 
-        let background = match (level_json.bg_pos, level_json.bg_rel_path) {
-            (None, None) => Ok(None),
-            (None, Some(_)) => Err(ldtk_import_error!(
-                "bg_pos is None while bg_rel_path is Some(_)!"
-            )),
-            (Some(_), None) => Err(ldtk_import_error!(
-                "bg_pos is Some(_) while bg_rel_path is None!"
-            )),
-            (Some(bg_pos), Some(bg_rel_path)) => {
-                let path =
-                    ldtk_path_to_bevy_path(&project_context.read()?.project_directory, bg_rel_path);
-                let image = load_context.lock().await.load(path);
-                let background = LevelBackground::new(bg_pos, image)?;
-                Ok(Some(background))
+        let background = match background_json {
+            None => None,
+            Some((bg_pos, bg_rel_path)) => {
+                let (load_images, path) = {
+                    let project_context = project_context.read()?;
+                    let path =
+                        ldtk_path_to_bevy_path(&project_context.project_directory, bg_rel_path);
+                    (project_context.load_images, path)
+                };
+                let image = if load_images {
+                    Some(load_context.lock().await.load(path))
+                } else {
+                    None
+                };
+                Some(LevelBackground::new(bg_pos, image)?)
             }
-        }?;
+        };
 
         let iid = Iid::from_str(&level_json.iid)?;
 
-        let field_instances_iter = level_json
-            .field_instances
-            .into_iter()
-            .filter(|field_instance_json| field_instance_json.value.is_some())
-            .map(|field_instance_json| {
-                let project_context = project_context.clone();
-                async move {
-                    let identifier = field_instance_json.identifier.clone();
-                    let field_instance =
-                        FieldInstance::new(field_instance_json, project_context).await?;
-
-                    LdtkResult::Ok((identifier, field_instance))
-                }
-            });
-
-        let field_instances = try_join_all(field_instances_iter)
-            .await?
-            .into_iter()
-            .collect();
+        let field_instances =
+            Self::construct_field_instances(level_json.field_instances, project_context.clone())
+                .await?;
 
         let size = (level_json.px_wid, level_json.px_hei).into();
 
@@ -250,10 +283,6 @@ impl Level {
 
         let location = (level_json.world_x, level_json.world_y).into();
 
-        let layer_instances = level_json.layer_instances.ok_or_else(|| ldtk_import_error!(
-            "layer_instances is None? Are we opening the local layer definition instead of the external one?"
-        ))?;
-
         let num_layers = layer_instances.len();
 
         let layers_iter =
@@ -266,8 +295,12 @@ impl Level {
                     let index = num_layers - index - 1;
 
                     async move {
-                        let layer_label =
-                            format!("{level_label}/{}", layer_instance_json.identifier);
+                        // Levels loaded from their own `.ldtkl` file have no label of their own.
+                        let layer_label = if level_label.is_empty() {
+                            layer_instance_json.identifier.clone()
+                        } else {
+                            format!("{level_label}/{}", layer_instance_json.identifier)
+                        };
                         debug!("constructing layer asset: {layer_label}");
 
                         let iid = Iid::parse_str(&layer_instance_json.iid)?;
@@ -305,10 +338,60 @@ impl Level {
             location,
             layers,
             index,
+            source,
         })
     }
 }
 
+impl Level {
+    // Takes the background position and image path out of a level's JSON, which must either both
+    // be set, or neither. Shared with [crate::ldtk_data].
+    pub(crate) fn take_background_json(
+        level_json: &mut ldtk::Level,
+    ) -> LdtkResult<Option<(ldtk::LevelBackgroundPosition, String)>> {
+        match (level_json.bg_pos.take(), level_json.bg_rel_path.take()) {
+            (None, None) => Ok(None),
+            (None, Some(_)) => Err(ldtk_import_error!(
+                "bg_pos is None while bg_rel_path is Some(_)!"
+            )),
+            (Some(_), None) => Err(ldtk_import_error!(
+                "bg_pos is Some(_) while bg_rel_path is None!"
+            )),
+            (Some(bg_pos), Some(bg_rel_path)) => Ok(Some((bg_pos, bg_rel_path))),
+        }
+    }
+
+    // Shared with [crate::ldtk_data].
+    pub(crate) fn take_layer_instances(
+        level_json: &mut ldtk::Level,
+    ) -> LdtkResult<Vec<ldtk::LayerInstance>> {
+        level_json.layer_instances.take().ok_or_else(|| ldtk_import_error!(
+            "layer_instances is None? Are we opening the local layer definition instead of the external one?"
+        ))
+    }
+
+    pub(crate) async fn construct_field_instances(
+        field_instances_json: Vec<ldtk::FieldInstance>,
+        project_context: Arc<RwLock<ProjectContext<'_>>>,
+    ) -> LdtkResult<HashMap<String, FieldInstance>> {
+        let field_instances_iter = field_instances_json.into_iter().map(|field_instance_json| {
+            let project_context = project_context.clone();
+            async move {
+                let identifier = field_instance_json.identifier.clone();
+                let field_instance =
+                    FieldInstance::new(field_instance_json, project_context).await?;
+
+                LdtkResult::Ok((identifier, field_instance))
+            }
+        });
+
+        Ok(try_join_all(field_instances_iter)
+            .await?
+            .into_iter()
+            .collect())
+    }
+}
+
 impl LdtkAsset for Level {
     fn get_identifier(&self) -> &str {
         &self.identifier
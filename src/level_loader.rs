@@ -0,0 +1,506 @@
+//! The [AssetLoader] for LDtk external level files (`.ldtkl`).
+//!
+//! When a project is saved with the `Save levels to separate files` option, each level is written
+//! to its own `.ldtkl` file. Each of these is loaded as an independent [Level] asset, which the
+//! project's [crate::world::World::levels] refers to. Since the level is its own asset, Bevy will
+//! hot reload it alone when it changes, without re-importing the rest of the project.
+//!
+//! A level can also be loaded directly:
+//!
+//! ```ignore
+//! let level: Handle<Level> = asset_server.load("project/Level_0.ldtkl");
+//! ```
+//!
+//! Its layers and entities are labeled sub assets of the level:
+//!
+//! ```text
+//! project/Level_0.ldtkl
+//! project/Level_0.ldtkl#Entities
+//! project/Level_0.ldtkl#Entities/Player@e594faf8-fe91-4a25-8082-95ff47040f43
+//! ```
+//!
+//! External levels are not loaded on demand. Loading a project starts loading every one of its
+//! external levels which passes the project's world and level filters, see
+//! [crate::project_loader::ProjectLoaderSettings::levels].
+//!
+//! The definitions a level refers to belong to its parent project, so loading a level on its own
+//! also loads the whole project, unless it is already loaded. The project is loaded with the
+//! level's [LevelLoaderSettings] for images, entities, icons and dangling references, but with
+//! the default world and level filters, so it in turn loads every one of its external levels. A
+//! level loaded by its project shares the project's parsed JSON, while a level loaded on its own
+//! also reads the project file itself. To load only some levels of a project, load the project
+//! with a level filter first.
+
+use std::sync::{Arc, RwLock};
+
+use bevy_asset::io::Reader;
+use bevy_asset::{Asset, AssetLoader, AssetPath, AssetServer, Handle, LoadContext, UntypedHandle};
+use bevy_ecs::world::{FromWorld, World as BevyWorld};
+use bevy_log::debug;
+use bevy_reflect::{Reflect, TypePath};
+use futures::lock::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::baked_project::{parse_level_bytes, parse_project_bytes};
+use crate::entity::EntityInstance;
+use crate::field_definition::{FieldDefinition, construct_field_definitions};
+use crate::field_instance::EntityRefIds;
+use crate::iid::{Iid, IidMap, IidSet};
+use crate::layer::LayerInstance;
+use crate::ldtk;
+use crate::ldtk::migration::JsonVersion;
+use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_asset_path;
+use crate::level::Level;
+use crate::project::{ProjectContext, worlds_json};
+use crate::project_loader::{DanglingEntityRefs, ProjectLoaderSettings};
+use crate::result::LdtkResult;
+use crate::uid::UidMap;
+use crate::world::World;
+
+/// Settings for the [LevelLoader].
+///
+/// When a level is loaded by its project, these are filled in from the project's
+/// [crate::project_loader::ProjectLoaderSettings].
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelLoaderSettings {
+    /// The path of the project this level belongs to.
+    ///
+    /// When `None`, LDtk's layout is assumed: `some/dir/project/Level.ldtkl` belongs to
+    /// `some/dir/project.ldtk`.
+    pub project_path: Option<AssetPath<'static>>,
+    /// How to handle an [crate::field_instance::EntityRef] whose target entity does not exist.
+    pub dangling_entity_refs: DanglingEntityRefs,
+    /// Load the tileset and level background images.
+    pub load_images: bool,
+    /// Create an [EntityInstance] sub asset for every LDtk entity.
+    pub load_entities: bool,
+    /// See [crate::project_loader::ProjectLoaderSettings::ldtk_icons].
+    pub ldtk_icons: Option<AssetPath<'static>>,
+    // Filled in by the project when it loads its levels, so that the project is parsed once
+    // rather than once per level. When `None`, the project file is read by the level.
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub(crate) project: Option<Arc<LevelProjectJson>>,
+}
+
+impl Default for LevelLoaderSettings {
+    fn default() -> Self {
+        Self {
+            project_path: None,
+            dangling_entity_refs: DanglingEntityRefs::default(),
+            load_images: true,
+            load_entities: true,
+            ldtk_icons: None,
+            project: None,
+        }
+    }
+}
+
+// The parts of a project's JSON which its levels need: the definitions, and the level stubs of
+// every world.
+#[derive(Debug)]
+pub(crate) struct LevelProjectJson {
+    json_version: JsonVersion,
+    worlds: Vec<ldtk::World>,
+    definitions: ldtk::Definitions,
+    field_definitions: UidMap<Arc<FieldDefinition>>,
+}
+
+impl LevelProjectJson {
+    // `worlds` are the worlds taken out of `project_json` by [crate::project::worlds_json].
+    pub(crate) fn new(
+        project_json: &ldtk::LdtkProject,
+        worlds: Vec<ldtk::World>,
+        field_definitions: UidMap<Arc<FieldDefinition>>,
+    ) -> LdtkResult<Self> {
+        Ok(Self {
+            json_version: project_json.json_version.parse()?,
+            worlds,
+            definitions: project_json.defs.clone(),
+            field_definitions,
+        })
+    }
+}
+
+/// Loads an LDtk external level file (`.ldtkl`) as a [Level], along with its layers and entities
+/// as labeled sub assets.
+#[derive(TypePath)]
+pub struct LevelLoader {
+    asset_server: AssetServer,
+}
+
+impl FromWorld for LevelLoader {
+    fn from_world(world: &mut BevyWorld) -> Self {
+        Self {
+            asset_server: world.resource::<AssetServer>().clone(),
+        }
+    }
+}
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = LevelLoaderSettings;
+    type Error = crate::error::LdtkError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> LdtkResult<Self::Asset> {
+        let project_path = match &settings.project_path {
+            Some(project_path) => project_path.clone(),
+            None => project_path_for_level(load_context.path())?,
+        };
+
+        let project_json = match &settings.project {
+            Some(project_json) => project_json.clone(),
+            None => {
+                let project_bytes = load_context.read_asset_bytes(&project_path).await?;
+                let mut project_json = parse_project_bytes(&project_bytes)?;
+                let field_definitions = construct_field_definitions(&project_json.defs)?;
+                let worlds = worlds_json(&mut project_json)?;
+                Arc::new(LevelProjectJson::new(
+                    &project_json,
+                    worlds,
+                    field_definitions,
+                )?)
+            }
+        };
+        let json_version = project_json.json_version;
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level_json = parse_level_bytes(&bytes, json_version)?;
+        let level_iid = Iid::parse_str(&level_json.iid)?;
+
+        let project_directory = project_path
+            .parent()
+            .ok_or_else(|| ldtk_import_error!("Unable to get project_directory!"))?;
+
+        let worlds_json = &project_json.worlds;
+
+        let index = worlds_json
+            .iter()
+            .find_map(|world_json| {
+                world_json
+                    .levels
+                    .iter()
+                    .position(|stub| stub.iid == level_json.iid)
+            })
+            .ok_or_else(|| {
+                ldtk_import_error!(
+                    "Level {} not found in project {project_path}!",
+                    level_json.identifier
+                )
+            })?;
+
+        // Handles to the project's definitions. These are labeled assets of the project, so are
+        // loaded from there rather than being rebuilt here.
+        let definition_path =
+            |label: String| -> AssetPath<'static> { project_path.clone().with_label(label) };
+
+        let tileset_definitions = project_json
+            .definitions
+            .tilesets
+            .iter()
+            .map(|definition| {
+                let label = format!("tileset_definition:{}", definition.identifier);
+                (
+                    definition.uid,
+                    self.load_from_project(definition_path(label), settings),
+                )
+            })
+            .collect();
+
+        let layer_definitions = project_json
+            .definitions
+            .layers
+            .iter()
+            .map(|definition| {
+                let label = format!("layer_definition:{}", definition.identifier);
+                (
+                    definition.uid,
+                    self.load_from_project(definition_path(label), settings),
+                )
+            })
+            .collect();
+
+        let enum_definitions = project_json
+            .definitions
+            .enums
+            .iter()
+            .map(|definition| {
+                let label = format!("enum_definition:{}", definition.identifier);
+                (
+                    definition.identifier.clone(),
+                    self.load_from_project(definition_path(label), settings),
+                )
+            })
+            .collect();
+
+        let entity_definitions = project_json
+            .definitions
+            .entities
+            .iter()
+            .map(|definition| {
+                let label = format!("entity_definition:{}", definition.identifier);
+                (
+                    definition.uid,
+                    self.load_from_project(definition_path(label), settings),
+                )
+            })
+            .collect();
+
+        let field_definitions = project_json.field_definitions.clone();
+
+        let (entity_ref_ids, entity_ref_handles) = if settings.load_entities {
+            self.construct_entity_ref_ids(
+                &level_json,
+                worlds_json,
+                &project_path,
+                json_version,
+                settings,
+                load_context,
+            )
+            .await?
+        } else {
+            (IidMap::default(), Vec::new())
+        };
+
+        let project_context = Arc::new(RwLock::new(ProjectContext {
+            tileset_definitions,
+            layer_definitions,
+            enum_definitions,
+            entity_definitions,
+            field_definitions,
+            entity_ref_ids,
+            entity_ref_handles,
+            excluded_level_iids: IidSet::default(),
+            dangling_entity_refs: settings.dangling_entity_refs,
+            load_images: settings.load_images,
+            load_entities: settings.load_entities,
+            ldtk_icons: settings.ldtk_icons.clone(),
+            project_directory,
+            external_level_paths: IidMap::default(),
+            level_project_json: None,
+        }));
+
+        debug!(
+            "constructing level asset: {} ({level_iid})",
+            load_context.path()
+        );
+
+        let level = Level::new(
+            level_json,
+            index,
+            project_context,
+            Arc::new(Mutex::new(load_context)),
+            "",
+        )
+        .await?;
+
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtkl"]
+    }
+}
+
+impl LevelLoader {
+    // Indexes every entity which an EntityRef in this level can resolve to. These are the
+    // entities of this level, which are labeled sub assets of it, and the entities of any other
+    // level referenced from here, which are read from their own `.ldtkl` files.
+    async fn construct_entity_ref_ids(
+        &self,
+        level_json: &ldtk::Level,
+        worlds_json: &[ldtk::World],
+        project_path: &AssetPath<'static>,
+        json_version: JsonVersion,
+        settings: &LevelLoaderSettings,
+        load_context: &mut LoadContext<'_>,
+    ) -> LdtkResult<(IidMap<EntityRefIds>, Vec<UntypedHandle>)> {
+        let project_directory = project_path
+            .parent()
+            .ok_or_else(|| ldtk_import_error!("Unable to get project_directory!"))?;
+        let mut entity_ref_ids = IidMap::default();
+        let mut handles = Vec::new();
+
+        let referenced_level_iids: IidSet = referenced_level_iids(level_json)
+            .into_iter()
+            .filter(|iid| iid.to_string() != level_json.iid)
+            .collect();
+
+        for world_json in worlds_json {
+            for stub in &world_json.levels {
+                let iid = Iid::parse_str(&stub.iid)?;
+
+                // `None` for the level being loaded, whose sub assets are labeled here.
+                let (other_level_path, other_level_json) = if stub.iid == level_json.iid {
+                    (None, None)
+                } else if referenced_level_iids.contains(&iid) {
+                    let ldtk_path = stub.external_rel_path.as_ref().ok_or_else(|| {
+                        ldtk_import_error!(
+                            "external_rel_path is `None` in an external_levels project?"
+                        )
+                    })?;
+                    let path = ldtk_path_to_asset_path(&project_directory, ldtk_path);
+                    let bytes = load_context.read_asset_bytes(&path).await?;
+                    let other_level_json = parse_level_bytes(&bytes, json_version)?;
+                    (Some(path), Some(other_level_json))
+                } else {
+                    continue;
+                };
+
+                let level = self.asset_server.load::<Level>(
+                    other_level_path
+                        .clone()
+                        .unwrap_or_else(|| load_context.path().clone_owned()),
+                );
+
+                let world: Handle<World> = self.load_from_project(
+                    project_path
+                        .clone()
+                        .with_label(format!("world:{}", world_json.identifier)),
+                    settings,
+                );
+
+                let layers_json = other_level_json
+                    .as_ref()
+                    .unwrap_or(level_json)
+                    .layer_instances
+                    .iter()
+                    .flatten();
+
+                for layer_json in layers_json {
+                    let layer_label = layer_json.identifier.clone();
+                    let layer: Handle<LayerInstance> = self.sub_asset_handle(
+                        other_level_path.as_ref(),
+                        layer_label.clone(),
+                        load_context,
+                    );
+
+                    for entity_json in &layer_json.entity_instances {
+                        let entity_iid = Iid::parse_str(&entity_json.iid)?;
+                        let entity_label =
+                            format!("{layer_label}/{}@{}", entity_json.identifier, entity_iid);
+                        let entity: Handle<EntityInstance> = self.sub_asset_handle(
+                            other_level_path.as_ref(),
+                            entity_label,
+                            load_context,
+                        );
+
+                        entity_ref_ids.insert(
+                            entity_iid,
+                            EntityRefIds {
+                                entity: entity.id(),
+                                layer: layer.id(),
+                                level: level.id(),
+                                world: world.id(),
+                            },
+                        );
+
+                        handles.push(entity.untyped());
+                    }
+
+                    handles.push(layer.untyped());
+                }
+
+                handles.push(level.untyped());
+                handles.push(world.untyped());
+            }
+        }
+
+        Ok((entity_ref_ids, handles))
+    }
+
+    // Loads a labeled asset of the project. If this level is being loaded on its own, this also
+    // starts loading the project, which needs the same settings to succeed.
+    //
+    // Loading a labeled path which is not loaded yet makes the asset server load the whole
+    // project again, with these settings rather than the project's own. So when the project is
+    // already loading this level, the handle it created is used instead.
+    fn load_from_project<A: Asset>(
+        &self,
+        path: AssetPath<'static>,
+        settings: &LevelLoaderSettings,
+    ) -> Handle<A> {
+        if let Some(handle) = self.asset_server.get_handle(&path) {
+            return handle;
+        }
+
+        let settings = ProjectLoaderSettings {
+            dangling_entity_refs: settings.dangling_entity_refs,
+            load_images: settings.load_images,
+            load_entities: settings.load_entities,
+            ldtk_icons: settings.ldtk_icons.clone(),
+            ..Default::default()
+        };
+
+        self.asset_server
+            .load_with_settings(path, move |s: &mut ProjectLoaderSettings| {
+                *s = settings.clone();
+            })
+    }
+
+    fn sub_asset_handle<A: Asset>(
+        &self,
+        other_level_path: Option<&AssetPath<'static>>,
+        label: String,
+        load_context: &mut LoadContext<'_>,
+    ) -> Handle<A> {
+        match other_level_path {
+            None => load_context.get_label_handle(label),
+            Some(path) => self.asset_server.load(path.clone().with_label(label)),
+        }
+    }
+}
+
+// LDtk saves the external levels of `some/dir/project.ldtk` in `some/dir/project/`.
+pub(crate) fn project_path_for_level(level_path: &AssetPath<'_>) -> LdtkResult<AssetPath<'static>> {
+    let level_directory = level_path.path().parent();
+
+    let project_name = level_directory
+        .and_then(|directory| directory.file_name())
+        .ok_or_else(|| ldtk_import_error!("Unable to find the project for level {level_path}!"))?;
+
+    let project_path = level_directory
+        .and_then(|directory| directory.parent())
+        .unwrap_or_else(|| std::path::Path::new(""))
+        .join(project_name)
+        .with_extension("ldtk");
+
+    Ok(AssetPath::from(project_path).with_source(level_path.source().clone_owned()))
+}
+
+// Collects the level Iid of every EntityRef in the level's fields, and in the fields of its
+// entities.
+fn referenced_level_iids(level_json: &ldtk::Level) -> Vec<Iid> {
+    let field_instances = level_json.field_instances.iter().chain(
+        level_json
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer_json| layer_json.entity_instances.iter())
+            .flat_map(|entity_json| entity_json.field_instances.iter()),
+    );
+
+    field_instances
+        .filter(|field_instance| {
+            matches!(
+                field_instance.field_instance_type.as_str(),
+                "EntityRef" | "Array<EntityRef>"
+            )
+        })
+        .filter_map(|field_instance| field_instance.value.as_ref())
+        .flat_map(|value| match value {
+            serde_json::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        })
+        .filter_map(|value| value.get("levelIid")?.as_str())
+        .filter_map(|level_iid| Iid::parse_str(level_iid).ok())
+        .collect()
+}
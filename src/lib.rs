@@ -3,31 +3,52 @@
 #![warn(missing_docs)]
 #![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
 
+// Allows the derive macros to refer to `::bevy_ldtk_asset` from within this crate.
+extern crate self as bevy_ldtk_asset;
+
 mod anchor;
 mod color;
 mod ldtk;
 mod ldtk_path;
-mod project_loader;
 mod systems;
 
+pub mod baked_project;
+pub mod codegen;
 pub mod entity;
 pub mod entity_definition;
+pub mod entity_registry;
 pub mod enum_definition;
 pub mod error;
+pub mod field_definition;
 pub mod field_instance;
+pub mod from_field_instance;
 pub mod iid;
+pub mod iid_index;
 pub mod layer;
 pub mod layer_definition;
 pub mod ldtk_asset_trait;
+pub mod ldtk_assets;
+pub mod ldtk_data;
+pub mod ldtk_enum;
 pub mod level;
+pub mod level_loader;
 pub mod plugin;
 pub mod prelude;
 pub mod project;
+pub mod project_builder;
+pub mod project_loader;
+pub mod project_saver;
+pub mod rasterize;
+pub mod spawn;
+pub mod streaming;
+pub mod tile_chunks;
 pub mod tile_instance;
+pub mod tile_mesh;
 pub mod tileset_definition;
 pub mod tileset_rectangle;
 pub mod uid;
 pub mod world;
+pub mod world_map;
 
 pub use error::LdtkError;
 
@@ -36,5 +57,12 @@ pub mod result {
     pub type LdtkResult<T> = core::result::Result<T, crate::error::LdtkError>;
 }
 
+// Re-exports referred to by generated code, see [codegen].
+#[doc(hidden)]
+pub mod __private {
+    pub use bevy_color::Color;
+    pub use bevy_math::I64Vec2;
+}
+
 #[cfg(test)]
 mod test;
@@ -1,17 +1,35 @@
 #![allow(missing_docs)]
 
-use bevy_app::{App, Plugin};
+use bevy_app::{App, Plugin, Update};
 use bevy_asset::AssetApp;
+use bevy_ecs::schedule::IntoScheduleConfigs;
+use bevy_image::TextureAtlasPlugin;
 
+use crate::baked_project::{
+    BakedLevelSaver, BakedProjectSaver, LevelBaker, LevelJson, LevelJsonLoader, ProjectBaker,
+    ProjectJson, ProjectJsonLoader,
+};
 use crate::entity::EntityInstance;
 use crate::entity_definition::EntityDefinition;
+use crate::entity_registry::LdtkEntityRegistry;
 use crate::enum_definition::EnumDefinition;
 use crate::iid::Iid;
+use crate::iid_index::{LdtkIid, LdtkIidIndex};
 use crate::layer::LayerInstance;
 use crate::layer_definition::LayerDefinition;
 use crate::level::{Level, LevelBackground};
+use crate::level_loader::LevelLoader;
 use crate::project::Project;
 use crate::project_loader::ProjectLoader;
+use crate::spawn::{
+    LdtkEntity, LdtkLayer, LdtkLevel, LdtkProjectRoot, LdtkProjectSpawned, LdtkWorld,
+    despawn_modified_ldtk_projects, respawn_modified_ldtk_levels, spawn_ldtk_projects,
+};
+use crate::streaming::{
+    LdtkLevelStreamedIn, LdtkLevelStreamedOut, LdtkStreaming, LdtkStreamingRange,
+    stream_ldtk_levels,
+};
+use crate::tile_chunks::LdtkTileChunk;
 use crate::tileset_definition::TilesetDefinition;
 use crate::world::World;
 
@@ -26,6 +44,12 @@ pub struct BevyLdtkAssetPlugin;
 
 impl Plugin for BevyLdtkAssetPlugin {
     fn build(&self, app: &mut App) {
+        // Tileset definitions carry a texture atlas layout. Bevy's sprite plugin adds the same
+        // plugin, so only add it if it's missing.
+        if !app.is_plugin_added::<TextureAtlasPlugin>() {
+            app.add_plugins(TextureAtlasPlugin);
+        }
+
         app //
             .init_asset::<EntityInstance>()
             .init_asset::<LayerInstance>()
@@ -37,6 +61,13 @@ impl Plugin for BevyLdtkAssetPlugin {
             .init_asset::<TilesetDefinition>()
             .init_asset::<EnumDefinition>()
             .init_asset_loader::<ProjectLoader>()
+            .init_asset_loader::<LevelLoader>()
+            .init_asset::<ProjectJson>()
+            .init_asset_loader::<ProjectJsonLoader>()
+            .register_asset_processor::<ProjectBaker>(BakedProjectSaver.into())
+            .init_asset::<LevelJson>()
+            .init_asset_loader::<LevelJsonLoader>()
+            .register_asset_processor::<LevelBaker>(BakedLevelSaver.into())
             .register_asset_reflect::<EntityInstance>()
             .register_asset_reflect::<LayerInstance>()
             .register_asset_reflect::<Level>()
@@ -47,12 +78,34 @@ impl Plugin for BevyLdtkAssetPlugin {
             .register_asset_reflect::<TilesetDefinition>()
             .register_asset_reflect::<EnumDefinition>()
             .register_type::<Iid>()
-            .register_type::<LevelBackground>();
+            .register_type::<LevelBackground>()
+            .register_type::<LdtkProjectRoot>()
+            .register_type::<LdtkProjectSpawned>()
+            .register_type::<LdtkWorld>()
+            .register_type::<LdtkLevel>()
+            .register_type::<LdtkLayer>()
+            .register_type::<LdtkEntity>()
+            .register_type::<LdtkIid>()
+            .register_type::<LdtkStreaming>()
+            .register_type::<LdtkStreamingRange>()
+            .register_type::<LdtkTileChunk>()
+            .add_message::<LdtkLevelStreamedIn>()
+            .add_message::<LdtkLevelStreamedOut>()
+            .init_resource::<LdtkEntityRegistry>()
+            .init_resource::<LdtkIidIndex>()
+            .add_systems(
+                Update,
+                (
+                    despawn_modified_ldtk_projects,
+                    respawn_modified_ldtk_levels,
+                    spawn_ldtk_projects,
+                    stream_ldtk_levels,
+                )
+                    .chain(),
+            );
 
         #[cfg(feature = "asset_messages_debug")]
         {
-            use bevy_app::Update;
-
             use crate::systems::asset_messages_debug::asset_messages_debug_output;
 
             app.add_systems(
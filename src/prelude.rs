@@ -1,15 +1,26 @@
 //! A convenient place to re-export the most used types.
 
 // The main LDtk types.
+pub use crate::baked_project::{LevelBaker, ProjectBaker};
 pub use crate::entity::EntityInstance;
 pub use crate::layer::LayerInstance;
+pub use crate::ldtk_data::{FileSystemReader, LdtkData, LdtkDataReader};
 pub use crate::level::{Level, LevelBackground, Neighbour, NeighbourDir};
+pub use crate::level_loader::LevelLoaderSettings;
 pub use crate::project::Project;
+pub use crate::project_builder::{LayerBuilder, LevelBuilder, ProjectBuilder};
+pub use crate::project_loader::{
+    DanglingEntityRefs, LevelPredicate, LevelSummary, LoadFilter, ProjectLoaderSettings,
+};
+pub use crate::project_saver::{ProjectSaverSettings, SavedLevel, SavedProject, save_project};
+pub use crate::rasterize::LdtkRasterizer;
 pub use crate::world::{World, WorldLayout};
+pub use crate::world_map::{WorldMapLevelStyle, WorldMapSettings};
 
 // Definitions
 pub use crate::entity_definition::{EntityDefinition, TileRenderMode};
 pub use crate::enum_definition::{EnumDefinition, EnumValueDefinition};
+pub use crate::field_definition::{AllowedRefs, FieldDefaultValue, FieldDefinition};
 pub use crate::layer_definition::{
     IntGridValue, IntGridValuesGroup, IntGridValuesGroups, LayerDefinition, LayerDefinitionType,
 };
@@ -17,15 +28,33 @@ pub use crate::tileset_definition::TilesetDefinition;
 
 // Others
 pub use crate::field_instance::{EntityRef, EnumValue, FieldInstance, FieldInstanceType};
+pub use crate::tile_chunks::{
+    LdtkTileChunk, TileChunk, TileChunkLayout, tiles_layer_chunk, tiles_layer_chunks,
+};
 pub use crate::tile_instance::TileInstance;
+pub use crate::tile_mesh::{TileMeshBuilder, TilesLayerMaterial, tiles_layer_mesh};
 pub use crate::tileset_rectangle::TilesetRectangle;
 
 // Iids/Uids
 pub use crate::iid::{Iid, IidMap, IidSet, iid};
 pub use crate::uid::{Uid, UidMap, UidSet};
 
+// Spawning
+pub use crate::entity_registry::{LdtkEntityAppExt, LdtkEntityContext, LdtkEntityRegistry};
+pub use crate::iid_index::{LdtkIid, LdtkIidIndex};
+pub use crate::ldtk_assets::LdtkAssets;
+pub use crate::spawn::{
+    LdtkEntity, LdtkLayer, LdtkLevel, LdtkProjectRoot, LdtkProjectSpawned, LdtkWorld,
+};
+pub use crate::streaming::{
+    LdtkLevelStreamedIn, LdtkLevelStreamedOut, LdtkStreaming, LdtkStreamingRange,
+};
+
 // Traits
+pub use crate::from_field_instance::{FromFieldInstance, LdtkFields};
 pub use crate::ldtk_asset_trait::LdtkAsset;
 pub use crate::ldtk_asset_trait::LdtkAssetWithChildren;
+pub use crate::ldtk_asset_trait::LdtkAssetWithFieldDefinitions;
 pub use crate::ldtk_asset_trait::LdtkAssetWithFieldInstances;
 pub use crate::ldtk_asset_trait::LdtkAssetWithTags;
+pub use crate::ldtk_enum::{LdtkEnum, LdtkEnumAppExt};
@@ -0,0 +1,60 @@
+use bevy_asset::{LoadContext, UntypedHandle};
+
+use crate::field_instance::EntityRefIds;
+use crate::iid::{Iid, IidMap};
+use crate::ldtk;
+use crate::result::LdtkResult;
+
+// Indexes every entity in the project by its Iid, along with the ids of it and its parents. The
+// labels here must match those given to the assets as they are constructed.
+//
+// The handles returned alongside must be kept alive until the labeled assets are added, so that the
+// ids stay valid.
+pub(super) fn construct_entity_ref_ids(
+    worlds_json: &IidMap<ldtk::World>,
+    load_context: &mut LoadContext<'_>,
+) -> LdtkResult<(IidMap<EntityRefIds>, Vec<UntypedHandle>)> {
+    let mut entity_ref_ids = IidMap::default();
+    let mut handles = Vec::new();
+
+    for world_json in worlds_json.values() {
+        let world_label = format!("world:{}", world_json.identifier);
+        let world = load_context.get_label_handle(&world_label);
+
+        for level_json in &world_json.levels {
+            let level_label = format!("{world_label}/{}", level_json.identifier);
+            let level = load_context.get_label_handle(&level_label);
+
+            for layer_json in level_json.layer_instances.iter().flatten() {
+                let layer_label = format!("{level_label}/{}", layer_json.identifier);
+                let layer = load_context.get_label_handle(&layer_label);
+
+                for entity_json in &layer_json.entity_instances {
+                    let iid = Iid::parse_str(&entity_json.iid)?;
+                    let entity_label = format!("{layer_label}/{}@{}", entity_json.identifier, iid);
+                    let entity = load_context.get_label_handle(entity_label);
+
+                    entity_ref_ids.insert(
+                        iid,
+                        EntityRefIds {
+                            entity: entity.id(),
+                            layer: layer.id(),
+                            level: level.id(),
+                            world: world.id(),
+                        },
+                    );
+
+                    handles.push(entity.untyped());
+                }
+
+                handles.push(layer.untyped());
+            }
+
+            handles.push(level.untyped());
+        }
+
+        handles.push(world.untyped());
+    }
+
+    Ok((entity_ref_ids, handles))
+}
@@ -1,5 +1,6 @@
 use bevy_asset::{AssetPath, Handle, LoadContext};
 use bevy_image::Image;
+use bevy_platform::collections::HashMap;
 use futures::future::try_join_all;
 
 use crate::ldtk;
@@ -11,10 +12,13 @@ use crate::uid::UidMap;
 pub(super) async fn construct_tileset_definitions(
     tileset_definitions: Vec<ldtk::TilesetDefinition>,
     project_directory: &AssetPath<'_>,
+    load_images: bool,
+    ldtk_icons: Option<&AssetPath<'static>>,
     load_context: &mut LoadContext<'_>,
 ) -> LdtkResult<UidMap<Handle<TilesetDefinition>>> {
-    let tileset_definition_images = tileset_definitions
+    let tileset_definition_images: HashMap<_, _> = tileset_definitions
         .iter()
+        .filter(|_| load_images)
         .filter_map(|ldtk_tileset_definition| ldtk_tileset_definition.rel_path.clone())
         .map(|rel_path| {
             let tileset_image: Handle<Image> = {
@@ -26,28 +30,48 @@ pub(super) async fn construct_tileset_definitions(
         })
         .collect::<LdtkResult<_>>()?;
 
+    // The user supplied copy of LDtk's internal atlas, shared by every tileset which embeds it.
+    let ldtk_icons_image: Option<Handle<Image>> = tileset_definitions
+        .iter()
+        .any(|tileset_definition| tileset_definition.embed_atlas.is_some())
+        .then_some(ldtk_icons)
+        .flatten()
+        .filter(|_| load_images)
+        .map(|path| load_context.load(path.clone()));
+
     let tileset_definitions =
         tileset_definitions
             .into_iter()
             .map(|tileset_definition_json| async {
                 let uid = tileset_definition_json.uid;
 
-                let tileset_definition =
-                    TilesetDefinition::new(tileset_definition_json, &tileset_definition_images)
-                        .await?;
+                let tileset_definition = TilesetDefinition::new(
+                    tileset_definition_json,
+                    &tileset_definition_images,
+                    ldtk_icons_image.as_ref(),
+                    load_images,
+                )
+                .await?;
 
                 LdtkResult::Ok((uid, tileset_definition))
             });
 
-    Ok(try_join_all(tileset_definitions)
+    try_join_all(tileset_definitions)
         .await?
         .into_iter()
-        .map(|(uid, tileset_definition)| {
+        .map(|(uid, mut tileset_definition)| {
+            let texture_atlas_layout_label =
+                format!("texture_atlas_layout:{}", tileset_definition.identifier);
+            tileset_definition.texture_atlas_layout = load_context.add_labeled_asset(
+                texture_atlas_layout_label,
+                tileset_definition.build_texture_atlas_layout()?,
+            );
+
             let tileset_definition_label =
                 format!("tileset_definition:{}", tileset_definition.identifier);
             let tileset_definition =
                 load_context.add_labeled_asset(tileset_definition_label, tileset_definition);
-            (uid, tileset_definition)
+            Ok((uid, tileset_definition))
         })
-        .collect())
+        .collect()
 }
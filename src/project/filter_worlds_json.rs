@@ -0,0 +1,89 @@
+use std::sync::{Arc, RwLock};
+
+use futures::future::try_join_all;
+
+use crate::iid::{Iid, IidMap, IidSet};
+use crate::ldtk;
+use crate::level::Level;
+use crate::project::ProjectContext;
+use crate::project_loader::{LevelPredicate, LevelSummary, ProjectLoaderSettings};
+use crate::result::LdtkResult;
+
+// Removes the worlds and levels rejected by the world and level filters, returning the Iids of
+// every level removed.
+pub(super) fn filter_worlds_json(
+    worlds_json: &mut IidMap<ldtk::World>,
+    settings: &ProjectLoaderSettings,
+) -> LdtkResult<IidSet> {
+    let mut excluded_level_iids = IidSet::default();
+
+    for (iid, world_json) in worlds_json.iter_mut() {
+        let world_included = settings.worlds.matches(&world_json.identifier, *iid);
+
+        let mut levels_json = Vec::with_capacity(world_json.levels.len());
+        for level_json in world_json.levels.drain(..) {
+            let level_iid = Iid::parse_str(&level_json.iid)?;
+            if world_included && settings.levels.matches(&level_json.identifier, level_iid) {
+                levels_json.push(level_json);
+            } else {
+                excluded_level_iids.insert(level_iid);
+            }
+        }
+        world_json.levels = levels_json;
+    }
+
+    worlds_json.retain(|iid, world_json| settings.worlds.matches(&world_json.identifier, *iid));
+
+    Ok(excluded_level_iids)
+}
+
+// Removes the levels rejected by the level predicate, returning the Iids of every level removed.
+//
+// The field instances given to the predicate are parsed with every level marked as excluded, so that
+// entity refs resolve to no handles instead of being reported as dangling.
+pub(super) async fn filter_levels_json_by_predicate(
+    worlds_json: &mut IidMap<ldtk::World>,
+    level_predicate: &LevelPredicate,
+    project_context: Arc<RwLock<ProjectContext<'_>>>,
+) -> LdtkResult<IidSet> {
+    let mut excluded_level_iids = IidSet::default();
+
+    for world_json in worlds_json.values_mut() {
+        let world_identifier = world_json.identifier.as_str();
+        let verdicts_iter = world_json.levels.iter().map(|level_json| {
+            let project_context = project_context.clone();
+            async move {
+                let iid = Iid::parse_str(&level_json.iid)?;
+                let field_instances = Level::construct_field_instances(
+                    level_json.field_instances.clone(),
+                    project_context,
+                )
+                .await?;
+
+                let included = (level_predicate.0)(&LevelSummary {
+                    world_identifier,
+                    identifier: &level_json.identifier,
+                    iid,
+                    field_instances: &field_instances,
+                });
+
+                LdtkResult::Ok((iid, included))
+            }
+        });
+
+        let verdicts: IidMap<bool> = try_join_all(verdicts_iter).await?.into_iter().collect();
+
+        excluded_level_iids.extend(
+            verdicts
+                .iter()
+                .filter(|(_, included)| !**included)
+                .map(|(iid, _)| *iid),
+        );
+
+        world_json.levels.retain(|level_json| {
+            Iid::parse_str(&level_json.iid).is_ok_and(|iid| verdicts.get(&iid) == Some(&true))
+        });
+    }
+
+    Ok(excluded_level_iids)
+}
@@ -1,45 +1,58 @@
 //! The LDtk project top level representation!
 
 mod construct_entity_definitions;
+mod construct_entity_ref_ids;
 mod construct_enum_definitions;
 mod construct_layer_definitions;
 mod construct_tileset_definitions;
 mod construct_worlds_from_world_json;
+mod filter_worlds_json;
+mod worlds_json;
 
 use std::sync::Arc;
 use std::sync::RwLock;
 
 use bevy_asset::AssetPath;
-use bevy_asset::{Asset, Handle, LoadContext};
+use bevy_asset::{Asset, Handle, LoadContext, UntypedHandle};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::Reflect;
 
 use crate::entity_definition::EntityDefinition;
 use crate::enum_definition::EnumDefinition;
+use crate::field_definition::{FieldDefinition, construct_field_definitions};
+use crate::field_instance::EntityRefIds;
 use crate::iid::Iid;
-use crate::iid::IidMap;
+use crate::iid::{IidMap, IidSet};
 use crate::layer_definition::LayerDefinition;
 use crate::ldtk;
-use crate::ldtk_asset_trait::LdtkAsset;
+use crate::ldtk_asset_trait::{LdtkAsset, LdtkAssetWithFieldDefinitions};
 use crate::ldtk_import_error;
+use crate::ldtk_path::ldtk_path_to_asset_path;
+use crate::level_loader::LevelProjectJson;
+use crate::project_loader::{DanglingEntityRefs, LoadFilter, ProjectLoaderSettings};
 use crate::result::LdtkResult;
 use crate::tileset_definition::TilesetDefinition;
 use crate::uid::UidMap;
 use crate::world::World;
 
 use construct_entity_definitions::construct_entity_definitions;
+use construct_entity_ref_ids::construct_entity_ref_ids;
 use construct_enum_definitions::construct_enum_definitions;
 use construct_layer_definitions::construct_layer_definitions;
 use construct_tileset_definitions::construct_tileset_definitions;
 use construct_worlds_from_world_json::construct_worlds_from_world_json;
+use filter_worlds_json::{filter_levels_json_by_predicate, filter_worlds_json};
+
+pub(crate) use worlds_json::worlds_json;
 
 /// This asset represents the entirety of an LDtk project file.
 ///
 /// All referenced files (tilemaps, etc) will have assets created with their asset
 /// labels referencing this top level asset.
 ///
-/// External levels, if present, will be parsed along with this top level definition and included
-/// as sub assets of this.
+/// External levels, if present, are not part of this asset. Each `.ldtkl` file is loaded as its own
+/// [crate::level::Level] asset by the [crate::level_loader::LevelLoader], and referenced from
+/// [World::levels].
 ///
 /// See [LDtk Project](https://ldtk.io/json/#ldtk-ProjectJson) for a full description.
 #[derive(Debug, Asset, Reflect)]
@@ -49,41 +62,85 @@ pub struct Project {
     /// The version of the LDtk tool used to create this project.
     pub ldtk_version: String,
 
-    // The global collection of tileset definitions.
-    // pub tileset_definitions: UidMap<Handle<TilesetDefinition>>,
+    /// The tileset definitions of this project, indexed by their [crate::uid::Uid]s.
+    pub tileset_definitions: UidMap<Handle<TilesetDefinition>>,
+    /// The layer definitions of this project, indexed by their [crate::uid::Uid]s.
+    pub layer_definitions: UidMap<Handle<LayerDefinition>>,
+    /// The enum definitions of this project, indexed by their identifiers.
+    pub enum_definitions: HashMap<String, Handle<EnumDefinition>>,
+    /// The entity definitions of this project, indexed by their [crate::uid::Uid]s.
+    pub entity_definitions: UidMap<Handle<EntityDefinition>>,
     /// The associated worlds in this project, indexed by their [Iid]s.
     ///
     /// This is the top level of the entire sub asset heirarchy.
     pub worlds: IidMap<Handle<World>>,
+    /// The definitions of the fields of every [crate::level::Level] in this project, in the order
+    /// they are declared in LDtk.
+    pub level_field_definitions: Vec<FieldDefinition>,
+    // The parts of the project's JSON which its assets do not represent, such as the full
+    // definitions, kept so that [crate::project_saver] can write them back. `None` if the project
+    // was only partially loaded, as saving it would lose whatever was left out.
+    #[reflect(ignore)]
+    pub(crate) source: Option<Box<ldtk::LdtkProject>>,
 }
 
-#[allow(unused)] // DELETE ME!
 pub(crate) struct ProjectContext<'a> {
     pub(crate) tileset_definitions: UidMap<Handle<TilesetDefinition>>,
     pub(crate) layer_definitions: UidMap<Handle<LayerDefinition>>,
     pub(crate) enum_definitions: HashMap<String, Handle<EnumDefinition>>,
     pub(crate) entity_definitions: UidMap<Handle<EntityDefinition>>,
-    pub(crate) external_levels: bool,
+    pub(crate) field_definitions: UidMap<Arc<FieldDefinition>>,
+    pub(crate) entity_ref_ids: IidMap<EntityRefIds>,
+    // Keeps the handles behind [Self::entity_ref_ids] alive until the load is finished.
+    pub(crate) entity_ref_handles: Vec<UntypedHandle>,
+    pub(crate) excluded_level_iids: IidSet,
+    pub(crate) dangling_entity_refs: DanglingEntityRefs,
+    pub(crate) load_images: bool,
+    pub(crate) load_entities: bool,
+    pub(crate) ldtk_icons: Option<AssetPath<'static>>,
     pub(crate) project_directory: AssetPath<'a>,
+    // The `.ldtkl` path of every level in an external levels project, indexed by world and then
+    // by level. Empty otherwise.
+    pub(crate) external_level_paths: IidMap<IidMap<AssetPath<'static>>>,
+    // Passed on to the external levels, see [crate::level_loader::LevelLoaderSettings]. `None`
+    // when the project has no external levels, and when loading a level.
+    pub(crate) level_project_json: Option<Arc<LevelProjectJson>>,
 }
 
 impl Project {
-    const SUPPORTED_VERSION: &'static str = "1.5.3";
-
     pub(crate) async fn new(
-        project_json: ldtk::LdtkProject,
+        mut project_json: ldtk::LdtkProject,
+        settings: &ProjectLoaderSettings,
         load_context: &mut LoadContext<'_>,
     ) -> LdtkResult<Self> {
         let iid: Iid = project_json.iid.clone().try_into()?;
 
+        let partial = settings.worlds != LoadFilter::All
+            || settings.levels != LoadFilter::All
+            || settings.level_predicate.is_some()
+            || !settings.load_entities;
+        let source = (!partial).then(|| Box::new(project_source(&mut project_json)));
+
+        let field_definitions = construct_field_definitions(&project_json.defs)?;
+
+        let worlds_json = worlds_json(&mut project_json)?;
+
+        // Parsed once here and shared with every external level, rather than each level parsing
+        // the project again.
+        let level_project_json = project_json
+            .external_levels
+            .then(|| {
+                LevelProjectJson::new(
+                    &project_json,
+                    worlds_json.clone(),
+                    field_definitions.clone(),
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
+
+        // The version was checked when parsing, see [crate::ldtk::migration].
         let ldtk_version = project_json.json_version;
-        if ldtk_version != Self::SUPPORTED_VERSION {
-            return Err(ldtk_import_error!(
-                "Bad LDtk JSON version! expected: {} given: {}",
-                Self::SUPPORTED_VERSION,
-                ldtk_version
-            ));
-        }
 
         let project_directory = load_context
             .path()
@@ -93,6 +150,8 @@ impl Project {
         let tileset_definitions = construct_tileset_definitions(
             project_json.defs.tilesets,
             &project_directory,
+            settings.load_images,
+            settings.ldtk_icons.as_ref(),
             load_context,
         )
         .await?;
@@ -119,63 +178,135 @@ impl Project {
         )
         .await?;
 
-        let worlds_json = if project_json.worlds.is_empty() {
-            // if we're not a multi-world project, then we simply construct a
-            // single [ldtk::World] and insert into an array. This saves us lots
-            // of complexity when we transpose to our [crate::world::World]
-            // object.
-            IidMap::from([(
-                iid,
-                ldtk::World {
-                    default_level_height: project_json.default_level_height.ok_or_else(|| {
-                        ldtk_import_error!("Missing default_level_height on single world project!")
-                    })?,
-                    default_level_width: project_json.default_level_width.ok_or_else(|| {
-                        ldtk_import_error!("Missing default_level_width on single world project!")
-                    })?,
-                    identifier: "World".to_string(),
-                    iid: project_json.iid,
-                    levels: project_json.levels,
-                    world_grid_height: project_json.world_grid_height.ok_or_else(|| {
-                        ldtk_import_error!("Missing world_grid_height on single world project!")
-                    })?,
-                    world_grid_width: project_json.world_grid_width.ok_or_else(|| {
-                        ldtk_import_error!("Missing world_grid_width on single world project!")
-                    })?,
-                    world_layout: project_json.world_layout.clone(),
-                },
-            )])
-        } else {
-            project_json
-                .worlds
-                .into_iter()
-                .map(|world_json| {
-                    let iid = Iid::parse_str(&world_json.iid)?;
-                    LdtkResult::Ok((iid, world_json))
+        let level_field_definitions = project_json
+            .defs
+            .level_fields
+            .into_iter()
+            .map(FieldDefinition::new)
+            .collect::<LdtkResult<_>>()?;
+
+        let mut worlds_json = worlds_json
+            .into_iter()
+            .map(|world_json| {
+                let iid = Iid::parse_str(&world_json.iid)?;
+                LdtkResult::Ok((iid, world_json))
+            })
+            .collect::<LdtkResult<IidMap<_>>>()?;
+
+        let external_level_paths = if project_json.external_levels {
+            worlds_json
+                .iter()
+                .map(|(world_iid, world_json)| {
+                    let level_paths = world_json
+                        .levels
+                        .iter()
+                        .map(|level_json| {
+                            let ldtk_path =
+                                level_json.external_rel_path.as_ref().ok_or_else(|| {
+                                    ldtk_import_error!(
+                                        "external_rel_path is `None` in an external_levels project?"
+                                    )
+                                })?;
+                            let path = ldtk_path_to_asset_path(&project_directory, ldtk_path);
+                            LdtkResult::Ok((Iid::parse_str(&level_json.iid)?, path))
+                        })
+                        .collect::<LdtkResult<_>>()?;
+                    LdtkResult::Ok((*world_iid, level_paths))
                 })
                 .collect::<LdtkResult<_>>()?
+        } else {
+            IidMap::default()
         };
 
+        let mut excluded_level_iids = filter_worlds_json(&mut worlds_json, settings)?;
+
         let project_context = Arc::new(RwLock::new(ProjectContext {
-            tileset_definitions,
-            layer_definitions,
-            enum_definitions,
-            entity_definitions,
-            external_levels: project_json.external_levels,
+            tileset_definitions: tileset_definitions.clone(),
+            layer_definitions: layer_definitions.clone(),
+            enum_definitions: enum_definitions.clone(),
+            entity_definitions: entity_definitions.clone(),
+            field_definitions,
+            entity_ref_ids: IidMap::default(),
+            entity_ref_handles: Vec::new(),
+            excluded_level_iids: IidSet::default(),
+            dangling_entity_refs: settings.dangling_entity_refs,
+            load_images: settings.load_images,
+            load_entities: settings.load_entities,
+            ldtk_icons: settings.ldtk_icons.clone(),
             project_directory,
+            external_level_paths,
+            level_project_json,
         }));
 
+        if let Some(level_predicate) = &settings.level_predicate {
+            project_context.write()?.excluded_level_iids = worlds_json
+                .values()
+                .flat_map(|world_json| world_json.levels.iter())
+                .map(|level_json| Iid::parse_str(&level_json.iid))
+                .collect::<Result<_, _>>()?;
+
+            excluded_level_iids.extend(
+                filter_levels_json_by_predicate(
+                    &mut worlds_json,
+                    level_predicate,
+                    project_context.clone(),
+                )
+                .await?,
+            );
+        }
+
+        {
+            let mut project_context = project_context.write()?;
+            project_context.excluded_level_iids = excluded_level_iids;
+            if settings.load_entities {
+                (
+                    project_context.entity_ref_ids,
+                    project_context.entity_ref_handles,
+                ) = construct_entity_ref_ids(&worlds_json, load_context)?;
+            }
+        }
+
         let worlds =
             construct_worlds_from_world_json(worlds_json, project_context, load_context).await?;
 
         Ok(Self {
             iid,
             ldtk_version,
+            tileset_definitions,
+            layer_definitions,
+            enum_definitions,
+            entity_definitions,
             worlds,
+            level_field_definitions,
+            source,
         })
     }
 }
 
+// The project's JSON without its levels and table of contents, which are rebuilt from the assets
+// when saving.
+fn project_source(project_json: &mut ldtk::LdtkProject) -> ldtk::LdtkProject {
+    let levels = std::mem::take(&mut project_json.levels);
+    let world_levels: Vec<_> = project_json
+        .worlds
+        .iter_mut()
+        .map(|world| std::mem::take(&mut world.levels))
+        .collect();
+    let toc = std::mem::take(&mut project_json.toc);
+
+    let source = project_json.clone();
+
+    project_json.levels = levels;
+    project_json
+        .worlds
+        .iter_mut()
+        .zip(world_levels)
+        .for_each(|(world, levels)| world.levels = levels);
+    project_json.toc = toc;
+
+    source
+}
+
 impl LdtkAsset for Project {
     fn get_identifier(&self) -> &str {
         "project"
@@ -186,6 +317,12 @@ impl LdtkAsset for Project {
     }
 }
 
+impl LdtkAssetWithFieldDefinitions for Project {
+    fn get_field_definitions(&self) -> &[FieldDefinition] {
+        &self.level_field_definitions
+    }
+}
+
 // impl LdtkAssetWithChildren<World> for Project {
 //     fn get_children(&self) -> impl Iterator<Item = &Handle<World>> {
 //         self.worlds.values()
@@ -0,0 +1,32 @@
+use crate::ldtk;
+use crate::ldtk_import_error;
+use crate::result::LdtkResult;
+
+// Takes the worlds out of the project. If we're not a multi-world project, then we simply
+// construct a single [ldtk::World] named `World`, sharing the project's Iid. This saves us lots of
+// complexity when we transpose to our [crate::world::World] object, and is shared with the level
+// loader and [crate::ldtk_data] so that all of them see the same worlds.
+pub fn worlds_json(project_json: &mut ldtk::LdtkProject) -> LdtkResult<Vec<ldtk::World>> {
+    if !project_json.worlds.is_empty() {
+        return Ok(std::mem::take(&mut project_json.worlds));
+    }
+
+    macro_rules! required {
+        ($field:ident) => {
+            project_json.$field.ok_or_else(|| {
+                ldtk_import_error!("Missing {} on single world project!", stringify!($field))
+            })?
+        };
+    }
+
+    Ok(vec![ldtk::World {
+        default_level_height: required!(default_level_height),
+        default_level_width: required!(default_level_width),
+        identifier: "World".to_string(),
+        iid: project_json.iid.clone(),
+        levels: std::mem::take(&mut project_json.levels),
+        world_grid_height: required!(world_grid_height),
+        world_grid_width: required!(world_grid_width),
+        world_layout: project_json.world_layout.clone(),
+    }])
+}
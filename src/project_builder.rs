@@ -0,0 +1,858 @@
+//! Building LDtk projects in code, rather than in the LDtk editor.
+//!
+//! A [ProjectBuilder] holds the definitions of a project, and the levels built from them with a
+//! [LevelBuilder] and its [LayerBuilder]s. Iids are generated for everything which is built, and
+//! uids are given out by the project, so that they stay consistent across its definitions and
+//! levels:
+//!
+//! ```ignore
+//! let mut project = ProjectBuilder::new("maps/generated.ldtk");
+//! let walls = project.add_int_grid_layer("Walls", 16, &[(1, "Wall", Color::BLACK)]);
+//! let things = project.add_entities_layer("Things", 16);
+//! let player = project.add_entity("Player", I64Vec2::splat(16), Color::WHITE);
+//!
+//! let mut level = LevelBuilder::new("Level_0", I64Vec2::new(256, 256));
+//!
+//! let mut walls_layer = LayerBuilder::new(walls);
+//! (0..16).for_each(|x| walls_layer.set_int_grid(I64Vec2::new(x, 0), 1));
+//! level.add_layer(walls_layer);
+//!
+//! let mut things_layer = LayerBuilder::new(things);
+//! let player_iid = things_layer.add_entity(player, I64Vec2::new(2, 3));
+//! level.add_layer(things_layer);
+//!
+//! project.add_level(level);
+//! ```
+//!
+//! The finished project can then either be written out as LDtk JSON with [ProjectBuilder::save],
+//! or loaded as a [Project] asset with [ProjectBuilder::load].
+//!
+//! Built projects are single world projects with a [crate::world::WorldLayout::Free] layout. The
+//! neighbours of each level are left for LDtk to find, the next time the project is saved in the
+//! editor.
+
+use std::path::Path;
+
+use bevy_asset::io::embedded::EmbeddedAssetRegistry;
+use bevy_asset::{AssetPath, AssetServer, Handle};
+use bevy_color::Color;
+use bevy_math::I64Vec2;
+use bevy_platform::collections::HashMap;
+
+use crate::color::ldtk_string_from_bevy_color;
+use crate::iid::Iid;
+use crate::ldtk;
+use crate::ldtk::migration::{SCHEMA_APP_BUILD_ID, SCHEMA_VERSION};
+use crate::ldtk_export_error;
+use crate::ldtk_path::bevy_path_to_ldtk_path;
+use crate::project::Project;
+use crate::project_saver::{ProjectSaverSettings, SavedProject, write_project_json};
+use crate::result::LdtkResult;
+use crate::uid::{Uid, UidMap};
+
+// LDtk's defaults for a new project.
+const DEFAULT_GRID_SIZE: i64 = 16;
+const DEFAULT_LEVEL_SIZE: i64 = 256;
+const DEFAULT_WORLD_GRID_SIZE: i64 = 256;
+const DEFAULT_BG_COLOR: &str = "#40465B";
+const DEFAULT_LEVEL_BG_COLOR: &str = "#696A79";
+
+/// Builds an LDtk project in code.
+///
+/// See the [module level documentation](self).
+#[derive(Debug)]
+pub struct ProjectBuilder {
+    // Where the project will be loaded from, which relative paths are written against.
+    path: AssetPath<'static>,
+    iid: Iid,
+    dummy_world_iid: Iid,
+    next_uid: Uid,
+    tileset_definitions: Vec<ldtk::TilesetDefinition>,
+    layer_definitions: Vec<ldtk::LayerDefinition>,
+    entity_definitions: Vec<ldtk::EntityDefinition>,
+    levels: Vec<(Uid, LevelBuilder)>,
+}
+
+impl ProjectBuilder {
+    /// Starts an empty project, which will be loaded from the given asset path.
+    ///
+    /// Relative paths in the project, such as those of tileset images, are written relative to
+    /// the directory of this path. It is also the path [ProjectBuilder::load] gives the project.
+    pub fn new(path: impl Into<AssetPath<'static>>) -> Self {
+        Self {
+            path: path.into(),
+            iid: Iid::new_v4(),
+            dummy_world_iid: Iid::new_v4(),
+            next_uid: 0,
+            tileset_definitions: Vec::new(),
+            layer_definitions: Vec::new(),
+            entity_definitions: Vec::new(),
+            levels: Vec::new(),
+        }
+    }
+
+    /// The [Iid] of the project.
+    pub const fn iid(&self) -> Iid {
+        self.iid
+    }
+
+    /// Adds a tileset definition, and returns its uid.
+    ///
+    /// The image is given as a Bevy asset path. As LDtk does not read the image here either, its
+    /// size in pixels must be given.
+    pub fn add_tileset(
+        &mut self,
+        identifier: impl Into<String>,
+        image: impl AsRef<Path>,
+        image_size: I64Vec2,
+        tile_grid_size: i64,
+    ) -> Uid {
+        let uid = self.next_uid();
+        let rel_path = self
+            .path
+            .parent()
+            .map(|project_directory| bevy_path_to_ldtk_path(&project_directory, image));
+
+        self.tileset_definitions.push(ldtk::TilesetDefinition {
+            c_hei: image_size.y / tile_grid_size.max(1),
+            c_wid: image_size.x / tile_grid_size.max(1),
+            cached_pixel_data: None,
+            custom_data: Vec::new(),
+            embed_atlas: None,
+            enum_tags: Vec::new(),
+            identifier: identifier.into(),
+            padding: 0,
+            px_hei: image_size.y,
+            px_wid: image_size.x,
+            rel_path,
+            saved_selections: Vec::new(),
+            spacing: 0,
+            tags: Vec::new(),
+            tags_source_enum_uid: None,
+            tile_grid_size,
+            uid,
+        });
+
+        uid
+    }
+
+    /// Adds an IntGrid layer definition with the given values, and returns its uid.
+    ///
+    /// Each value is given as its number, identifier, and the color LDtk draws it with. Values
+    /// start at 1, as 0 marks an empty cell.
+    pub fn add_int_grid_layer(
+        &mut self,
+        identifier: impl Into<String>,
+        grid_cell_size: i64,
+        values: &[(i64, &str, Color)],
+    ) -> Uid {
+        let int_grid_values = values
+            .iter()
+            .map(|(value, identifier, color)| ldtk::IntGridValueDefinition {
+                color: ldtk_string_from_bevy_color(*color),
+                group_uid: 0,
+                identifier: Some((*identifier).to_string()),
+                tile: None,
+                value: *value,
+            })
+            .collect();
+
+        self.add_layer_definition(
+            identifier.into(),
+            ldtk::Type::IntGrid,
+            grid_cell_size,
+            int_grid_values,
+            None,
+        )
+    }
+
+    /// Adds a Tiles layer definition drawing from the given tileset, and returns its uid.
+    pub fn add_tiles_layer(
+        &mut self,
+        identifier: impl Into<String>,
+        grid_cell_size: i64,
+        tileset: Uid,
+    ) -> Uid {
+        self.add_layer_definition(
+            identifier.into(),
+            ldtk::Type::Tiles,
+            grid_cell_size,
+            Vec::new(),
+            Some(tileset),
+        )
+    }
+
+    /// Adds an Entities layer definition, and returns its uid.
+    pub fn add_entities_layer(
+        &mut self,
+        identifier: impl Into<String>,
+        grid_cell_size: i64,
+    ) -> Uid {
+        self.add_layer_definition(
+            identifier.into(),
+            ldtk::Type::Entities,
+            grid_cell_size,
+            Vec::new(),
+            None,
+        )
+    }
+
+    /// Adds an entity definition, and returns its uid.
+    ///
+    /// Entities are drawn as rectangles of the given size and color, anchored at their top left
+    /// corner.
+    pub fn add_entity(
+        &mut self,
+        identifier: impl Into<String>,
+        size: I64Vec2,
+        color: Color,
+    ) -> Uid {
+        let uid = self.next_uid();
+
+        self.entity_definitions.push(ldtk::EntityDefinition {
+            allow_out_of_bounds: false,
+            color: ldtk_string_from_bevy_color(color),
+            doc: None,
+            export_to_toc: false,
+            field_defs: Vec::new(),
+            fill_opacity: 0.08,
+            height: size.y,
+            hollow: false,
+            identifier: identifier.into(),
+            keep_aspect_ratio: false,
+            limit_behavior: ldtk::LimitBehavior::MoveLastOne,
+            limit_scope: ldtk::LimitScope::PerLevel,
+            line_opacity: 1.0,
+            max_count: 0,
+            max_height: None,
+            max_width: None,
+            min_height: None,
+            min_width: None,
+            nine_slice_borders: Vec::new(),
+            pivot_x: 0.0,
+            pivot_y: 0.0,
+            render_mode: ldtk::RenderMode::Rectangle,
+            resizable_x: false,
+            resizable_y: false,
+            show_name: true,
+            tags: Vec::new(),
+            tile_id: None,
+            tile_opacity: 1.0,
+            tile_rect: None,
+            tile_render_mode: ldtk::TileRenderMode::FitInside,
+            tileset_id: None,
+            uid,
+            ui_tile_rect: None,
+            width: size.x,
+        });
+
+        uid
+    }
+
+    /// Adds a level to the project, and returns its uid.
+    ///
+    /// Levels are ordered as they are added. The level is only checked against the definitions of
+    /// the project once the project is saved or loaded.
+    pub fn add_level(&mut self, mut level: LevelBuilder) -> Uid {
+        let uid = self.next_uid();
+
+        // The empty layers are made here, so that their Iids don't change between saves.
+        self.layer_definitions.iter().for_each(|layer_definition| {
+            if !level
+                .layers
+                .iter()
+                .any(|layer| layer.layer_definition == layer_definition.uid)
+            {
+                level.add_layer(LayerBuilder::new(layer_definition.uid));
+            }
+        });
+
+        self.levels.push((uid, level));
+        uid
+    }
+
+    /// Writes the project as LDtk JSON.
+    pub fn save(&self, settings: &ProjectSaverSettings) -> LdtkResult<SavedProject> {
+        let project_stem = self
+            .path
+            .path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| ldtk_export_error!("Unable to get the file name of {}!", self.path))?;
+
+        write_project_json(self.project_json()?, &project_stem, settings)
+    }
+
+    /// Loads the project through the [crate::project_loader::ProjectLoader], as any other
+    /// project.
+    ///
+    /// The project is placed in Bevy's `embedded://` asset source under its path, such as
+    /// `embedded://maps/generated.ldtk`. The relative paths in the project, such as those of
+    /// tileset images, still refer to the default asset source.
+    ///
+    /// Loading again under the same path, such as after adding more levels, reloads the
+    /// project in place and gives back the same handle.
+    pub fn load(
+        &self,
+        embedded_asset_registry: &EmbeddedAssetRegistry,
+        asset_server: &AssetServer,
+    ) -> LdtkResult<Handle<Project>> {
+        let saved = self.save(&ProjectSaverSettings::default())?;
+
+        embedded_asset_registry.insert_asset(Default::default(), self.path.path(), saved.project);
+
+        let path = format!("embedded://{}", self.path.path().display());
+
+        if let Some(handle) = asset_server.get_handle(&path) {
+            asset_server.reload(path);
+            Ok(handle)
+        } else {
+            Ok(asset_server.load(path))
+        }
+    }
+
+    const fn next_uid(&mut self) -> Uid {
+        let uid = self.next_uid;
+        self.next_uid += 1;
+        uid
+    }
+
+    // Layer definitions are listed from the top most layer down, so the first one added is drawn
+    // on top of the others.
+    fn add_layer_definition(
+        &mut self,
+        identifier: String,
+        layer_type: ldtk::Type,
+        grid_size: i64,
+        int_grid_values: Vec<ldtk::IntGridValueDefinition>,
+        tileset_def_uid: Option<Uid>,
+    ) -> Uid {
+        let uid = self.next_uid();
+
+        let layer_definition_type = match layer_type {
+            ldtk::Type::AutoLayer => "AutoLayer",
+            ldtk::Type::Entities => "Entities",
+            ldtk::Type::IntGrid => "IntGrid",
+            ldtk::Type::Tiles => "Tiles",
+        };
+
+        self.layer_definitions.push(ldtk::LayerDefinition {
+            layer_definition_type: layer_definition_type.to_string(),
+            auto_rule_groups: Vec::new(),
+            auto_source_layer_def_uid: None,
+            auto_tileset_def_uid: None,
+            auto_tiles_killed_by_other_layer_uid: None,
+            biome_field_uid: None,
+            can_select_when_inactive: true,
+            display_opacity: 1.0,
+            doc: None,
+            excluded_tags: Vec::new(),
+            grid_size,
+            guide_grid_hei: 0,
+            guide_grid_wid: 0,
+            hide_fields_when_inactive: false,
+            hide_in_list: false,
+            identifier,
+            inactive_opacity: 1.0,
+            int_grid_values,
+            int_grid_values_groups: Vec::new(),
+            parallax_factor_x: 0.0,
+            parallax_factor_y: 0.0,
+            parallax_scaling: true,
+            px_offset_x: 0,
+            px_offset_y: 0,
+            render_in_world_view: true,
+            required_tags: Vec::new(),
+            tile_pivot_x: 0.0,
+            tile_pivot_y: 0.0,
+            tileset_def_uid,
+            purple_type: layer_type,
+            ui_color: None,
+            uid,
+            ui_filter_tags: Vec::new(),
+            use_async_render: false,
+        });
+
+        self.levels
+            .iter_mut()
+            .for_each(|(_, level)| level.add_layer(LayerBuilder::new(uid)));
+
+        uid
+    }
+
+    fn project_json(&self) -> LdtkResult<ldtk::LdtkProject> {
+        let definitions = Definitions {
+            tilesets: self
+                .tileset_definitions
+                .iter()
+                .map(|definition| (definition.uid, definition))
+                .collect(),
+            layers: self
+                .layer_definitions
+                .iter()
+                .map(|definition| (definition.uid, definition))
+                .collect(),
+            entities: self
+                .entity_definitions
+                .iter()
+                .map(|definition| (definition.uid, definition))
+                .collect(),
+        };
+
+        let levels = self
+            .levels
+            .iter()
+            .map(|(uid, level)| self.level_json(*uid, level, &definitions))
+            .collect::<LdtkResult<_>>()?;
+
+        Ok(ldtk::LdtkProject {
+            forced_refs: None,
+            app_build_id: SCHEMA_APP_BUILD_ID,
+            backup_limit: 10,
+            backup_on_save: false,
+            backup_rel_path: None,
+            bg_color: DEFAULT_BG_COLOR.to_string(),
+            custom_commands: Vec::new(),
+            default_entity_height: DEFAULT_GRID_SIZE,
+            default_entity_width: DEFAULT_GRID_SIZE,
+            default_grid_size: DEFAULT_GRID_SIZE,
+            default_level_bg_color: DEFAULT_LEVEL_BG_COLOR.to_string(),
+            default_level_height: Some(DEFAULT_LEVEL_SIZE),
+            default_level_width: Some(DEFAULT_LEVEL_SIZE),
+            default_pivot_x: 0.0,
+            default_pivot_y: 0.0,
+            defs: ldtk::Definitions {
+                entities: self.entity_definitions.clone(),
+                enums: Vec::new(),
+                external_enums: Vec::new(),
+                layers: self.layer_definitions.clone(),
+                level_fields: Vec::new(),
+                tilesets: self.tileset_definitions.clone(),
+            },
+            dummy_world_iid: self.dummy_world_iid.to_string(),
+            export_level_bg: true,
+            export_png: None,
+            export_tiled: false,
+            external_levels: false,
+            flags: Vec::new(),
+            identifier_style: ldtk::IdentifierStyle::Capitalize,
+            iid: self.iid.to_string(),
+            image_export_mode: ldtk::ImageExportMode::None,
+            json_version: SCHEMA_VERSION.to_string(),
+            level_name_pattern: "Level_%idx".to_string(),
+            levels,
+            minify_json: false,
+            next_uid: self.next_uid,
+            png_file_pattern: None,
+            simplified_export: false,
+            toc: Vec::new(),
+            tutorial_desc: None,
+            world_grid_height: Some(DEFAULT_WORLD_GRID_SIZE),
+            world_grid_width: Some(DEFAULT_WORLD_GRID_SIZE),
+            world_layout: Some(ldtk::WorldLayout::Free),
+            worlds: Vec::new(),
+        })
+    }
+
+    fn level_json(
+        &self,
+        uid: Uid,
+        level: &LevelBuilder,
+        definitions: &Definitions,
+    ) -> LdtkResult<ldtk::Level> {
+        let mut layers: UidMap<&LayerBuilder> = UidMap::default();
+        for layer in &level.layers {
+            if !definitions.layers.contains_key(&layer.layer_definition) {
+                return Err(ldtk_export_error!(
+                    "Bad layer definition uid in level {}! given: {}",
+                    level.identifier,
+                    layer.layer_definition
+                ));
+            }
+
+            if layers.insert(layer.layer_definition, layer).is_some() {
+                return Err(ldtk_export_error!(
+                    "Two layers with the same layer definition in level {}! uid: {}",
+                    level.identifier,
+                    layer.layer_definition
+                ));
+            }
+        }
+
+        // LDtk expects one layer in every level for each layer definition, in the same order.
+        let layer_instances = self
+            .layer_definitions
+            .iter()
+            .map(|layer_definition| {
+                let layer = layers.get(&layer_definition.uid).ok_or_else(|| {
+                    ldtk_export_error!(
+                        "No layer for layer definition {} in level {}!",
+                        layer_definition.identifier,
+                        level.identifier
+                    )
+                })?;
+
+                layer_json(uid, level, layer, layer_definition, definitions)
+            })
+            .collect::<LdtkResult<_>>()?;
+
+        let bg_color = level.bg_color.map(ldtk_string_from_bevy_color);
+
+        Ok(ldtk::Level {
+            bg_color: bg_color
+                .clone()
+                .unwrap_or_else(|| DEFAULT_LEVEL_BG_COLOR.to_string()),
+            bg_pos: None,
+            neighbours: Vec::new(),
+            smart_color: bg_color
+                .clone()
+                .unwrap_or_else(|| DEFAULT_LEVEL_BG_COLOR.to_string()),
+            level_bg_color: bg_color,
+            bg_pivot_x: 0.5,
+            bg_pivot_y: 0.5,
+            level_bg_pos: None,
+            bg_rel_path: None,
+            external_rel_path: None,
+            field_instances: Vec::new(),
+            identifier: level.identifier.clone(),
+            iid: level.iid.to_string(),
+            layer_instances: Some(layer_instances),
+            px_hei: level.size.y,
+            px_wid: level.size.x,
+            uid,
+            use_auto_identifier: false,
+            world_depth: level.world_depth,
+            world_x: level.location.x,
+            world_y: level.location.y,
+        })
+    }
+}
+
+/// Builds a level for a [ProjectBuilder].
+///
+/// Layers which are not added to the level are left empty, including those of layer definitions
+/// which are added to the project later.
+#[derive(Debug)]
+pub struct LevelBuilder {
+    /// The identifier of the level, which should be unique in the project.
+    pub identifier: String,
+    /// The size of the level in pixels.
+    pub size: I64Vec2,
+    /// The location of the top left corner of the level in the world, in pixels, with the y axis
+    /// pointing down as in LDtk.
+    pub location: I64Vec2,
+    /// The depth of the level in the world.
+    pub world_depth: i64,
+    /// The background color of the level, or `None` for the default of the project.
+    pub bg_color: Option<Color>,
+    // Generated when the builder is created, so that it can be referred to before the project is
+    // finished.
+    iid: Iid,
+    layers: Vec<LayerBuilder>,
+}
+
+impl LevelBuilder {
+    /// Starts an empty level of the given size in pixels, at the origin of the world.
+    pub fn new(identifier: impl Into<String>, size: I64Vec2) -> Self {
+        Self {
+            identifier: identifier.into(),
+            size,
+            location: I64Vec2::ZERO,
+            world_depth: 0,
+            bg_color: None,
+            iid: Iid::new_v4(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// The [Iid] of the level.
+    pub const fn iid(&self) -> Iid {
+        self.iid
+    }
+
+    /// Adds a layer to the level. Each layer must have a different layer definition.
+    pub fn add_layer(&mut self, layer: LayerBuilder) {
+        self.layers.push(layer);
+    }
+}
+
+/// Builds a layer for a [LevelBuilder].
+///
+/// What a layer may hold depends on the type of its layer definition: IntGrid layers hold int
+/// grid values, Tiles layers hold tiles, and Entities layers hold entities. Anything else is an
+/// error once the project is saved or loaded.
+///
+/// Cells are given in grid coordinates of the layer, from the top left corner of the level.
+#[derive(Debug)]
+pub struct LayerBuilder {
+    layer_definition: Uid,
+    iid: Iid,
+    int_grid: HashMap<I64Vec2, i64>,
+    tiles: Vec<BuilderTile>,
+    entities: Vec<BuilderEntity>,
+}
+
+#[derive(Debug)]
+struct BuilderTile {
+    cell: I64Vec2,
+    tile_id: i64,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+#[derive(Debug)]
+struct BuilderEntity {
+    iid: Iid,
+    entity_definition: Uid,
+    cell: I64Vec2,
+}
+
+impl LayerBuilder {
+    /// Starts an empty layer of the given layer definition.
+    pub fn new(layer_definition: Uid) -> Self {
+        Self {
+            layer_definition,
+            iid: Iid::new_v4(),
+            int_grid: HashMap::default(),
+            tiles: Vec::new(),
+            entities: Vec::new(),
+        }
+    }
+
+    /// The [Iid] of the layer.
+    pub const fn iid(&self) -> Iid {
+        self.iid
+    }
+
+    /// Sets the int grid value of a cell, replacing any previous value. A value of 0 empties the
+    /// cell.
+    pub fn set_int_grid(&mut self, cell: I64Vec2, value: i64) {
+        self.int_grid.insert(cell, value);
+    }
+
+    /// Places a tile of the tileset in a cell. Tiles are numbered from the top left of the
+    /// tileset, row by row.
+    ///
+    /// Tiles placed later are drawn over earlier ones.
+    pub fn add_tile(&mut self, cell: I64Vec2, tile_id: i64) {
+        self.add_flipped_tile(cell, tile_id, false, false);
+    }
+
+    /// As [LayerBuilder::add_tile], mirroring the tile horizontally and/or vertically.
+    pub fn add_flipped_tile(&mut self, cell: I64Vec2, tile_id: i64, flip_x: bool, flip_y: bool) {
+        self.tiles.push(BuilderTile {
+            cell,
+            tile_id,
+            flip_x,
+            flip_y,
+        });
+    }
+
+    /// Places an entity of the given entity definition with its top left corner at a cell, and
+    /// returns its [Iid].
+    pub fn add_entity(&mut self, entity_definition: Uid, cell: I64Vec2) -> Iid {
+        let iid = Iid::new_v4();
+        self.entities.push(BuilderEntity {
+            iid,
+            entity_definition,
+            cell,
+        });
+        iid
+    }
+}
+
+struct Definitions<'a> {
+    tilesets: UidMap<&'a ldtk::TilesetDefinition>,
+    layers: UidMap<&'a ldtk::LayerDefinition>,
+    entities: UidMap<&'a ldtk::EntityDefinition>,
+}
+
+fn layer_json(
+    level_uid: Uid,
+    level: &LevelBuilder,
+    layer: &LayerBuilder,
+    layer_definition: &ldtk::LayerDefinition,
+    definitions: &Definitions,
+) -> LdtkResult<ldtk::LayerInstance> {
+    let identifier = &layer_definition.identifier;
+    let grid_size = layer_definition.grid_size;
+    if grid_size <= 0 {
+        return Err(ldtk_export_error!("Bad grid size for layer {identifier}!"));
+    }
+
+    // LDtk rounds up, so that the grid covers the whole level.
+    let c_wid = (level.size.x + grid_size - 1) / grid_size;
+    let c_hei = (level.size.y + grid_size - 1) / grid_size;
+
+    let coord_id = |cell: I64Vec2| -> LdtkResult<i64> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= c_wid || cell.y >= c_hei {
+            return Err(ldtk_export_error!(
+                "Cell {cell} is outside of layer {identifier} in level {}!",
+                level.identifier
+            ));
+        }
+        Ok(cell.y * c_wid + cell.x)
+    };
+
+    let mut layer_json = ldtk::LayerInstance {
+        c_hei,
+        c_wid,
+        grid_size,
+        identifier: identifier.clone(),
+        opacity: 1.0,
+        px_total_offset_x: layer_definition.px_offset_x,
+        px_total_offset_y: layer_definition.px_offset_y,
+        tileset_def_uid: None,
+        tileset_rel_path: None,
+        layer_instance_type: layer_definition.layer_definition_type.clone(),
+        auto_layer_tiles: Vec::new(),
+        entity_instances: Vec::new(),
+        grid_tiles: Vec::new(),
+        iid: layer.iid.to_string(),
+        int_grid: None,
+        int_grid_csv: Vec::new(),
+        layer_def_uid: layer_definition.uid,
+        level_id: level_uid,
+        optional_rules: Vec::new(),
+        override_tileset_uid: None,
+        px_offset_x: 0,
+        px_offset_y: 0,
+        seed: 0,
+        visible: true,
+    };
+
+    let unexpected = |what: &str| {
+        ldtk_export_error!(
+            "{what} in layer {identifier} of level {}, which is a {} layer!",
+            level.identifier,
+            layer_definition.layer_definition_type
+        )
+    };
+
+    match layer_definition.purple_type {
+        ldtk::Type::IntGrid | ldtk::Type::AutoLayer => {
+            if !layer.tiles.is_empty() {
+                return Err(unexpected("Tiles"));
+            }
+            if !layer.entities.is_empty() {
+                return Err(unexpected("Entities"));
+            }
+
+            let mut int_grid_csv = vec![0; (c_wid * c_hei) as usize];
+            for (cell, value) in &layer.int_grid {
+                if *value != 0
+                    && !layer_definition
+                        .int_grid_values
+                        .iter()
+                        .any(|int_grid_value| int_grid_value.value == *value)
+                {
+                    return Err(ldtk_export_error!(
+                        "Int grid value {value} is not defined for layer {identifier}!"
+                    ));
+                }
+                int_grid_csv[coord_id(*cell)? as usize] = *value;
+            }
+            layer_json.int_grid_csv = int_grid_csv;
+        }
+        ldtk::Type::Tiles => {
+            if !layer.int_grid.is_empty() {
+                return Err(unexpected("Int grid values"));
+            }
+            if !layer.entities.is_empty() {
+                return Err(unexpected("Entities"));
+            }
+
+            let tileset = layer_definition
+                .tileset_def_uid
+                .map(|uid| {
+                    definitions
+                        .tilesets
+                        .get(&uid)
+                        .copied()
+                        .ok_or_else(|| ldtk_export_error!("Bad tileset definition uid! {uid}"))
+                })
+                .transpose()?;
+
+            layer_json.tileset_def_uid = tileset.map(|tileset| tileset.uid);
+            layer_json.tileset_rel_path = tileset.and_then(|tileset| tileset.rel_path.clone());
+
+            layer_json.grid_tiles = layer
+                .tiles
+                .iter()
+                .map(|tile| {
+                    let tileset = tileset.ok_or_else(|| {
+                        ldtk_export_error!("Layer {identifier} has tiles, but no tileset!")
+                    })?;
+
+                    if tile.tile_id < 0 || tile.tile_id >= tileset.c_wid * tileset.c_hei {
+                        return Err(ldtk_export_error!(
+                            "Tile {} is outside of tileset {}!",
+                            tile.tile_id,
+                            tileset.identifier
+                        ));
+                    }
+
+                    let stride = tileset.tile_grid_size + tileset.spacing;
+
+                    Ok(ldtk::TileInstance {
+                        a: 1.0,
+                        d: vec![coord_id(tile.cell)?],
+                        f: i64::from(tile.flip_x) | (i64::from(tile.flip_y) << 1),
+                        px: vec![tile.cell.x * grid_size, tile.cell.y * grid_size],
+                        src: vec![
+                            tileset.padding + (tile.tile_id % tileset.c_wid) * stride,
+                            tileset.padding + (tile.tile_id / tileset.c_wid) * stride,
+                        ],
+                        t: tile.tile_id,
+                    })
+                })
+                .collect::<LdtkResult<_>>()?;
+        }
+        ldtk::Type::Entities => {
+            if !layer.int_grid.is_empty() {
+                return Err(unexpected("Int grid values"));
+            }
+            if !layer.tiles.is_empty() {
+                return Err(unexpected("Tiles"));
+            }
+
+            layer_json.entity_instances = layer
+                .entities
+                .iter()
+                .map(|entity| {
+                    let entity_definition = definitions
+                        .entities
+                        .get(&entity.entity_definition)
+                        .ok_or_else(|| {
+                            ldtk_export_error!(
+                                "Bad entity definition uid! {}",
+                                entity.entity_definition
+                            )
+                        })?;
+
+                    coord_id(entity.cell)?;
+                    let px = entity.cell * grid_size;
+
+                    Ok(ldtk::EntityInstance {
+                        grid: vec![entity.cell.x, entity.cell.y],
+                        identifier: entity_definition.identifier.clone(),
+                        pivot: vec![entity_definition.pivot_x, entity_definition.pivot_y],
+                        smart_color: entity_definition.color.clone(),
+                        tags: entity_definition.tags.clone(),
+                        tile: None,
+                        world_x: Some(level.location.x + layer_json.px_total_offset_x + px.x),
+                        world_y: Some(level.location.y + layer_json.px_total_offset_y + px.y),
+                        def_uid: entity_definition.uid,
+                        field_instances: Vec::new(),
+                        height: entity_definition.height,
+                        iid: entity.iid.to_string(),
+                        px: vec![px.x, px.y],
+                        width: entity_definition.width,
+                    })
+                })
+                .collect::<LdtkResult<_>>()?;
+        }
+    }
+
+    Ok(layer_json)
+}
@@ -1,29 +1,189 @@
+//! The [AssetLoader] for LDtk project files, and the settings which control it.
+//!
+//! By default the whole project is loaded: every world, level, layer and entity becomes a labeled
+//! sub asset, and every tileset and level background image is loaded. [ProjectLoaderSettings] can
+//! restrict this to a subset of the project:
+//!
+//! ```ignore
+//! let project: Handle<Project> = asset_server.load_with_settings(
+//!     "project.ldtk",
+//!     |settings: &mut ProjectLoaderSettings| {
+//!         settings.levels = LoadFilter::Identifiers(vec!["Lobby".to_string()]);
+//!         settings.load_images = false;
+//!     },
+//! );
+//! ```
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
 use bevy_asset::io::Reader;
-use bevy_asset::{AssetLoader, LoadContext};
+use bevy_asset::{AssetLoader, AssetPath, LoadContext};
+use bevy_platform::collections::HashMap;
 use bevy_reflect::Reflect;
+use serde::{Deserialize, Serialize};
 
+use crate::baked_project::parse_project_bytes;
+use crate::field_instance::FieldInstance;
+use crate::from_field_instance::{FromFieldInstance, extract_field};
+use crate::iid::Iid;
 use crate::project::Project;
 use crate::result::LdtkResult;
 
+/// How to handle an [crate::field_instance::EntityRef] whose target entity does not exist in the
+/// project.
+///
+/// LDtk can leave these behind after the referenced entity is deleted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum DanglingEntityRefs {
+    /// Fail to load the project.
+    #[default]
+    Error,
+    /// Log a warning, and leave [crate::field_instance::EntityRef::ids] as `None`.
+    Warn,
+}
+
+/// Selects which worlds or levels of a project are loaded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum LoadFilter {
+    /// Load everything.
+    #[default]
+    All,
+    /// Load only those with one of the given identifiers.
+    Identifiers(Vec<String>),
+    /// Load only those with one of the given [Iid]s.
+    Iids(Vec<Iid>),
+}
+
+impl LoadFilter {
+    /// Returns `true` if an asset with the given identifier and [Iid] passes this filter.
+    pub fn matches(&self, identifier: &str, iid: Iid) -> bool {
+        match self {
+            Self::All => true,
+            Self::Identifiers(identifiers) => identifiers.iter().any(|i| i == identifier),
+            Self::Iids(iids) => iids.contains(&iid),
+        }
+    }
+}
+
+/// What a [LevelPredicate] is given to decide whether a level is loaded.
+#[derive(Debug)]
+pub struct LevelSummary<'a> {
+    /// The identifier of the [crate::world::World] containing the level.
+    pub world_identifier: &'a str,
+    /// The identifier of the level.
+    pub identifier: &'a str,
+    /// The [Iid] of the level.
+    pub iid: Iid,
+    /// The field instances of the level.
+    ///
+    /// Any [crate::field_instance::EntityRef] here will have no ids.
+    pub field_instances: &'a HashMap<String, FieldInstance>,
+}
+
+impl LevelSummary<'_> {
+    /// Extracts the field instance with the given identifier as a `T`.
+    ///
+    /// See [crate::from_field_instance] for the conversions available.
+    pub fn get_field<T: FromFieldInstance>(&self, identifier: &str) -> LdtkResult<T> {
+        let field_instance = self.field_instances.get(identifier);
+
+        extract_field(
+            identifier,
+            field_instance,
+            field_instance.map(|field_instance| field_instance.field_definition.as_ref()),
+        )
+    }
+}
+
+/// A user supplied test, run against every level which passes [ProjectLoaderSettings::levels].
+///
+/// Levels for which this returns `false` are not loaded.
+#[derive(Clone)]
+pub struct LevelPredicate(pub Arc<dyn Fn(&LevelSummary) -> bool + Send + Sync>);
+
+impl LevelPredicate {
+    /// Wraps the given closure.
+    pub fn new(predicate: impl Fn(&LevelSummary) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(predicate))
+    }
+}
+
+impl Debug for LevelPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LevelPredicate")
+    }
+}
+
+/// Settings for the [ProjectLoader].
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectLoaderSettings {
+    /// How to handle an [crate::field_instance::EntityRef] whose target entity does not exist.
+    pub dangling_entity_refs: DanglingEntityRefs,
+    /// Which worlds to load.
+    ///
+    /// A single world project has one world, with the identifier `World`.
+    pub worlds: LoadFilter,
+    /// Which levels to load, from within the loaded worlds.
+    pub levels: LoadFilter,
+    /// An optional test on each level's field instances, run after [Self::levels].
+    ///
+    /// This cannot be serialized, so it is not available in `.meta` files.
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub level_predicate: Option<LevelPredicate>,
+    /// Load the tileset and level background images.
+    ///
+    /// When `false`, every image handle in the project is `None`.
+    pub load_images: bool,
+    /// Create an [crate::entity::EntityInstance] sub asset for every LDtk entity.
+    ///
+    /// When `false`, every entities layer is left empty.
+    pub load_entities: bool,
+    /// The path, within the app's assets, of a copy of LDtk's internal icon atlas image.
+    ///
+    /// LDtk can embed its own `LdtkIcons` atlas into a tileset definition. This image is not
+    /// distributed with this crate for licensing reasons, so a project with such a tileset fails
+    /// to load unless a copy is supplied here. See
+    /// <https://github.com/stinkytoe/bevy_ldtk_asset/issues/35>.
+    pub ldtk_icons: Option<AssetPath<'static>>,
+}
+
+impl Default for ProjectLoaderSettings {
+    fn default() -> Self {
+        Self {
+            dangling_entity_refs: DanglingEntityRefs::default(),
+            worlds: LoadFilter::default(),
+            levels: LoadFilter::default(),
+            level_predicate: None,
+            load_images: true,
+            load_entities: true,
+            ldtk_icons: None,
+        }
+    }
+}
+
+/// Loads an LDtk project file (`.ldtk`) as a [Project], along with all of its labeled sub assets.
 #[derive(Default, Reflect)]
 pub struct ProjectLoader;
 
 impl AssetLoader for ProjectLoader {
     type Asset = Project;
-    type Settings = ();
+    type Settings = ProjectLoaderSettings;
     type Error = crate::error::LdtkError;
 
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> LdtkResult<Self::Asset> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let ldtk_project = serde_json::from_slice(&bytes)?;
+        let ldtk_project = parse_project_bytes(&bytes)?;
 
-        let project = Project::new(ldtk_project, load_context).await?;
+        let project = Project::new(ldtk_project, settings, load_context).await?;
 
         Ok(project)
     }
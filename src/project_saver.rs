@@ -0,0 +1,1022 @@
+//! Writing a [Project], along with all of its sub assets, back to LDtk JSON.
+//!
+//! This allows maps to be authored or modified programmatically, such as by procedural generation
+//! or a migration script, and then opened again in the LDtk editor:
+//!
+//! ```ignore
+//! fn save(project: Res<MyProject>, ldtk_assets: LdtkAssets) -> Result {
+//!     let saved = save_project(&project.handle, &ldtk_assets, &ProjectSaverSettings::default())?;
+//!     saved.write("assets/maps/project.ldtk")?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The project is written as LDtk JSON version 1.5.3, whichever version it was loaded from. Iids
+//! and uids are preserved. Whatever this crate does not represent as an asset, such as the
+//! definitions with their auto layer rules, is written back as it was in the loaded file.
+//! Values which LDtk derives, such as the table of contents and the id of every tile, are
+//! computed again from the assets.
+//!
+//! Relative paths, such as those of tileset images and `FilePath` fields, are written relative to
+//! the directory the project was loaded from, so the project should be written back there.
+//!
+//! Only a project which was fully loaded can be saved, as anything left out by the
+//! [crate::project_loader::ProjectLoaderSettings] would otherwise be lost.
+//!
+//! Entities are written in the order LDtk listed them, see [crate::entity::EntityInstance::index].
+
+use std::path::{Path, PathBuf};
+
+use bevy_asset::{AssetPath, Handle};
+use bevy_platform::collections::HashMap;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::anchor::ldtk_pivot_from_bevy_anchor;
+use crate::color::{ldtk_int_from_bevy_color, ldtk_string_from_bevy_color};
+use crate::entity::EntityInstance;
+use crate::field_instance::{FieldInstance, FieldInstanceType};
+use crate::layer::{LayerInstance, LayerType, TilesLayer};
+use crate::ldtk;
+use crate::ldtk::migration::{SCHEMA_APP_BUILD_ID, SCHEMA_VERSION};
+use crate::ldtk_assets::LdtkAssets;
+use crate::ldtk_export_error;
+use crate::ldtk_path::bevy_path_to_ldtk_path;
+use crate::level::Level;
+use crate::project::Project;
+use crate::result::LdtkResult;
+use crate::tile_instance::TileInstance;
+use crate::tileset_definition::TilesetDefinition;
+use crate::tileset_rectangle::TilesetRectangle;
+use crate::uid::{Uid, UidMap};
+use crate::world::{World, WorldLayout};
+
+// LDtk's defaults for a new world.
+const DEFAULT_LEVEL_SIZE: i64 = 256;
+const DEFAULT_WORLD_GRID_SIZE: i64 = 256;
+
+/// Settings for [save_project].
+#[derive(Clone, Debug, Default)]
+pub struct ProjectSaverSettings {
+    /// Save every level into its own `.ldtkl` file, as with the "Save levels to separate files"
+    /// option of LDtk.
+    ///
+    /// As LDtk does, the files are placed in a directory named after the project:
+    /// `project/Level_0.ldtkl` for `project.ldtk`.
+    pub external_levels: bool,
+}
+
+/// The files which make up a saved project.
+#[derive(Clone, Debug)]
+pub struct SavedProject {
+    /// The contents of the `.ldtk` file.
+    pub project: Vec<u8>,
+    /// The external level files. Empty unless [ProjectSaverSettings::external_levels] was set.
+    pub levels: Vec<SavedLevel>,
+}
+
+/// An external level file of a [SavedProject].
+#[derive(Clone, Debug)]
+pub struct SavedLevel {
+    /// The path of the file, relative to the directory of the project.
+    pub path: PathBuf,
+    /// The contents of the `.ldtkl` file.
+    pub json: Vec<u8>,
+}
+
+impl SavedProject {
+    /// Writes the project to the given path, and its external levels relative to it, creating
+    /// any missing directories for the levels.
+    pub fn write(&self, project_path: impl AsRef<Path>) -> LdtkResult<()> {
+        let project_path = project_path.as_ref();
+        let project_directory = project_path.parent().unwrap_or_else(|| Path::new(""));
+
+        std::fs::write(project_path, &self.project)?;
+
+        self.levels.iter().try_for_each(|level| {
+            let path = project_directory.join(&level.path);
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory)?;
+            }
+            std::fs::write(path, &level.json)?;
+            Ok(())
+        })
+    }
+}
+
+/// Writes the given project, and all of its sub assets, as LDtk JSON.
+///
+/// The project must have been loaded from a file, in full, and all of its sub assets must be
+/// loaded. See [LdtkAssets::is_project_loaded].
+pub fn save_project(
+    project: &Handle<Project>,
+    ldtk_assets: &LdtkAssets,
+    settings: &ProjectSaverSettings,
+) -> LdtkResult<SavedProject> {
+    let project_path = project
+        .path()
+        .ok_or_else(|| ldtk_export_error!("Only a project loaded from a file can be saved!"))?;
+
+    let project_asset = ldtk_assets
+        .projects
+        .get(project.id())
+        .ok_or_else(|| ldtk_export_error!("Project {project_path} is not loaded!"))?;
+
+    let source = project_asset.source.as_deref().ok_or_else(|| {
+        ldtk_export_error!(
+            "Project {project_path} was only partially loaded, and cannot be saved! \
+             See ProjectLoaderSettings."
+        )
+    })?;
+
+    let project_directory = project_path
+        .parent()
+        .ok_or_else(|| ldtk_export_error!("Unable to get project_directory!"))?;
+
+    let project_stem = project_path
+        .path()
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .ok_or_else(|| ldtk_export_error!("Unable to get the file name of {project_path}!"))?;
+
+    ProjectWriter::new(source, ldtk_assets, project_directory).write(
+        project_asset,
+        &project_stem,
+        settings,
+    )
+}
+
+// Both project and level files begin with a header describing the application which wrote them.
+#[derive(Serialize)]
+struct LdtkFile<'a, T> {
+    #[serde(rename = "__header__")]
+    header: LdtkHeader,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkHeader {
+    file_type: &'static str,
+    app: &'static str,
+    doc: &'static str,
+    schema: &'static str,
+    app_author: &'static str,
+    app_version: String,
+    url: &'static str,
+}
+
+impl Default for LdtkHeader {
+    fn default() -> Self {
+        Self {
+            file_type: "LDtk Project JSON",
+            app: "LDtk",
+            doc: "https://ldtk.io/json",
+            schema: "https://ldtk.io/files/JSON_SCHEMA.json",
+            app_author: "Sebastien 'deepnight' Benard",
+            app_version: SCHEMA_VERSION.to_string(),
+            url: "https://ldtk.io",
+        }
+    }
+}
+
+struct ProjectWriter<'a> {
+    source: &'a ldtk::LdtkProject,
+    ldtk_assets: &'a LdtkAssets<'a>,
+    project_directory: AssetPath<'a>,
+    tileset_definitions: UidMap<&'a ldtk::TilesetDefinition>,
+    layer_definitions: UidMap<&'a ldtk::LayerDefinition>,
+    entity_definitions: UidMap<&'a ldtk::EntityDefinition>,
+    field_definitions: UidMap<&'a ldtk::FieldDefinition>,
+}
+
+impl<'a> ProjectWriter<'a> {
+    fn new(
+        source: &'a ldtk::LdtkProject,
+        ldtk_assets: &'a LdtkAssets<'a>,
+        project_directory: AssetPath<'a>,
+    ) -> Self {
+        let defs = &source.defs;
+
+        Self {
+            source,
+            ldtk_assets,
+            project_directory,
+            tileset_definitions: defs.tilesets.iter().map(|def| (def.uid, def)).collect(),
+            layer_definitions: defs.layers.iter().map(|def| (def.uid, def)).collect(),
+            entity_definitions: defs.entities.iter().map(|def| (def.uid, def)).collect(),
+            field_definitions: defs
+                .level_fields
+                .iter()
+                .chain(defs.entities.iter().flat_map(|def| &def.field_defs))
+                .map(|def| (def.uid, def))
+                .collect(),
+        }
+    }
+
+    fn write(
+        &self,
+        project: &Project,
+        project_stem: &str,
+        settings: &ProjectSaverSettings,
+    ) -> LdtkResult<SavedProject> {
+        let worlds = self.worlds(project)?;
+
+        let mut worlds_json = worlds
+            .iter()
+            .map(|(world, levels)| self.world_json(world, levels))
+            .collect::<LdtkResult<Vec<_>>>()?;
+
+        let toc = self.table_of_contents(&worlds_json);
+
+        let mut project_json = ldtk::LdtkProject {
+            iid: project.iid.to_string(),
+            app_build_id: SCHEMA_APP_BUILD_ID,
+            json_version: SCHEMA_VERSION.to_string(),
+            toc,
+            ..self.source.clone()
+        };
+
+        if self.source.worlds.is_empty() {
+            // A single world project keeps its world in the project itself.
+            let world = worlds_json
+                .pop()
+                .ok_or_else(|| ldtk_export_error!("A single world project without a world!"))?;
+
+            if !worlds_json.is_empty() {
+                return Err(ldtk_export_error!(
+                    "A single world project with more than one world!"
+                ));
+            }
+
+            project_json.levels = world.levels;
+            project_json.default_level_width = Some(world.default_level_width);
+            project_json.default_level_height = Some(world.default_level_height);
+            project_json.world_grid_width = Some(world.world_grid_width);
+            project_json.world_grid_height = Some(world.world_grid_height);
+            project_json.world_layout = world.world_layout;
+        } else {
+            project_json.worlds = worlds_json;
+        }
+
+        write_project_json(project_json, project_stem, settings)
+    }
+
+    // Every world of the project with its levels, in the order LDtk had them.
+    fn worlds(&self, project: &'a Project) -> LdtkResult<Vec<(&'a World, Vec<&'a Level>)>> {
+        let mut worlds = project
+            .worlds
+            .values()
+            .map(|handle| {
+                let world = self
+                    .ldtk_assets
+                    .worlds
+                    .get(handle.id())
+                    .ok_or_else(|| ldtk_export_error!("World not loaded! {handle:?}"))?;
+
+                let mut levels = world
+                    .levels
+                    .values()
+                    .map(|handle| {
+                        self.ldtk_assets
+                            .levels
+                            .get(handle.id())
+                            .ok_or_else(|| ldtk_export_error!("Level not loaded! {handle:?}"))
+                    })
+                    .collect::<LdtkResult<Vec<_>>>()?;
+                levels.sort_by_key(|level| level.index);
+
+                LdtkResult::Ok((world, levels))
+            })
+            .collect::<LdtkResult<Vec<_>>>()?;
+
+        // Worlds which are new to the project go last.
+        worlds.sort_by_cached_key(|(world, _)| {
+            self.source
+                .worlds
+                .iter()
+                .position(|world_json| world_json.iid == world.iid.to_string())
+                .unwrap_or(usize::MAX)
+        });
+
+        Ok(worlds)
+    }
+
+    fn world_json(&self, world: &World, levels: &[&Level]) -> LdtkResult<ldtk::World> {
+        let source = &self.source;
+        let world_source = source
+            .worlds
+            .iter()
+            .find(|world_json| world_json.iid == world.iid.to_string());
+
+        let (default_level_width, default_level_height, world_grid_width, world_grid_height) =
+            world_source.map_or_else(
+                || {
+                    (
+                        source.default_level_width.unwrap_or(DEFAULT_LEVEL_SIZE),
+                        source.default_level_height.unwrap_or(DEFAULT_LEVEL_SIZE),
+                        source.world_grid_width.unwrap_or(DEFAULT_WORLD_GRID_SIZE),
+                        source.world_grid_height.unwrap_or(DEFAULT_WORLD_GRID_SIZE),
+                    )
+                },
+                |world_json| {
+                    (
+                        world_json.default_level_width,
+                        world_json.default_level_height,
+                        world_json.world_grid_width,
+                        world_json.world_grid_height,
+                    )
+                },
+            );
+
+        let (world_layout, world_grid_width, world_grid_height) = match world.world_layout {
+            WorldLayout::Free => (ldtk::WorldLayout::Free, world_grid_width, world_grid_height),
+            WorldLayout::GridVania(grid_size) => {
+                (ldtk::WorldLayout::GridVania, grid_size.x, grid_size.y)
+            }
+            WorldLayout::LinearHorizontal => (
+                ldtk::WorldLayout::LinearHorizontal,
+                world_grid_width,
+                world_grid_height,
+            ),
+            WorldLayout::LinearVertical => (
+                ldtk::WorldLayout::LinearVertical,
+                world_grid_width,
+                world_grid_height,
+            ),
+        };
+
+        let levels = levels
+            .iter()
+            .map(|level| self.level_json(level))
+            .collect::<LdtkResult<_>>()?;
+
+        // The world of a single world project takes the Iid of the project, but LDtk gives it an
+        // Iid of its own, which is only seen in the table of contents.
+        let iid = match world_source {
+            None if source.worlds.is_empty() => source.dummy_world_iid.clone(),
+            _ => world.iid.to_string(),
+        };
+
+        Ok(ldtk::World {
+            default_level_height,
+            default_level_width,
+            identifier: world.identifier.clone(),
+            iid,
+            levels,
+            world_grid_height,
+            world_grid_width,
+            world_layout: Some(world_layout),
+        })
+    }
+
+    fn level_json(&self, level: &Level) -> LdtkResult<ldtk::Level> {
+        let source = level.source.as_ref();
+
+        let bg_color = ldtk_string_from_bevy_color(level.bg_color);
+
+        // LDtk only stores a level's color when it differs from the project default.
+        let level_bg_color = match source.map(|source| &source.level_bg_color) {
+            Some(Some(_)) => Some(bg_color.clone()),
+            _ if bg_color.eq_ignore_ascii_case(&self.source.default_level_bg_color) => None,
+            _ => Some(bg_color.clone()),
+        };
+
+        let (bg_pos, level_bg_pos, bg_rel_path) = match &level.background {
+            None => (None, None, None),
+            Some(background) => {
+                let bg_rel_path = background
+                    .image
+                    .as_ref()
+                    .and_then(|image| image.path())
+                    .map(|path| bevy_path_to_ldtk_path(&self.project_directory, path.path()))
+                    .or_else(|| source.and_then(|source| source.bg_rel_path.clone()))
+                    .ok_or_else(|| {
+                        ldtk_export_error!(
+                            "No path for the background image of level {}!",
+                            level.identifier
+                        )
+                    })?;
+
+                let bg_pos = ldtk::LevelBackgroundPosition {
+                    crop_rect: vec![
+                        background.crop_corner.x,
+                        background.crop_corner.y,
+                        background.crop_size.x,
+                        background.crop_size.y,
+                    ],
+                    scale: vec![background.scale.x, background.scale.y],
+                    top_left_px: vec![background.corner.x, background.corner.y],
+                };
+
+                let level_bg_pos = source
+                    .and_then(|source| source.level_bg_pos.clone())
+                    .unwrap_or(ldtk::BgPos::Unscaled);
+
+                (Some(bg_pos), Some(level_bg_pos), Some(bg_rel_path))
+            }
+        };
+
+        let mut layers = level
+            .layers
+            .values()
+            .map(|handle| {
+                self.ldtk_assets
+                    .layers
+                    .get(handle.id())
+                    .ok_or_else(|| ldtk_export_error!("Layer not loaded! {handle:?}"))
+            })
+            .collect::<LdtkResult<Vec<_>>>()?;
+        // LDtk lists the top most layer first.
+        layers.sort_by_key(|layer| std::cmp::Reverse(layer.index));
+
+        let layer_instances = layers
+            .into_iter()
+            .map(|layer| self.layer_json(layer))
+            .collect::<LdtkResult<_>>()?;
+
+        Ok(ldtk::Level {
+            bg_color: bg_color.clone(),
+            bg_pos,
+            neighbours: level
+                .neighbours
+                .iter()
+                .map(|neighbour| ldtk::NeighbourLevel {
+                    dir: neighbour.dir.ldtk_dir().to_string(),
+                    level_iid: neighbour.level_iid.to_string(),
+                    level_uid: None,
+                })
+                .collect(),
+            smart_color: source.map_or(bg_color, |source| source.smart_color.clone()),
+            level_bg_color,
+            bg_pivot_x: source.map_or(0.5, |source| source.bg_pivot.x),
+            bg_pivot_y: source.map_or(0.5, |source| source.bg_pivot.y),
+            level_bg_pos,
+            bg_rel_path,
+            external_rel_path: None,
+            field_instances: self
+                .field_instances_json(&level.field_instances, &self.source.defs.level_fields)?,
+            identifier: level.identifier.clone(),
+            iid: level.iid.to_string(),
+            layer_instances: Some(layer_instances),
+            px_hei: level.size.y,
+            px_wid: level.size.x,
+            uid: level.uid,
+            use_auto_identifier: source.is_some_and(|source| source.use_auto_identifier),
+            world_depth: level.world_depth,
+            world_x: level.location.x,
+            world_y: level.location.y,
+        })
+    }
+
+    fn layer_json(&self, layer: &LayerInstance) -> LdtkResult<ldtk::LayerInstance> {
+        let layer_def_uid = self
+            .ldtk_assets
+            .layer_definitions
+            .get(layer.layer_definition.id())
+            .map(|layer_definition| layer_definition.uid)
+            .ok_or_else(|| {
+                ldtk_export_error!("Layer definition of {} not loaded!", layer.identifier)
+            })?;
+
+        let layer_definition = self
+            .layer_definitions
+            .get(&layer_def_uid)
+            .ok_or_else(|| ldtk_export_error!("Bad layer definition uid! {layer_def_uid}"))?;
+
+        let source = layer.source.as_ref();
+
+        let mut layer_json = ldtk::LayerInstance {
+            c_hei: layer.grid_size.y,
+            c_wid: layer.grid_size.x,
+            grid_size: layer.grid_cell_size,
+            identifier: layer.identifier.clone(),
+            opacity: layer.opacity,
+            px_total_offset_x: layer.location.x,
+            px_total_offset_y: layer.location.y,
+            tileset_def_uid: None,
+            tileset_rel_path: None,
+            layer_instance_type: layer_definition.layer_definition_type.clone(),
+            auto_layer_tiles: Vec::new(),
+            entity_instances: Vec::new(),
+            grid_tiles: Vec::new(),
+            iid: layer.iid.to_string(),
+            int_grid: None,
+            int_grid_csv: Vec::new(),
+            layer_def_uid,
+            level_id: layer.level_id,
+            optional_rules: source.map_or_else(Vec::new, |source| source.optional_rules.clone()),
+            override_tileset_uid: None,
+            px_offset_x: layer.location.x - layer_definition.px_offset_x,
+            px_offset_y: layer.location.y - layer_definition.px_offset_y,
+            seed: source.map_or(0, |source| source.seed),
+            visible: source.is_none_or(|source| source.visible),
+        };
+
+        match &layer.layer_type {
+            LayerType::Entities(entities_layer) => {
+                let mut entities = entities_layer
+                    .entities
+                    .values()
+                    .map(|handle| {
+                        self.ldtk_assets
+                            .entities
+                            .get(handle.id())
+                            .ok_or_else(|| ldtk_export_error!("Entity not loaded! {handle:?}"))
+                    })
+                    .collect::<LdtkResult<Vec<_>>>()?;
+                entities.sort_by_key(|entity| entity.index);
+
+                layer_json.entity_instances = entities
+                    .into_iter()
+                    .map(|entity| self.entity_json(entity))
+                    .collect::<LdtkResult<_>>()?;
+            }
+            LayerType::Tiles(tiles_layer)
+            | LayerType::IntGrid(tiles_layer)
+            | LayerType::AutoLayer(tiles_layer) => {
+                let tileset = tiles_layer
+                    .tileset_definition
+                    .as_ref()
+                    .map(|handle| self.tileset_definition(handle))
+                    .transpose()?;
+
+                layer_json.tileset_def_uid = tileset.map(|tileset| tileset.uid);
+                layer_json.tileset_rel_path = tileset.and_then(|tileset| tileset.rel_path.clone());
+                layer_json.override_tileset_uid = layer_json
+                    .tileset_def_uid
+                    .filter(|uid| Some(*uid) != layer_definition.tileset_def_uid);
+                layer_json.int_grid_csv = tiles_layer.int_grid.clone();
+
+                if layer_definition.layer_definition_type == "Tiles" {
+                    layer_json.grid_tiles = self.tiles_json(layer, tiles_layer, tileset, None)?;
+                } else {
+                    let auto_tile_rules = source.map_or(&[][..], |source| &source.auto_tile_rules);
+                    layer_json.auto_layer_tiles =
+                        self.tiles_json(layer, tiles_layer, tileset, Some(auto_tile_rules))?;
+                }
+            }
+        }
+
+        Ok(layer_json)
+    }
+
+    // The `d` field of every tile holds the coordinate id of the tile in the layer, preceded by
+    // the uid of the rule which placed it for auto layer tiles.
+    fn tiles_json(
+        &self,
+        layer: &LayerInstance,
+        tiles_layer: &TilesLayer,
+        tileset: Option<&ldtk::TilesetDefinition>,
+        auto_tile_rules: Option<&[Uid]>,
+    ) -> LdtkResult<Vec<ldtk::TileInstance>> {
+        if tiles_layer.tiles.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let tileset = tileset.ok_or_else(|| {
+            ldtk_export_error!("Layer {} has tiles, but no tileset!", layer.identifier)
+        })?;
+
+        if let Some(auto_tile_rules) = auto_tile_rules
+            && auto_tile_rules.len() < tiles_layer.tiles.len()
+        {
+            return Err(ldtk_export_error!(
+                "Layer {} has auto layer tiles which were not placed by any rule!",
+                layer.identifier
+            ));
+        }
+
+        tiles_layer
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(index, tile)| {
+                let coord_id = (tile.offset.y / layer.grid_cell_size) * layer.grid_size.x
+                    + tile.offset.x / layer.grid_cell_size;
+
+                let d = auto_tile_rules.map_or_else(
+                    || vec![coord_id],
+                    |auto_tile_rules| vec![auto_tile_rules[index], coord_id],
+                );
+
+                tile_json(tile, tileset, d)
+            })
+            .collect()
+    }
+
+    fn entity_json(&self, entity: &EntityInstance) -> LdtkResult<ldtk::EntityInstance> {
+        let def_uid = self
+            .ldtk_assets
+            .entity_definitions
+            .get(entity.entity_definition.id())
+            .map(|entity_definition| entity_definition.uid)
+            .ok_or_else(|| {
+                ldtk_export_error!("Entity definition of {} not loaded!", entity.identifier)
+            })?;
+
+        let entity_definition = self
+            .entity_definitions
+            .get(&def_uid)
+            .ok_or_else(|| ldtk_export_error!("Bad entity definition uid! {def_uid}"))?;
+
+        Ok(ldtk::EntityInstance {
+            grid: vec![entity.grid.x, entity.grid.y],
+            identifier: entity.identifier.clone(),
+            pivot: ldtk_pivot_from_bevy_anchor(&entity.anchor)
+                .map(widen)
+                .to_vec(),
+            smart_color: ldtk_string_from_bevy_color(entity.smart_color),
+            tags: entity.tags.clone(),
+            tile: entity
+                .tile
+                .as_ref()
+                .map(|tile| self.tileset_rectangle_json(tile))
+                .transpose()?,
+            world_x: entity.world_location.map(|world_location| world_location.x),
+            world_y: entity.world_location.map(|world_location| world_location.y),
+            def_uid,
+            field_instances: self
+                .field_instances_json(&entity.field_instances, &entity_definition.field_defs)?,
+            height: entity.size.y,
+            iid: entity.iid.to_string(),
+            px: vec![entity.location.x, entity.location.y],
+            width: entity.size.x,
+        })
+    }
+
+    // Field instances are written in the order of their definitions, as LDtk does.
+    fn field_instances_json(
+        &self,
+        field_instances: &HashMap<String, FieldInstance>,
+        field_definitions: &[ldtk::FieldDefinition],
+    ) -> LdtkResult<Vec<ldtk::FieldInstance>> {
+        if let Some(identifier) = field_instances.keys().find(|identifier| {
+            !field_definitions
+                .iter()
+                .any(|field_definition| &field_definition.identifier == *identifier)
+        }) {
+            return Err(ldtk_export_error!(
+                "Field instance {identifier} has no field definition!"
+            ));
+        }
+
+        field_definitions
+            .iter()
+            .filter_map(|field_definition| {
+                field_instances
+                    .get(&field_definition.identifier)
+                    .map(|field_instance| (&field_definition.identifier, field_instance))
+            })
+            .map(|(identifier, field_instance)| {
+                let field_definition = self
+                    .field_definitions
+                    .get(&field_instance.def_uid)
+                    .ok_or_else(|| {
+                        ldtk_export_error!("Bad field definition uid! {}", field_instance.def_uid)
+                    })?;
+
+                let (value, real_editor_values) = self.field_value_json(field_instance)?;
+
+                Ok(ldtk::FieldInstance {
+                    identifier: identifier.clone(),
+                    tile: field_instance
+                        .tileset_rectangle
+                        .as_ref()
+                        .map(|tile| self.tileset_rectangle_json(tile))
+                        .transpose()?,
+                    field_instance_type: field_definition.field_definition_type.clone(),
+                    value: Some(value),
+                    def_uid: field_instance.def_uid,
+                    real_editor_values,
+                })
+            })
+            .collect()
+    }
+
+    // Returns the `__value` of a field, along with its `realEditorValues`. The latter is what the
+    // LDtk editor reads back, in its own internal format.
+    fn field_value_json(
+        &self,
+        field_instance: &FieldInstance,
+    ) -> LdtkResult<(Value, Vec<Option<Value>>)> {
+        if let Some(elements) = field_instance.array_elements() {
+            // Null elements keep their place in `realEditorValues`, as a null entry.
+            let (values, real_editor_values): (Vec<_>, Vec<_>) = elements
+                .iter()
+                .map(|element| match element {
+                    FieldInstanceType::Null(_) => Ok((Value::Null, vec![None])),
+                    element => self.field_element_value_json(element),
+                })
+                .collect::<LdtkResult<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+
+            return Ok((
+                Value::Array(values),
+                real_editor_values.into_iter().flatten().collect(),
+            ));
+        }
+
+        self.field_element_value_json(&field_instance.field_instance_type)
+    }
+
+    // The same as [Self::field_value_json], for a single (non-array) value.
+    fn field_element_value_json(
+        &self,
+        field_instance_type: &FieldInstanceType,
+    ) -> LdtkResult<(Value, Vec<Option<Value>>)> {
+        fn editor_value(id: &str, param: impl Into<Value>) -> Vec<Option<Value>> {
+            vec![Some(json!({ "id": id, "params": [param.into()] }))]
+        }
+
+        Ok(match field_instance_type {
+            FieldInstanceType::Null(_) => (Value::Null, Vec::new()),
+            FieldInstanceType::Bool(value) => ((*value).into(), editor_value("V_Bool", *value)),
+            FieldInstanceType::Color(color) => (
+                ldtk_string_from_bevy_color(*color).into(),
+                editor_value("V_Int", ldtk_int_from_bevy_color(*color)),
+            ),
+            FieldInstanceType::EntityRef(entity_ref) => (
+                json!({
+                    "entityIid": entity_ref.entity_iid.to_string(),
+                    "layerIid": entity_ref.layer_iid.to_string(),
+                    "levelIid": entity_ref.level_iid.to_string(),
+                    "worldIid": entity_ref.world_iid.to_string(),
+                }),
+                editor_value("V_String", entity_ref.entity_iid.to_string()),
+            ),
+            FieldInstanceType::Enum(enum_value) => (
+                enum_value.value().into(),
+                editor_value("V_String", enum_value.value()),
+            ),
+            FieldInstanceType::FilePath(path) => {
+                let path = bevy_path_to_ldtk_path(&self.project_directory, path);
+                (path.clone().into(), editor_value("V_String", path))
+            }
+            FieldInstanceType::Float(value) => ((*value).into(), editor_value("V_Float", *value)),
+            FieldInstanceType::Int(value) => ((*value).into(), editor_value("V_Int", *value)),
+            FieldInstanceType::Multilines(value) | FieldInstanceType::String(value) => (
+                value.clone().into(),
+                editor_value("V_String", value.clone()),
+            ),
+            FieldInstanceType::Point(point) => (
+                json!({ "cx": point.x, "cy": point.y }),
+                editor_value("V_String", format!("{},{}", point.x, point.y)),
+            ),
+            FieldInstanceType::Tile(tile) => (
+                serde_json::to_value(self.tileset_rectangle_json(tile)?)?,
+                editor_value(
+                    "V_String",
+                    format!(
+                        "{},{},{},{}",
+                        tile.corner.x, tile.corner.y, tile.size.x, tile.size.y
+                    ),
+                ),
+            ),
+            array => {
+                return Err(ldtk_export_error!(
+                    "Unexpected array field instance! {}",
+                    array.type_name()
+                ));
+            }
+        })
+    }
+
+    fn tileset_definition(
+        &self,
+        handle: &Handle<TilesetDefinition>,
+    ) -> LdtkResult<&'a ldtk::TilesetDefinition> {
+        let uid = self
+            .ldtk_assets
+            .tileset_definitions
+            .get(handle.id())
+            .map(|tileset_definition| tileset_definition.uid)
+            .ok_or_else(|| ldtk_export_error!("Tileset definition not loaded! {handle:?}"))?;
+
+        self.tileset_definitions
+            .get(&uid)
+            .copied()
+            .ok_or_else(|| ldtk_export_error!("Bad tileset definition uid! {uid}"))
+    }
+
+    fn tileset_rectangle_json(
+        &self,
+        tileset_rectangle: &TilesetRectangle,
+    ) -> LdtkResult<ldtk::TilesetRectangle> {
+        Ok(ldtk::TilesetRectangle {
+            h: tileset_rectangle.size.y,
+            tileset_uid: self
+                .tileset_definition(&tileset_rectangle.tileset_definition)?
+                .uid,
+            w: tileset_rectangle.size.x,
+            x: tileset_rectangle.corner.x,
+            y: tileset_rectangle.corner.y,
+        })
+    }
+
+    // Every entity whose definition is marked to be exported to the table of contents.
+    fn table_of_contents(&self, worlds: &[ldtk::World]) -> Vec<ldtk::LdtkTableOfContentEntry> {
+        let old_format = self
+            .source
+            .flags
+            .contains(&ldtk::Flag::ExportOldTableOfContentData);
+
+        self.source
+            .defs
+            .entities
+            .iter()
+            .filter(|entity_definition| entity_definition.export_to_toc)
+            .map(|entity_definition| {
+                let instances_data: Vec<_> = worlds
+                    .iter()
+                    .flat_map(|world| world.levels.iter().map(move |level| (world, level)))
+                    .flat_map(|(world, level)| {
+                        level
+                            .layer_instances
+                            .iter()
+                            .flatten()
+                            .map(move |layer| (world, level, layer))
+                    })
+                    .flat_map(|(world, level, layer)| {
+                        layer
+                            .entity_instances
+                            .iter()
+                            .filter(|entity| entity.def_uid == entity_definition.uid)
+                            .map(move |entity| toc_instance_data(world, level, layer, entity))
+                    })
+                    .map(|mut instance_data| {
+                        instance_data.fields = instance_data
+                            .fields
+                            .map(|fields| self.toc_fields(entity_definition, fields));
+                        instance_data
+                    })
+                    .collect();
+
+                ldtk::LdtkTableOfContentEntry {
+                    identifier: entity_definition.identifier.clone(),
+                    instances: Some(if old_format {
+                        instances_data
+                            .iter()
+                            .map(|instance_data| instance_data.iids.clone())
+                            .collect()
+                    } else {
+                        Vec::new()
+                    }),
+                    instances_data,
+                }
+            })
+            .collect()
+    }
+
+    // Only the fields marked to be exported to the table of contents are kept.
+    fn toc_fields(&self, entity_definition: &ldtk::EntityDefinition, fields: Value) -> Value {
+        let Value::Object(fields) = fields else {
+            return fields;
+        };
+
+        Value::Object(
+            fields
+                .into_iter()
+                .filter(|(identifier, _)| {
+                    entity_definition.field_defs.iter().any(|field_definition| {
+                        &field_definition.identifier == identifier && field_definition.export_to_toc
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+// Writes out a project with all of its levels embedded, moving them to external level files if
+// asked to.
+pub(crate) fn write_project_json(
+    mut project_json: ldtk::LdtkProject,
+    project_stem: &str,
+    settings: &ProjectSaverSettings,
+) -> LdtkResult<SavedProject> {
+    let minify = project_json.minify_json;
+
+    let mut levels = Vec::new();
+    if settings.external_levels {
+        project_json
+            .levels
+            .iter_mut()
+            .chain(
+                project_json
+                    .worlds
+                    .iter_mut()
+                    .flat_map(|world| &mut world.levels),
+            )
+            .try_for_each(|level| {
+                let path = format!("{project_stem}/{}.ldtkl", level.identifier);
+
+                levels.push(SavedLevel {
+                    path: PathBuf::from(&path),
+                    json: to_json(&*level, minify)?,
+                });
+
+                level.layer_instances = None;
+                level.external_rel_path = Some(path);
+
+                LdtkResult::Ok(())
+            })?;
+    }
+    project_json.external_levels = settings.external_levels;
+
+    Ok(SavedProject {
+        project: to_json(&project_json, minify)?,
+        levels,
+    })
+}
+
+fn to_json(value: &impl Serialize, minify: bool) -> LdtkResult<Vec<u8>> {
+    // Going through a [Value] sorts the keys of every object, including those kept in hash
+    // maps, so that saving the same project twice gives the same file.
+    let file = serde_json::to_value(LdtkFile {
+        header: LdtkHeader::default(),
+        value,
+    })?;
+
+    Ok(if minify {
+        serde_json::to_vec(&file)?
+    } else {
+        serde_json::to_vec_pretty(&file)?
+    })
+}
+
+fn toc_instance_data(
+    world: &ldtk::World,
+    level: &ldtk::Level,
+    layer: &ldtk::LayerInstance,
+    entity: &ldtk::EntityInstance,
+) -> ldtk::LdtkTocInstanceData {
+    let fields = entity
+        .field_instances
+        .iter()
+        .map(|field_instance| {
+            (
+                field_instance.identifier.clone(),
+                field_instance.value.clone().unwrap_or(Value::Null),
+            )
+        })
+        .collect();
+
+    let px = |index: usize| entity.px.get(index).copied().unwrap_or_default();
+
+    ldtk::LdtkTocInstanceData {
+        fields: Some(Value::Object(fields)),
+        hei_px: entity.height,
+        iids: ldtk::ReferenceToAnEntityInstance {
+            entity_iid: entity.iid.clone(),
+            layer_iid: layer.iid.clone(),
+            level_iid: level.iid.clone(),
+            world_iid: world.iid.clone(),
+        },
+        wid_px: entity.width,
+        world_x: entity
+            .world_x
+            .unwrap_or_else(|| level.world_x + layer.px_total_offset_x + px(0)),
+        world_y: entity
+            .world_y
+            .unwrap_or_else(|| level.world_y + layer.px_total_offset_y + px(1)),
+    }
+}
+
+fn tile_json(
+    tile: &TileInstance,
+    tileset: &ldtk::TilesetDefinition,
+    d: Vec<i64>,
+) -> LdtkResult<ldtk::TileInstance> {
+    // The id of a tile counts the tiles of its tileset from the top left, row by row.
+    let stride = tileset.tile_grid_size + tileset.spacing;
+    if stride <= 0 {
+        return Err(ldtk_export_error!(
+            "Bad tile grid size for tileset {}!",
+            tileset.identifier
+        ));
+    }
+    let t = ((tile.source.y - tileset.padding) / stride) * tileset.c_wid
+        + (tile.source.x - tileset.padding) / stride;
+
+    let f = i64::from(tile.flip_x) | (i64::from(tile.flip_y) << 1);
+
+    Ok(ldtk::TileInstance {
+        a: widen(tile.opacity),
+        d,
+        f,
+        px: vec![tile.offset.x, tile.offset.y],
+        src: vec![tile.source.x, tile.source.y],
+        t,
+    })
+}
+
+// Widens through the shortest decimal representation, so that an `f32` read from `0.7` is
+// written back as `0.7` rather than `0.699999988079071`.
+fn widen(value: f32) -> f64 {
+    value
+        .to_string()
+        .parse()
+        .unwrap_or_else(|_| f64::from(value))
+}
@@ -0,0 +1,355 @@
+//! Drawing layers and levels into an [Image] on the CPU.
+//!
+//! [LdtkRasterizer] composites a [LayerInstance], or an entire [Level], into a new image without
+//! touching the GPU. This makes it suitable for minimaps, thumbnails, and golden image tests of
+//! maps which run headless in CI:
+//!
+//! ```rust,ignore
+//! fn thumbnail(ldtk_assets: LdtkAssets, images: Res<Assets<Image>>) -> LdtkResult<Image> {
+//!     let rasterizer = LdtkRasterizer::from_ldtk_assets(&ldtk_assets, &images);
+//!     let level = ldtk_assets.levels.get(level_handle.id()).unwrap();
+//!     rasterizer.rasterize_level(level)
+//! }
+//! ```
+//!
+//! Levels are drawn the same way as in LDtk:
+//!
+//! * the level is filled with [Level::bg_color],
+//! * the [LevelBackground] image, if any, is cropped, scaled and placed at its corner,
+//! * then each tiles layer is drawn at its [LayerInstance::location] with its
+//!   [LayerInstance::opacity], in ascending [LayerInstance::index] order.
+//!
+//! Within a layer, the cells of its [TilesLayer::int_grid] are filled with their
+//! [IntGridValue::color](crate::layer_definition::IntGridValue::color), and its tiles are drawn
+//! over them, honoring each tile's flips and opacity. Entities layers are not drawn.
+//!
+//! Colors are blended in sRGB space, as LDtk does, and the resulting image is
+//! [TextureFormat::Rgba8UnormSrgb]. Source images must be uncompressed, see
+//! [Image::get_color_at].
+//!
+//! The assets being drawn, along with their images, must already be loaded. Tiles are skipped for
+//! layers without a [TilesLayer::tileset_image], such as when the project was loaded with
+//! [crate::project_loader::ProjectLoaderSettings::load_images] disabled.
+
+use bevy_asset::{Asset, Assets, Handle, RenderAssetUsages};
+use bevy_color::{Alpha, ColorToPacked, Srgba};
+use bevy_image::Image;
+use bevy_math::{DVec2, I64Vec2, UVec2};
+use wgpu_types::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::error::LdtkError;
+use crate::layer::{LayerInstance, TilesLayer};
+use crate::layer_definition::LayerDefinition;
+use crate::ldtk_assets::LdtkAssets;
+use crate::level::{Level, LevelBackground};
+use crate::result::LdtkResult;
+use crate::tile_mesh::get_tiles_layer;
+use crate::tileset_definition::TilesetDefinition;
+
+/// Draws layers and levels into [Image]s, see the [module](self) docs.
+///
+/// This only borrows the asset collections it reads from, so it is cheap to create wherever it is
+/// needed.
+#[allow(missing_docs)]
+#[derive(Clone, Copy)]
+pub struct LdtkRasterizer<'a> {
+    pub images: &'a Assets<Image>,
+    pub levels: &'a Assets<Level>,
+    pub layers: &'a Assets<LayerInstance>,
+    pub layer_definitions: &'a Assets<LayerDefinition>,
+    pub tileset_definitions: &'a Assets<TilesetDefinition>,
+}
+
+impl<'a> LdtkRasterizer<'a> {
+    /// Borrows the asset collections from an [LdtkAssets] system parameter.
+    pub fn from_ldtk_assets(ldtk_assets: &'a LdtkAssets<'_>, images: &'a Assets<Image>) -> Self {
+        Self {
+            images,
+            levels: &ldtk_assets.levels,
+            layers: &ldtk_assets.layers,
+            layer_definitions: &ldtk_assets.layer_definitions,
+            tileset_definitions: &ldtk_assets.tileset_definitions,
+        }
+    }
+
+    /// Draws a tiles layer into an image of its full size, [LayerInstance::grid_size] cells of
+    /// [LayerInstance::grid_cell_size] pixels.
+    ///
+    /// The layer's own [LayerInstance::opacity] is not applied, as it only affects how the layer is
+    /// composited into its level. Returns [LdtkError::NotATilesLayer] for an entities layer.
+    pub fn rasterize_layer(&self, layer_instance: &LayerInstance) -> LdtkResult<Image> {
+        Ok(self.layer_canvas(layer_instance)?.into_image())
+    }
+
+    /// Draws a level into an image of [Level::size], with all of its layers.
+    pub fn rasterize_level(&self, level: &Level) -> LdtkResult<Image> {
+        Ok(self.level_canvas(level)?.into_image())
+    }
+
+    pub(crate) fn level_canvas(&self, level: &Level) -> LdtkResult<Canvas> {
+        let mut canvas = Canvas::new(level.size, level.bg_color.to_srgba())?;
+
+        if let Some(background) = &level.background
+            && let Some(image) = &background.image
+        {
+            let image = get_asset(self.images, image)?;
+            canvas.draw_background(background, image)?;
+        }
+
+        let mut layer_instances = level
+            .layers
+            .values()
+            .map(|handle| get_asset(self.layers, handle))
+            .collect::<LdtkResult<Vec<_>>>()?;
+        layer_instances.sort_by_key(|layer_instance| layer_instance.index);
+
+        for layer_instance in layer_instances
+            .into_iter()
+            .filter(|layer_instance| layer_instance.layer_type.is_tiles_layer())
+        {
+            let layer_canvas = self.layer_canvas(layer_instance)?;
+            canvas.draw_canvas(
+                &layer_canvas,
+                layer_instance.location,
+                layer_instance.opacity as f32,
+            );
+        }
+
+        Ok(canvas)
+    }
+
+    fn layer_canvas(&self, layer_instance: &LayerInstance) -> LdtkResult<Canvas> {
+        let tiles_layer = get_tiles_layer(layer_instance)?;
+        let grid_cell_size = I64Vec2::splat(layer_instance.grid_cell_size);
+
+        let mut canvas = Canvas::new(layer_instance.grid_size * grid_cell_size, Srgba::NONE)?;
+
+        if !tiles_layer.int_grid.is_empty() {
+            let layer_definition =
+                get_asset(self.layer_definitions, &layer_instance.layer_definition)?;
+
+            // Cells holding a value which isn't defined, such as zero for an empty cell, are
+            // left empty.
+            for (index, value) in tiles_layer.int_grid.iter().enumerate() {
+                let Some(int_grid_value) = layer_definition.int_grid_values.get(value) else {
+                    continue;
+                };
+
+                let index = index as i64;
+                let cell = I64Vec2::new(
+                    index % layer_instance.grid_size.x,
+                    index / layer_instance.grid_size.x,
+                );
+                canvas.fill_rect(
+                    cell * grid_cell_size,
+                    grid_cell_size,
+                    int_grid_value.color.to_srgba(),
+                );
+            }
+        }
+
+        if let Some(tileset_definition) = &tiles_layer.tileset_definition
+            && let Some(image) = &tiles_layer.tileset_image
+        {
+            let tileset_definition = get_asset(self.tileset_definitions, tileset_definition)?;
+            let image = get_asset(self.images, image)?;
+            canvas.draw_tiles(tiles_layer, tileset_definition, image)?;
+        }
+
+        Ok(canvas)
+    }
+}
+
+pub(crate) fn get_asset<'a, A: Asset>(
+    assets: &'a Assets<A>,
+    handle: &Handle<A>,
+) -> LdtkResult<&'a A> {
+    assets.get(handle.id()).ok_or_else(|| {
+        LdtkError::AssetNotLoaded(
+            handle
+                .path()
+                .map_or_else(|| handle.id().to_string(), ToString::to_string),
+        )
+    })
+}
+
+// A buffer of non-premultiplied sRGB pixels, in rows from the top left corner.
+pub(crate) struct Canvas {
+    size: UVec2,
+    pixels: Vec<Srgba>,
+}
+
+impl Canvas {
+    pub(crate) fn new(size: I64Vec2, color: Srgba) -> LdtkResult<Self> {
+        let size = UVec2::new(u32::try_from(size.x)?, u32::try_from(size.y)?);
+
+        Ok(Self {
+            size,
+            pixels: vec![color; size.element_product() as usize],
+        })
+    }
+
+    fn index(&self, location: I64Vec2) -> Option<usize> {
+        let x = u32::try_from(location.x)
+            .ok()
+            .filter(|x| *x < self.size.x)?;
+        let y = u32::try_from(location.y)
+            .ok()
+            .filter(|y| *y < self.size.y)?;
+
+        Some((y * self.size.x + x) as usize)
+    }
+
+    // Draws `color` over the pixel at `location`, with its alpha scaled by `opacity`.
+    pub(crate) fn blend(&mut self, location: I64Vec2, color: Srgba, opacity: f32) {
+        let Some(index) = self.index(location) else {
+            return;
+        };
+
+        let destination = self.pixels[index];
+        let source_alpha = color.alpha * opacity;
+        let destination_alpha = destination.alpha * (1.0 - source_alpha);
+        let alpha = source_alpha + destination_alpha;
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let mix = |source: f32, destination: f32| {
+            source.mul_add(source_alpha, destination * destination_alpha) / alpha
+        };
+
+        self.pixels[index] = Srgba::new(
+            mix(color.red, destination.red),
+            mix(color.green, destination.green),
+            mix(color.blue, destination.blue),
+            alpha,
+        );
+    }
+
+    // The average of the pixels from `min` up to, but not including, `max`, weighted by their
+    // alpha. `None` if the region lies entirely outside of the canvas.
+    pub(crate) fn average(&self, min: I64Vec2, max: I64Vec2) -> Option<Srgba> {
+        let min = min.max(I64Vec2::ZERO);
+        let max = max.min(self.size.as_i64vec2());
+        if min.cmpge(max).any() {
+            return None;
+        }
+
+        let (mut red, mut green, mut blue, mut alpha) = (0.0, 0.0, 0.0, 0.0);
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let color = self.pixels[(y * self.size.x as i64 + x) as usize];
+                red += color.red * color.alpha;
+                green += color.green * color.alpha;
+                blue += color.blue * color.alpha;
+                alpha += color.alpha;
+            }
+        }
+
+        if alpha <= 0.0 {
+            return Some(Srgba::NONE);
+        }
+
+        let count = (max - min).element_product() as f32;
+        Some(Srgba::new(
+            red / alpha,
+            green / alpha,
+            blue / alpha,
+            alpha / count,
+        ))
+    }
+
+    pub(crate) fn fill_rect(&mut self, corner: I64Vec2, size: I64Vec2, color: Srgba) {
+        for y in 0..size.y {
+            for x in 0..size.x {
+                self.blend(corner + I64Vec2::new(x, y), color, 1.0);
+            }
+        }
+    }
+
+    fn draw_canvas(&mut self, other: &Self, corner: I64Vec2, opacity: f32) {
+        for (index, color) in other.pixels.iter().enumerate() {
+            let index = index as u32;
+            let location = UVec2::new(index % other.size.x, index / other.size.x).as_i64vec2();
+            self.blend(corner + location, *color, opacity);
+        }
+    }
+
+    fn draw_background(&mut self, background: &LevelBackground, image: &Image) -> LdtkResult<()> {
+        let size = (background.crop_size * background.scale)
+            .round()
+            .as_i64vec2();
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                // Sample the center of each destination pixel.
+                let source = background.crop_corner
+                    + (DVec2::new(x as f64, y as f64) + 0.5) / background.scale;
+                if let Some(color) = get_image_color(image, source.floor().as_i64vec2())? {
+                    self.blend(background.corner + I64Vec2::new(x, y), color, 1.0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_tiles(
+        &mut self,
+        tiles_layer: &TilesLayer,
+        tileset_definition: &TilesetDefinition,
+        image: &Image,
+    ) -> LdtkResult<()> {
+        let tile_size = tileset_definition.tile_grid_pixel_size;
+
+        for tile in &tiles_layer.tiles {
+            for y in 0..tile_size {
+                for x in 0..tile_size {
+                    let source = I64Vec2::new(
+                        if tile.flip_x { tile_size - 1 - x } else { x },
+                        if tile.flip_y { tile_size - 1 - y } else { y },
+                    );
+                    if let Some(color) = get_image_color(image, tile.source + source)? {
+                        self.blend(tile.offset + I64Vec2::new(x, y), color, tile.opacity);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn into_image(self) -> Image {
+        let data = self
+            .pixels
+            .into_iter()
+            .flat_map(|color| color.to_u8_array())
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: self.size.x,
+                height: self.size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+// `None` for locations outside of the image.
+fn get_image_color(image: &Image, location: I64Vec2) -> LdtkResult<Option<Srgba>> {
+    let (Ok(x), Ok(y)) = (u32::try_from(location.x), u32::try_from(location.y)) else {
+        return Ok(None);
+    };
+    if x >= image.width() || y >= image.height() {
+        return Ok(None);
+    }
+
+    let color = image.get_color_at(x, y)?.to_srgba();
+
+    Ok((color.alpha() > 0.0).then_some(color))
+}
@@ -0,0 +1,287 @@
+//! Spawning an LDtk project into the Bevy ECS world.
+//!
+//! Inserting a [LdtkProjectRoot] component onto an entity will, once the [Project] and all of its
+//! labeled sub assets have finished loading, spawn one Bevy entity per [World], [Level],
+//! [LayerInstance] and [EntityInstance] beneath it. Each spawned entity is attached to its parent
+//! with a [ChildOf] relationship, and carries a component pointing back at its source asset:
+//!
+//! * [LdtkWorld]
+//! * [LdtkLevel]
+//! * [LdtkLayer]
+//! * [LdtkEntity]
+//!
+//! Each spawned entity also carries an [LdtkIid], which keeps the [crate::iid_index::LdtkIidIndex]
+//! resource up to date.
+//!
+//! [Transform]s are derived from [Level::location], [LayerInstance::location] and
+//! [EntityInstance::location]. Since LDtk uses a y-axis which is positive down, the y component is
+//! inverted. The `z` component is taken from [Level::world_depth] for levels, and from
+//! [LayerInstance::index] for layers.
+//!
+//! Spawned LDtk entities are further populated by any constructors registered in the
+//! [LdtkEntityRegistry].
+//!
+//! If the root also carries a [LdtkStreaming] component, its levels are instead spawned and
+//! despawned by the [crate::streaming] subsystem.
+//!
+//! If the [Project] asset is modified (i.e. hot reloaded), the spawned hierarchy is despawned and
+//! then spawned again from the new data. If only a [Level] asset is modified, such as an external
+//! level file, only that level is despawned and spawned again.
+
+use bevy_asset::{AssetEvent, AssetId, Handle};
+use bevy_camera::visibility::Visibility;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::hierarchy::{ChildOf, Children};
+use bevy_ecs::message::MessageReader;
+use bevy_ecs::name::Name;
+use bevy_ecs::query::{Has, With, Without};
+use bevy_ecs::system::{Commands, Query, Res};
+use bevy_math::{I64Vec2, Vec3};
+use bevy_reflect::Reflect;
+use bevy_transform::components::Transform;
+
+use crate::entity::EntityInstance;
+use crate::entity_registry::{LdtkEntityContext, LdtkEntityRegistry};
+use crate::iid_index::LdtkIid;
+use crate::layer::LayerInstance;
+use crate::ldtk_asset_trait::{LdtkAsset, LdtkAssetWithChildren};
+use crate::ldtk_assets::LdtkAssets;
+use crate::level::Level;
+use crate::project::Project;
+use crate::streaming::LdtkStreaming;
+use crate::world::World;
+
+/// Add this component to an entity to have the referenced [Project] spawned beneath it.
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkProjectRoot(pub Handle<Project>);
+
+/// Marker component inserted onto a [LdtkProjectRoot] entity once its hierarchy has been spawned.
+#[derive(Clone, Component, Debug, Default, Reflect)]
+pub struct LdtkProjectSpawned;
+
+/// Points back at the [World] asset a spawned entity was created from.
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkWorld(pub Handle<World>);
+
+/// Points back at the [Level] asset a spawned entity was created from.
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkLevel(pub Handle<Level>);
+
+/// Points back at the [LayerInstance] asset a spawned entity was created from.
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkLayer(pub Handle<LayerInstance>);
+
+/// Points back at the [EntityInstance] asset a spawned entity was created from.
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkEntity(pub Handle<EntityInstance>);
+
+/// Converts a location in LDtk space into a Bevy translation, inverting the y-axis.
+pub const fn ldtk_location_to_translation(location: I64Vec2, z: f32) -> Vec3 {
+    Vec3::new(location.x as f32, -location.y as f32, z)
+}
+
+pub(crate) fn spawn_ldtk_projects(
+    mut commands: Commands,
+    roots: Query<(Entity, &LdtkProjectRoot, Has<LdtkStreaming>), Without<LdtkProjectSpawned>>,
+    ldtk_assets: LdtkAssets,
+    entity_registry: Res<LdtkEntityRegistry>,
+) {
+    roots
+        .iter()
+        .for_each(|(root, LdtkProjectRoot(handle), streaming)| {
+            let Some(project) = ldtk_assets.projects.get(handle.id()) else {
+                return;
+            };
+
+            if !ldtk_assets.is_project_loaded(project) {
+                return;
+            }
+
+            let mut spawner = LdtkSpawner {
+                commands: &mut commands,
+                ldtk_assets: &ldtk_assets,
+                entity_registry: &entity_registry,
+            };
+
+            project.worlds.values().for_each(|world_handle| {
+                // Levels of a streamed project are spawned by the streaming system instead.
+                spawner.spawn_world(root, world_handle, !streaming);
+            });
+
+            commands.entity(root).insert(LdtkProjectSpawned);
+        });
+}
+
+pub(crate) fn despawn_modified_ldtk_projects(
+    mut commands: Commands,
+    mut project_events: MessageReader<AssetEvent<Project>>,
+    roots: Query<(Entity, &LdtkProjectRoot, Option<&Children>), With<LdtkProjectSpawned>>,
+    worlds: Query<(), With<LdtkWorld>>,
+) {
+    project_events.read().for_each(|event| {
+        let AssetEvent::Modified { id } = event else {
+            return;
+        };
+
+        roots
+            .iter()
+            .filter(|(_, LdtkProjectRoot(handle), _)| handle.id() == *id)
+            .for_each(|(root, _, children)| {
+                children
+                    .into_iter()
+                    .flatten()
+                    .filter(|child| worlds.contains(**child))
+                    .for_each(|child| commands.entity(*child).despawn());
+
+                commands.entity(root).remove::<LdtkProjectSpawned>();
+            });
+    });
+}
+
+// Runs after [despawn_modified_ldtk_projects], so the levels of a modified project are already
+// gone, and are spawned again along with the rest of their project.
+pub(crate) fn respawn_modified_ldtk_levels(
+    mut commands: Commands,
+    mut level_events: MessageReader<AssetEvent<Level>>,
+    levels: Query<(Entity, &LdtkLevel, &ChildOf)>,
+    ldtk_assets: LdtkAssets,
+    entity_registry: Res<LdtkEntityRegistry>,
+) {
+    let modified: Vec<AssetId<Level>> = level_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    if modified.is_empty() {
+        return;
+    }
+
+    let mut spawner = LdtkSpawner {
+        commands: &mut commands,
+        ldtk_assets: &ldtk_assets,
+        entity_registry: &entity_registry,
+    };
+
+    levels
+        .iter()
+        .filter(|(_, LdtkLevel(handle), _)| modified.contains(&handle.id()))
+        .for_each(|(entity, LdtkLevel(handle), child_of)| {
+            spawner.commands.entity(entity).despawn();
+            spawner.spawn_level(child_of.parent(), handle);
+        });
+}
+
+pub(crate) struct LdtkSpawner<'a, 'w, 's> {
+    pub(crate) commands: &'a mut Commands<'w, 's>,
+    pub(crate) ldtk_assets: &'a LdtkAssets<'a>,
+    pub(crate) entity_registry: &'a LdtkEntityRegistry,
+}
+
+impl LdtkSpawner<'_, '_, '_> {
+    fn spawn_world(&mut self, parent: Entity, handle: &Handle<World>, spawn_levels: bool) {
+        let Some(world) = self.ldtk_assets.worlds.get(handle.id()) else {
+            return;
+        };
+
+        let entity = self
+            .commands
+            .spawn((
+                LdtkWorld(handle.clone()),
+                LdtkIid(world.get_iid()),
+                Name::new(world.get_identifier().to_string()),
+                ChildOf(parent),
+            ))
+            .id();
+
+        if spawn_levels {
+            world.get_children().for_each(|level_handle| {
+                self.spawn_level(entity, level_handle);
+            });
+        }
+    }
+
+    pub(crate) fn spawn_level(&mut self, parent: Entity, handle: &Handle<Level>) -> Option<Entity> {
+        let level = self.ldtk_assets.levels.get(handle.id())?;
+
+        let translation = ldtk_location_to_translation(level.location, level.world_depth as f32);
+
+        let entity = self
+            .commands
+            .spawn((
+                LdtkLevel(handle.clone()),
+                LdtkIid(level.get_iid()),
+                Name::new(level.get_identifier().to_string()),
+                Transform::from_translation(translation),
+                ChildOf(parent),
+            ))
+            .id();
+
+        level.get_children().for_each(|layer_handle| {
+            self.spawn_layer(entity, layer_handle);
+        });
+
+        Some(entity)
+    }
+
+    fn spawn_layer(&mut self, parent: Entity, handle: &Handle<LayerInstance>) {
+        let Some(layer) = self.ldtk_assets.layers.get(handle.id()) else {
+            return;
+        };
+
+        let translation = ldtk_location_to_translation(layer.location, layer.index as f32);
+
+        let entity = self
+            .commands
+            .spawn((
+                LdtkLayer(handle.clone()),
+                LdtkIid(layer.get_iid()),
+                Name::new(layer.get_identifier().to_string()),
+                Transform::from_translation(translation),
+                ChildOf(parent),
+            ))
+            .id();
+
+        layer.get_children().for_each(|entity_handle| {
+            self.spawn_entity(entity, layer, entity_handle);
+        });
+    }
+
+    fn spawn_entity(
+        &mut self,
+        parent: Entity,
+        layer: &LayerInstance,
+        handle: &Handle<EntityInstance>,
+    ) {
+        let Some(entity_instance) = self.ldtk_assets.entities.get(handle.id()) else {
+            return;
+        };
+
+        let translation = ldtk_location_to_translation(entity_instance.location, 0.0);
+
+        let mut entity_commands = self.commands.spawn((
+            LdtkEntity(handle.clone()),
+            LdtkIid(entity_instance.get_iid()),
+            Name::new(entity_instance.get_identifier().to_string()),
+            Transform::from_translation(translation),
+            ChildOf(parent),
+        ));
+
+        let context = LdtkEntityContext {
+            entity: entity_commands.id(),
+            handle,
+            layer,
+        };
+
+        self.entity_registry
+            .construct(entity_instance, &context, &mut entity_commands);
+    }
+}
@@ -0,0 +1,295 @@
+//! Streaming levels in and out around a focus entity.
+//!
+//! Large worlds, such as a GridVania, do not need every level spawned at once. Inserting a
+//! [LdtkStreaming] component alongside a [LdtkProjectRoot] changes how the project is spawned:
+//! its worlds are spawned as usual, but its levels are only spawned while they are near the
+//! [LdtkStreaming::focus] entity, which is usually the camera or the player.
+//!
+//! ```ignore
+//! fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     let camera = commands.spawn(Camera2d).id();
+//!
+//!     commands.spawn((
+//!         LdtkProjectRoot(asset_server.load("gridvania.ldtk")),
+//!         LdtkStreaming {
+//!             focus: camera,
+//!             range: LdtkStreamingRange::Radius {
+//!                 spawn: 256.0,
+//!                 despawn: 512.0,
+//!             },
+//!         },
+//!     ));
+//! }
+//! ```
+//!
+//! Nearness is measured in one of two ways, see [LdtkStreamingRange]. Each has a separate spawn
+//! and despawn range, so a focus moving back and forth along a boundary does not repeatedly
+//! spawn and despawn the same level.
+//!
+//! A [LdtkLevelStreamedIn] message is written whenever a level is spawned, and a
+//! [LdtkLevelStreamedOut] message whenever one is despawned.
+//!
+//! Streaming is applied to every [World] of the project independently. To stream a single world
+//! of a multi world project, filter the others out with the
+//! [crate::project_loader::ProjectLoaderSettings].
+
+use std::collections::VecDeque;
+
+use bevy_asset::Handle;
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::hierarchy::Children;
+use bevy_ecs::message::{Message, MessageWriter};
+use bevy_ecs::query::With;
+use bevy_ecs::system::{Commands, Query, Res};
+use bevy_math::Vec2;
+use bevy_reflect::Reflect;
+use bevy_transform::components::GlobalTransform;
+
+use crate::entity_registry::LdtkEntityRegistry;
+use crate::iid::{Iid, IidMap};
+use crate::iid_index::LdtkIid;
+use crate::ldtk_assets::LdtkAssets;
+use crate::level::Level;
+use crate::spawn::{LdtkLevel, LdtkProjectSpawned, LdtkSpawner, LdtkWorld};
+use crate::world::World;
+
+#[cfg(doc)]
+use crate::spawn::LdtkProjectRoot;
+
+/// Add this component next to a [LdtkProjectRoot] to only spawn the levels near the
+/// [LdtkStreaming::focus] entity.
+#[derive(Clone, Component, Debug, Reflect)]
+pub struct LdtkStreaming {
+    /// The entity which levels are streamed around. Its [GlobalTransform] is compared against
+    /// the project root's, so it does not need to be a descendant of the project root.
+    ///
+    /// If the focus entity does not exist, no levels are spawned or despawned.
+    pub focus: Entity,
+    /// How near to the focus a level must be to be spawned, and how far to be despawned.
+    pub range: LdtkStreamingRange,
+}
+
+/// How the distance between the focus and a level is measured.
+///
+/// A level is spawned once it is within the `spawn` range, and despawned once it is beyond the
+/// `despawn` range. A `despawn` range smaller than the `spawn` range is treated as equal to it.
+#[derive(Clone, Copy, Debug, Reflect)]
+pub enum LdtkStreamingRange {
+    /// The distance, in pixels, between the focus and the nearest edge of the level's rectangle,
+    /// as given by [Level::location] and [Level::size]. A focus inside a level is at distance
+    /// zero.
+    Radius {
+        /// Levels within this distance are spawned.
+        spawn: f32,
+        /// Levels beyond this distance are despawned.
+        despawn: f32,
+    },
+    /// The number of steps through [Level::neighbours] from the level nearest to the focus, which
+    /// is at depth zero. Levels which cannot be reached are despawned.
+    Neighbours {
+        /// Levels within this depth are spawned.
+        spawn: usize,
+        /// Levels beyond this depth are despawned.
+        despawn: usize,
+    },
+}
+
+/// Written when the streaming system spawns a level.
+#[derive(Clone, Debug, Message)]
+pub struct LdtkLevelStreamedIn {
+    /// The [LdtkProjectRoot] entity the level was spawned beneath.
+    pub root: Entity,
+    /// The newly spawned level entity.
+    pub entity: Entity,
+    /// The [Iid] of the level.
+    pub iid: Iid,
+    /// The level asset.
+    pub level: Handle<Level>,
+}
+
+/// Written when the streaming system despawns a level.
+#[derive(Clone, Debug, Message)]
+pub struct LdtkLevelStreamedOut {
+    /// The [LdtkProjectRoot] entity the level was despawned from.
+    pub root: Entity,
+    /// The level entity, which no longer exists.
+    pub entity: Entity,
+    /// The [Iid] of the level.
+    pub iid: Iid,
+    /// The level asset.
+    pub level: Handle<Level>,
+}
+
+enum Streaming {
+    Spawn,
+    Keep,
+    Despawn,
+}
+
+impl LdtkStreamingRange {
+    fn streaming(&self, distance: f32, depth: Option<usize>) -> Streaming {
+        match (*self, depth) {
+            (Self::Radius { spawn, .. }, _) if distance <= spawn => Streaming::Spawn,
+            (Self::Radius { spawn, despawn }, _) if distance <= despawn.max(spawn) => {
+                Streaming::Keep
+            }
+            (Self::Neighbours { spawn, .. }, Some(depth)) if depth <= spawn => Streaming::Spawn,
+            (Self::Neighbours { spawn, despawn }, Some(depth)) if depth <= despawn.max(spawn) => {
+                Streaming::Keep
+            }
+            _ => Streaming::Despawn,
+        }
+    }
+}
+
+// Distance from a point to a level's rectangle, both in LDtk's coordinate space.
+fn distance_to_level(point: Vec2, level: &Level) -> f32 {
+    let min = level.location.as_vec2();
+    let max = (level.location + level.size).as_vec2();
+
+    let outside = (min - point).max(point - max).max(Vec2::ZERO);
+
+    outside.length()
+}
+
+// Breadth first search through the level neighbours, starting from the level nearest the focus.
+fn neighbour_depths(
+    world: &World,
+    distances: &IidMap<f32>,
+    ldtk_assets: &LdtkAssets,
+) -> IidMap<usize> {
+    let mut depths = IidMap::default();
+
+    let Some(nearest) = distances
+        .iter()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(iid, _)| *iid)
+    else {
+        return depths;
+    };
+
+    depths.insert(nearest, 0);
+    let mut queue = VecDeque::from([nearest]);
+
+    while let Some(iid) = queue.pop_front() {
+        let depth = depths[&iid];
+
+        let Some(level) = world
+            .levels
+            .get(&iid)
+            .and_then(|handle| ldtk_assets.levels.get(handle.id()))
+        else {
+            continue;
+        };
+
+        level.neighbours.iter().for_each(|neighbour| {
+            if world.levels.contains_key(&neighbour.level_iid)
+                && !depths.contains_key(&neighbour.level_iid)
+            {
+                depths.insert(neighbour.level_iid, depth + 1);
+                queue.push_back(neighbour.level_iid);
+            }
+        });
+    }
+
+    depths
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn stream_ldtk_levels(
+    mut commands: Commands,
+    roots: Query<(Entity, &LdtkStreaming, &GlobalTransform, &Children), With<LdtkProjectSpawned>>,
+    worlds: Query<(Entity, &LdtkWorld, Option<&Children>)>,
+    levels: Query<(&LdtkLevel, &LdtkIid)>,
+    focuses: Query<&GlobalTransform>,
+    ldtk_assets: LdtkAssets,
+    entity_registry: Res<LdtkEntityRegistry>,
+    mut streamed_in: MessageWriter<LdtkLevelStreamedIn>,
+    mut streamed_out: MessageWriter<LdtkLevelStreamedOut>,
+) {
+    roots
+        .iter()
+        .for_each(|(root, streaming, root_transform, root_children)| {
+            let Ok(focus_transform) = focuses.get(streaming.focus) else {
+                return;
+            };
+
+            // The focus in the root's local space, with the y-axis flipped back into LDtk space.
+            let focus = root_transform
+                .affine()
+                .inverse()
+                .transform_point3(focus_transform.translation());
+            let focus = Vec2::new(focus.x, -focus.y);
+
+            root_children
+                .iter()
+                .filter_map(|child| worlds.get(*child).ok())
+                .for_each(|(world_entity, LdtkWorld(world_handle), world_children)| {
+                    let Some(world) = ldtk_assets.worlds.get(world_handle.id()) else {
+                        return;
+                    };
+
+                    let distances: IidMap<f32> = world
+                        .levels
+                        .iter()
+                        .filter_map(|(iid, handle)| {
+                            let level = ldtk_assets.levels.get(handle.id())?;
+                            Some((*iid, distance_to_level(focus, level)))
+                        })
+                        .collect();
+
+                    let depths = match streaming.range {
+                        LdtkStreamingRange::Radius { .. } => IidMap::default(),
+                        LdtkStreamingRange::Neighbours { .. } => {
+                            neighbour_depths(world, &distances, &ldtk_assets)
+                        }
+                    };
+
+                    let spawned: IidMap<(Entity, &Handle<Level>)> = world_children
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|child| {
+                            let (LdtkLevel(handle), LdtkIid(iid)) = levels.get(*child).ok()?;
+                            Some((*iid, (*child, handle)))
+                        })
+                        .collect();
+
+                    let mut spawner = LdtkSpawner {
+                        commands: &mut commands,
+                        ldtk_assets: &ldtk_assets,
+                        entity_registry: &entity_registry,
+                    };
+
+                    distances.iter().for_each(|(iid, distance)| {
+                        let streaming = streaming
+                            .range
+                            .streaming(*distance, depths.get(iid).copied());
+
+                        match (spawned.get(iid), streaming) {
+                            (None, Streaming::Spawn) => {
+                                let level = world.levels[iid].clone();
+                                if let Some(entity) = spawner.spawn_level(world_entity, &level) {
+                                    streamed_in.write(LdtkLevelStreamedIn {
+                                        root,
+                                        entity,
+                                        iid: *iid,
+                                        level,
+                                    });
+                                }
+                            }
+                            (Some((entity, level)), Streaming::Despawn) => {
+                                spawner.commands.entity(*entity).despawn();
+                                streamed_out.write(LdtkLevelStreamedOut {
+                                    root,
+                                    entity: *entity,
+                                    iid: *iid,
+                                    level: (*level).clone(),
+                                });
+                            }
+                            _ => {}
+                        }
+                    });
+                });
+        });
+}
@@ -1,17 +1,75 @@
 #![allow(clippy::unwrap_used, clippy::panic)]
 
+use std::path::PathBuf;
+
 use bevy_app::{App, TaskPoolPlugin};
 use bevy_asset::io::embedded::GetAssetServer as _;
-use bevy_asset::{AssetPlugin, AssetServer, Assets, Handle, LoadState};
+use bevy_asset::{
+    AssetApp as _, AssetMode, AssetPlugin, AssetServer, Assets, Handle, LoadState,
+    RenderAssetUsages,
+};
+use bevy_camera::primitives::Aabb;
+use bevy_camera::visibility::NoAutoAabb;
+use bevy_color::{Alpha, Color, ColorToPacked, Srgba};
 use bevy_ecs::component::Component;
-use bevy_image::ImagePlugin;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::hierarchy::ChildOf;
+use bevy_ecs::message::Messages;
+use bevy_ecs::name::Name;
+use bevy_ecs::system::SystemState;
+use bevy_image::{Image, ImagePlugin, TextureAtlasLayout};
+use bevy_math::{DVec2, I64Vec2, UVec2, Vec3};
+use bevy_mesh::{Mesh, Mesh2d, VertexAttributeValues};
+use bevy_transform::components::{GlobalTransform, Transform};
+use wgpu_types::{Extent3d, TextureDimension, TextureFormat};
 
-use crate::iid::iid;
-use crate::layer::LayerInstance;
-use crate::level::Level;
+use crate::baked_project::{
+    LevelBaker, ProjectBaker, ProjectJson, bake, bake_level, parse_level_bytes, parse_project_bytes,
+};
+use crate::entity::EntityInstance;
+use crate::entity_definition::EntityDefinition;
+use crate::entity_registry::{LdtkEntityAppExt, LdtkEntityContext};
+use crate::enum_definition::EnumDefinition;
+use crate::error::LdtkError;
+use crate::field_definition::FieldDefaultValue;
+use crate::field_instance::{EntityRef, EntityRefIds, FieldInstance, FieldInstanceType};
+use crate::from_field_instance::{FromFieldInstance, LdtkFields};
+use crate::iid::{Iid, IidSet, iid};
+use crate::iid_index::{LdtkIid, LdtkIidIndex};
+use crate::layer::{LayerInstance, LayerType};
+use crate::layer_definition::LayerDefinition;
+use crate::ldtk::migration::{MINIMUM_VERSION, SCHEMA_VERSION, parse_level, parse_project};
+use crate::ldtk_asset_trait::{
+    LdtkAssetWithChildren, LdtkAssetWithFieldDefinitions, LdtkAssetWithFieldInstances,
+};
+use crate::ldtk_assets::LdtkAssets;
+use crate::ldtk_data::{FieldInstanceTypeData, LdtkData};
+use crate::ldtk_enum::LdtkEnum;
+use crate::ldtk_import_error;
+use crate::level::{Level, LevelBackground};
+use crate::level_loader::LevelLoaderSettings;
 use crate::plugin::BevyLdtkAssetPlugin;
 use crate::project::Project;
+use crate::project_builder::{LayerBuilder, LevelBuilder, ProjectBuilder};
+use crate::project_loader::{
+    DanglingEntityRefs, LevelPredicate, LevelSummary, LoadFilter, ProjectLoaderSettings,
+};
+use crate::project_saver::{ProjectSaverSettings, SavedProject, save_project};
+use crate::rasterize::LdtkRasterizer;
+use crate::result::LdtkResult;
+use crate::spawn::{
+    LdtkEntity, LdtkLayer, LdtkLevel, LdtkProjectRoot, LdtkProjectSpawned, LdtkWorld,
+};
+use crate::streaming::{
+    LdtkLevelStreamedIn, LdtkLevelStreamedOut, LdtkStreaming, LdtkStreamingRange,
+};
+use crate::tile_chunks::{LdtkTileChunk, TileChunkLayout, tiles_layer_chunk, tiles_layer_chunks};
+use crate::tile_instance::TileInstance;
+use crate::tile_mesh::{TileMeshBuilder, TilesLayerMaterial, tiles_layer_mesh};
+use crate::tileset_definition::TilesetDefinition;
+use crate::tileset_rectangle::TilesetRectangle;
 use crate::world::World;
+use crate::world_map::{WorldMapLevelStyle, WorldMapSettings};
 
 macro_rules! wait_on_asset_loaded {
     ($asset_server:expr, $app:expr, $handle:expr) => {
@@ -36,6 +94,10 @@ macro_rules! do_the_iid_check {
 }
 
 fn perpare_and_wait_on_project(project_path: &'static str) -> (Handle<Project>, App, AssetServer) {
+    perpare_and_wait_on_project_with_settings(project_path, ProjectLoaderSettings::default())
+}
+
+fn prepare_app() -> App {
     let mut app = App::new();
 
     app.add_plugins(TaskPoolPlugin::default());
@@ -43,7 +105,20 @@ fn perpare_and_wait_on_project(project_path: &'static str) -> (Handle<Project>,
     app.add_plugins(ImagePlugin::default());
     app.add_plugins(BevyLdtkAssetPlugin);
 
-    let project_handle = app.get_asset_server().load::<Project>(project_path);
+    app
+}
+
+fn perpare_and_wait_on_project_with_settings(
+    project_path: &'static str,
+    settings: ProjectLoaderSettings,
+) -> (Handle<Project>, App, AssetServer) {
+    let mut app = prepare_app();
+
+    let project_handle = app
+        .get_asset_server()
+        .load_with_settings::<Project, ProjectLoaderSettings>(project_path, move |s| {
+            *s = settings.clone();
+        });
 
     #[derive(Component)]
     struct MyComponent {
@@ -57,10 +132,37 @@ fn perpare_and_wait_on_project(project_path: &'static str) -> (Handle<Project>,
     let asset_server = app.get_asset_server().clone();
 
     wait_on_asset_loaded!(asset_server, app, project_handle);
+    wait_on_external_levels(&mut app, &asset_server, &project_handle);
 
     (project_handle, app, asset_server)
 }
 
+// External levels are assets of their own, which may still be loading.
+fn wait_on_external_levels(
+    app: &mut App,
+    asset_server: &AssetServer,
+    project_handle: &Handle<Project>,
+) {
+    let level_handles: Vec<Handle<Level>> = {
+        let project = app
+            .world()
+            .get_resource::<Assets<Project>>()
+            .unwrap()
+            .get(project_handle.id())
+            .unwrap();
+        let world_assets = app.world().get_resource::<Assets<World>>().unwrap();
+        project
+            .worlds
+            .values()
+            .filter_map(|handle| world_assets.get(handle.id()))
+            .flat_map(|world| world.levels.values().cloned())
+            .collect()
+    };
+    level_handles
+        .iter()
+        .for_each(|level_handle| wait_on_asset_loaded!(asset_server, app, level_handle));
+}
+
 #[test]
 fn single_world() {
     let (_project_handle, app, asset_server) =
@@ -78,14 +180,14 @@ fn single_world() {
     let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
     do_the_iid_check!(
         asset_server,
-        "ldtk/single_world.ldtk#world:World/Level_0",
+        "ldtk/single_world/Level_0.ldtkl",
         Level,
         level_assets,
         iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6")
     );
     do_the_iid_check!(
         asset_server,
-        "ldtk/single_world.ldtk#world:World/Level_1",
+        "ldtk/single_world/Level_1.ldtkl",
         Level,
         level_assets,
         iid!("fc64e1e0-ac70-11f0-b744-630b936a2808")
@@ -94,14 +196,14 @@ fn single_world() {
     let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
     do_the_iid_check!(
         asset_server,
-        "ldtk/single_world.ldtk#world:World/Level_0/Terrain",
+        "ldtk/single_world/Level_0.ldtkl#Terrain",
         LayerInstance,
         layer_assets,
         iid!("134da8d0-ac70-11f0-b744-152728b44161")
     );
     do_the_iid_check!(
         asset_server,
-        "ldtk/single_world.ldtk#world:World/Level_1/Terrain",
+        "ldtk/single_world/Level_1.ldtkl#Terrain",
         LayerInstance,
         layer_assets,
         iid!("fc6508f1-ac70-11f0-b744-b7538a3a8112")
@@ -135,3 +237,2644 @@ fn embedded_asset_fail() {
     let (_project_handle, _app, _asset_server) =
         perpare_and_wait_on_project("ldtk/embedded_assets.ldtk");
 }
+
+#[test]
+fn spawn_project_hierarchy() {
+    let (project_handle, mut app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    let root = app.world_mut().spawn(LdtkProjectRoot(project_handle)).id();
+
+    app.update();
+
+    assert!(app.world().get::<LdtkProjectSpawned>(root).is_some());
+
+    let world_entities: Vec<_> = app
+        .world_mut()
+        .query::<(&LdtkWorld, &ChildOf)>()
+        .iter(app.world())
+        .map(|(_, child_of)| child_of.parent())
+        .collect();
+    assert_eq!(world_entities, vec![root]);
+
+    let mut levels = app.world_mut().query::<(&LdtkLevel, &Name, &Transform)>();
+    let level_1_translation = levels
+        .iter(app.world())
+        .find(|(_, name, _)| name.as_str() == "Level_1")
+        .map(|(_, _, transform)| transform.translation)
+        .unwrap();
+    assert_eq!(levels.iter(app.world()).count(), 2);
+    assert_eq!(level_1_translation, Vec3::new(256.0, -1.0, 0.0));
+
+    let layer_count = app
+        .world_mut()
+        .query::<&LdtkLayer>()
+        .iter(app.world())
+        .count();
+    assert_eq!(layer_count, 6);
+
+    let (door_name, door_transform) = app
+        .world_mut()
+        .query::<(&LdtkEntity, &Name, &Transform)>()
+        .single(app.world())
+        .map(|(_, name, transform)| (name.to_string(), transform.translation))
+        .unwrap();
+    assert_eq!(door_name, "Door");
+    assert_eq!(door_transform, Vec3::new(192.0, -128.0, 0.0));
+
+    // Reloading an external level respawns only that level.
+    fn level_entity(app: &mut App, identifier: &str) -> Entity {
+        app.world_mut()
+            .query::<(Entity, &Name, &LdtkLevel)>()
+            .iter(app.world())
+            .find(|(_, name, _)| name.as_str() == identifier)
+            .map(|(entity, _, _)| entity)
+            .unwrap()
+    }
+    let level_0 = level_entity(&mut app, "Level_0");
+    let level_1 = level_entity(&mut app, "Level_1");
+    asset_server.reload("ldtk/single_world/Level_0.ldtkl");
+    for _ in 0..1000 {
+        if level_entity(&mut app, "Level_0") != level_0 {
+            break;
+        }
+        app.update();
+    }
+    assert_ne!(level_entity(&mut app, "Level_0"), level_0);
+    assert_eq!(level_entity(&mut app, "Level_1"), level_1);
+    assert!(app.world().get::<LdtkProjectSpawned>(root).is_some());
+    let layer_count = app
+        .world_mut()
+        .query::<&LdtkLayer>()
+        .iter(app.world())
+        .count();
+    assert_eq!(layer_count, 6);
+}
+
+#[test]
+fn entity_registry_constructors() {
+    #[derive(Component)]
+    struct Door {
+        integer: i64,
+        layer: String,
+    }
+
+    #[derive(Component)]
+    struct NeverInserted;
+
+    let (project_handle, mut app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    app.register_ldtk_entity(
+        "Door",
+        |entity_instance: &EntityInstance, context: &LdtkEntityContext| Door {
+            integer: *entity_instance.field_instances["Integer"]
+                .get_int()
+                .unwrap(),
+            layer: context.layer.identifier.clone(),
+        },
+    )
+    .register_ldtk_entity_tag("Enemy", |_: &EntityInstance, _: &LdtkEntityContext| {
+        NeverInserted
+    });
+
+    app.world_mut().spawn(LdtkProjectRoot(project_handle));
+
+    app.update();
+
+    let door = app
+        .world_mut()
+        .query::<&Door>()
+        .single(app.world())
+        .unwrap();
+    assert_eq!(door.integer, 99);
+    assert_eq!(door.layer, "Things");
+
+    let never_inserted = app
+        .world_mut()
+        .query::<&NeverInserted>()
+        .iter(app.world())
+        .count();
+    assert_eq!(never_inserted, 0);
+}
+
+// The derived code must not rely on the prelude's `Ok`, which the user's crate may shadow.
+mod derive_ldtk_fields_shadowed_ok {
+    #![allow(dead_code, non_snake_case)]
+
+    use crate::from_field_instance::LdtkFields;
+
+    fn Ok() {}
+
+    #[derive(LdtkFields)]
+    struct Fields {
+        integer: i64,
+    }
+}
+
+#[test]
+fn derive_ldtk_fields() {
+    #[derive(Debug, LdtkFields)]
+    struct DoorFields {
+        #[ldtk(rename = "Integer")]
+        integer: i64,
+        #[ldtk(rename = "Unlisted")]
+        unlisted: Option<String>,
+    }
+
+    #[derive(Debug, LdtkFields)]
+    struct MissingField {
+        #[allow(dead_code)]
+        missing: i64,
+    }
+
+    #[derive(Debug, LdtkFields)]
+    struct MistypedField {
+        #[allow(dead_code)]
+        #[ldtk(rename = "Integer")]
+        integer: String,
+    }
+
+    let (_project_handle, app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    let entity_assets = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap();
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/single_world/Level_0.ldtkl#Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = entity_assets.get(door_handle.id()).unwrap();
+
+    let door_fields = DoorFields::try_from(door).unwrap();
+    assert_eq!(door_fields.integer, 99);
+    assert_eq!(door_fields.unlisted, None);
+
+    assert!(matches!(
+        MissingField::try_from(door),
+        Err(LdtkError::FieldInstanceNotFound(identifier)) if identifier == "missing"
+    ));
+
+    let Err(LdtkError::FieldInstanceExtractionError { identifier, source }) =
+        MistypedField::try_from(door)
+    else {
+        panic!("expected a field instance extraction error!");
+    };
+    assert_eq!(identifier, "Integer");
+    assert!(matches!(
+        *source,
+        LdtkError::FieldInstanceTypeError {
+            expected: "String",
+            given: "Int"
+        }
+    ));
+}
+
+// The checked in output of the code generator for `field_types.ldtk`, compiled as part of the
+// tests. After changing the generator, write its new output over this file. The project's enum
+// `self` has a value `self`, which clippy would rather not see.
+#[allow(dead_code, clippy::enum_variant_names)]
+mod codegen_field_types {
+    include!("../assets/codegen/field_types.rs");
+}
+
+#[test]
+fn codegen_field_types() {
+    use codegen_field_types::enums::{Empty, Self_, TerrainType};
+
+    let source = crate::codegen::generate_from_path("assets/ldtk/field_types.ldtk").unwrap();
+    assert_eq!(source, include_str!("../assets/codegen/field_types.rs"));
+
+    assert_eq!(Self_::IDENTIFIER, "self");
+    assert_eq!(Self_::Crate.as_str(), "crate");
+    assert_eq!("self".parse::<Self_>().unwrap(), Self_::Self_);
+    assert_eq!(Empty::IDENTIFIER, "Empty");
+    assert!(matches!(
+        "Anything".parse::<Empty>(),
+        Err(LdtkError::EnumValueNotFound {
+            identifier: "Empty",
+            ..
+        })
+    ));
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let generated = codegen_field_types::entities::Door::try_from(door).unwrap();
+    assert_eq!(generated.integer, 99);
+    assert_eq!(generated.r#enum, TerrainType::Grass);
+    assert_eq!(generated.self_, 1);
+    assert_eq!(generated.nullable_array_int, vec![Some(4), None, Some(6)]);
+    assert_eq!(generated.null_enum, None);
+    // Nullable fields are generated as `Option<T>`, so an explicit null stays `None`.
+    assert_eq!(generated.default_int, None);
+    assert_eq!(generated.default_enum, None);
+}
+
+#[test]
+fn codegen_single_world() {
+    let source = crate::codegen::generate_from_path("assets/ldtk/single_world.ldtk").unwrap();
+
+    assert!(source.contains("pub const WORLD: &str = \"World\";"));
+    assert!(source.contains("pub const LEVEL_0: &str = \"Level_0\";"));
+    assert!(source.contains("pub const LEVEL_1: &str = \"Level_1\";"));
+    assert!(source.contains("pub const THINGS: &str = \"Things\";"));
+    assert!(source.contains("pub enum TerrainType {"));
+    assert!(source.contains("#[ldtk(rename = \"Water_0\")]\n        Water0,"));
+    assert!(source.contains("pub const IDENTIFIER: &'static str = \"Terrain_Type\";"));
+    assert!(source.contains("Self::Water0 => \"Water_0\","));
+    assert!(source.contains("impl ::core::str::FromStr for TerrainType {"));
+    assert!(source.contains("pub struct Door {"));
+    assert!(source.contains("#[ldtk(rename = \"Integer\")]\n        pub integer: i64,"));
+
+    let multi_world = crate::codegen::generate_from_path("assets/ldtk/multi_world.ldtk").unwrap();
+
+    assert!(multi_world.contains("pub const OVERWORLD: &str = \"Overworld\";"));
+    assert!(multi_world.contains("pub const UNDERWORLD: &str = \"Underworld\";"));
+}
+
+#[test]
+fn codegen_name_collisions() {
+    let bytes = std::fs::read("assets/ldtk/field_types.ldtk").unwrap();
+    let project_json = crate::ldtk::migration::parse_project(&bytes).unwrap();
+
+    let assert_collision = |project_json: &crate::ldtk::LdtkProject, expected: [&str; 3]| {
+        let Err(LdtkError::CodegenNameCollision {
+            name,
+            first,
+            second,
+        }) = crate::codegen::generate(project_json)
+        else {
+            panic!("expected a name collision for {expected:?}");
+        };
+        assert_eq!([name.as_str(), &first, &second], expected);
+    };
+
+    let mut enums = project_json.clone();
+    let mut terrain_type = enums.defs.enums[0].clone();
+    assert_eq!(terrain_type.identifier, "Terrain_Type");
+    terrain_type.identifier = "TerrainType".to_string();
+    enums.defs.enums.push(terrain_type);
+    assert_collision(&enums, ["TerrainType", "Terrain_Type", "TerrainType"]);
+
+    let mut fields = project_json.clone();
+    let door = fields
+        .defs
+        .entities
+        .iter_mut()
+        .find(|entity_definition| entity_definition.identifier == "Door")
+        .unwrap();
+    let mut integer = door
+        .field_defs
+        .iter()
+        .find(|field_definition| field_definition.identifier == "Integer")
+        .unwrap()
+        .clone();
+    integer.identifier = "integer".to_string();
+    door.field_defs.push(integer);
+    assert_collision(&fields, ["integer", "Integer", "integer"]);
+
+    let mut layers = project_json;
+    let mut things = layers.defs.layers[0].clone();
+    assert_eq!(things.identifier, "Things");
+    things.identifier = "things".to_string();
+    layers.defs.layers.push(things);
+    assert_collision(&layers, ["THINGS", "Things", "things"]);
+}
+
+#[test]
+fn field_definitions() {
+    let (project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    let project = app
+        .world()
+        .get_resource::<Assets<Project>>()
+        .unwrap()
+        .get(project_handle.id())
+        .unwrap();
+    assert!(project.level_field_definitions.is_empty());
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/single_world/Level_0.ldtkl#Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let door_definition = app
+        .world()
+        .get_resource::<Assets<EntityDefinition>>()
+        .unwrap()
+        .get(door.entity_definition.id())
+        .unwrap();
+
+    let field_instance = door.get_field_instance("Integer").unwrap();
+    let field_definition = door_definition
+        .get_field_definition(field_instance.def_uid)
+        .unwrap();
+
+    assert_eq!(field_definition.identifier, "Integer");
+    assert_eq!(field_definition.uid, 169);
+    assert_eq!(field_definition.field_type, "Int");
+    assert!(!field_definition.is_array);
+    assert!(!field_definition.can_be_null);
+    assert!(field_definition.default_override.is_none());
+    assert!(
+        door_definition
+            .get_field_definition_by_identifier("Integer")
+            .is_some_and(|by_identifier| by_identifier.uid == field_definition.uid)
+    );
+}
+
+#[test]
+fn field_instance_types() {
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let field = |identifier: &str| door.get_field_instance(identifier).unwrap();
+
+    let entity_ref = EntityRef {
+        entity_iid: iid!("de1e0e30-ac70-11f0-9854-a5862cb5a481"),
+        layer_iid: iid!("b9a6ba70-ac70-11f0-9854-f397063a23ed"),
+        level_iid: iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6"),
+        world_iid: iid!("ea1bf701-ac70-11f0-b03c-2b67fe2293e1"),
+        ids: Some(EntityRefIds {
+            entity: door_handle.id(),
+            layer: asset_server
+                .load("ldtk/field_types.ldtk#world:World/Level_0/Things")
+                .id(),
+            level: asset_server
+                .load("ldtk/field_types.ldtk#world:World/Level_0")
+                .id(),
+            world: asset_server.load("ldtk/field_types.ldtk#world:World").id(),
+        }),
+    };
+    let terrain_path = PathBuf::from("tilesets/terrain.png");
+
+    assert_eq!(field("Integer").get_int(), Some(&99));
+    assert_eq!(field("Float").get_float(), Some(&1.5));
+    assert_eq!(field("Bool").get_bool(), Some(&true));
+    assert_eq!(field("String").get_string().unwrap(), "hello");
+    assert_eq!(
+        field("Multilines").get_multilines().unwrap(),
+        "line one\nline two"
+    );
+    assert_eq!(
+        field("Color").get_color(),
+        Some(&Color::srgb_u8(0xFF, 0x80, 0x00))
+    );
+    assert!(field("Enum").is_enum());
+    assert_eq!(field("FilePath").get_file_path(), Some(&terrain_path));
+    assert_eq!(
+        field("Tile").get_tile().unwrap().corner,
+        I64Vec2::new(16, 0)
+    );
+    assert_eq!(field("EntityRef").get_entity_ref(), Some(&entity_ref));
+    assert_eq!(field("Point").get_point(), Some(&I64Vec2::new(3, 4)));
+
+    assert_eq!(
+        field("ArrayInt").get_array_int(),
+        Some(&vec![Some(1), Some(2), Some(3)])
+    );
+    assert_eq!(
+        field("NullableArrayInt").get_array_int(),
+        Some(&vec![Some(4), None, Some(6)])
+    );
+    assert_eq!(
+        field("ArrayFloat").get_array_float(),
+        Some(&vec![Some(0.5), Some(1.5)])
+    );
+    assert_eq!(
+        field("ArrayBool").get_array_bool(),
+        Some(&vec![Some(true), Some(false)])
+    );
+    assert_eq!(
+        field("ArrayString").get_array_string(),
+        Some(&vec![Some("a".to_string()), Some("b".to_string())])
+    );
+    assert_eq!(
+        field("ArrayMultilines").get_array_multilines(),
+        Some(&vec![
+            Some("one\ntwo".to_string()),
+            Some("three".to_string())
+        ])
+    );
+    assert_eq!(
+        field("ArrayColor").get_array_color(),
+        Some(&vec![
+            Some(Color::srgb_u8(0xFF, 0x00, 0x00)),
+            Some(Color::srgb_u8(0x00, 0xFF, 0x00))
+        ])
+    );
+    assert_eq!(field("ArrayEnum").get_array_enum().unwrap().len(), 2);
+    assert_eq!(
+        field("ArrayFilePath").get_array_file_path(),
+        Some(&vec![Some(terrain_path)])
+    );
+    assert_eq!(field("ArrayTile").get_array_tile().unwrap().len(), 1);
+    assert_eq!(
+        field("ArrayEntityRef").get_array_entity_ref(),
+        Some(&vec![Some(entity_ref)])
+    );
+    assert_eq!(
+        field("ArrayPoint").get_array_point(),
+        Some(&vec![Some(I64Vec2::new(1, 2)), Some(I64Vec2::new(5, 6))])
+    );
+}
+
+#[test]
+fn field_default_overrides() {
+    #[derive(Debug, PartialEq, LdtkEnum)]
+    #[ldtk(rename = "Terrain_Type")]
+    enum Terrain {
+        Sand,
+        Dirt,
+    }
+
+    #[derive(Debug, LdtkFields)]
+    struct DefaultFields {
+        #[ldtk(rename = "DefaultInt")]
+        default_int: i32,
+        #[ldtk(rename = "DefaultEnum")]
+        default_enum: Terrain,
+        #[ldtk(rename = "NullInt")]
+        null_int: Option<i64>,
+    }
+
+    #[derive(Debug, LdtkFields)]
+    struct OptionalDefaultFields {
+        #[ldtk(rename = "DefaultInt")]
+        default_int: Option<i64>,
+        #[ldtk(rename = "DefaultEnum")]
+        default_enum: Option<Terrain>,
+    }
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    // The fields were set to null, even though their definitions have defaults.
+    assert!(door.get_field_instance("DefaultInt").unwrap().is_null());
+    assert!(door.get_field_instance("DefaultEnum").unwrap().is_null());
+    assert!(matches!(
+        door.get_field_instance_definition("DefaultInt")
+            .unwrap()
+            .default_override,
+        Some(FieldDefaultValue::Int(7))
+    ));
+
+    let default_fields = DefaultFields::try_from(door).unwrap();
+    assert_eq!(default_fields.default_int, 7);
+    assert_eq!(default_fields.default_enum, Terrain::Dirt);
+    assert_eq!(default_fields.null_int, None);
+
+    // An explicit null stays `None`, rather than taking the default.
+    let optional_default_fields = OptionalDefaultFields::try_from(door).unwrap();
+    assert_eq!(optional_default_fields.default_int, None);
+    assert_eq!(optional_default_fields.default_enum, None);
+    assert_eq!(door.get_field::<Option<i64>>("DefaultInt").unwrap(), None);
+
+    assert_eq!(door.get_field::<i64>("DefaultInt").unwrap(), 7);
+    assert_eq!(door.get_field::<Option<f32>>("NullInt").unwrap(), None);
+    assert!(matches!(
+        door.get_field::<String>("DefaultInt"),
+        Err(LdtkError::FieldInstanceExtractionError { source, .. })
+            if matches!(*source, LdtkError::FieldInstanceTypeError { expected: "String", given: "Null" })
+    ));
+}
+
+#[test]
+fn null_field_instances() {
+    #[derive(Debug, LdtkFields)]
+    struct NullFields {
+        #[ldtk(rename = "NullInt")]
+        null_int: Option<i64>,
+        #[ldtk(rename = "Integer")]
+        integer: Option<i64>,
+    }
+
+    #[derive(Debug, LdtkFields)]
+    struct RequiredNullField {
+        #[allow(dead_code)]
+        #[ldtk(rename = "NullInt")]
+        null_int: i64,
+    }
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let null_int = door.get_field_instance("NullInt").unwrap();
+    assert!(null_int.is_null());
+    assert_eq!(null_int.get_null().unwrap(), "Int");
+
+    let null_enum = door.get_field_instance("NullEnum").unwrap();
+    assert_eq!(null_enum.get_null().unwrap(), "LocalEnum.Terrain_Type");
+
+    let nullable_array_int = door.get_field_instance("NullableArrayInt").unwrap();
+    assert!(matches!(
+        nullable_array_int.array_elements().unwrap()[1],
+        FieldInstanceType::Null(ref element_type) if element_type == "Int"
+    ));
+
+    let array_enum = door.get_field_instance("ArrayEnum").unwrap();
+    let mut enum_values = array_enum.get_array_enum().unwrap().clone();
+    enum_values.push(None);
+    let nullable_array_enum = FieldInstance {
+        tileset_rectangle: None,
+        field_instance_type: FieldInstanceType::ArrayEnum(enum_values),
+        def_uid: array_enum.def_uid,
+        field_definition: array_enum.field_definition.clone(),
+    };
+    assert!(matches!(
+        nullable_array_enum.array_elements().unwrap()[2],
+        FieldInstanceType::Null(ref element_type) if element_type == "LocalEnum.Terrain_Type"
+    ));
+
+    assert!(door.get_field_instance("Undefined").is_none());
+
+    let null_fields = NullFields::try_from(door).unwrap();
+    assert_eq!(null_fields.null_int, None);
+    assert_eq!(null_fields.integer, Some(99));
+
+    assert!(matches!(
+        RequiredNullField::try_from(door),
+        Err(LdtkError::FieldInstanceExtractionError { identifier, source })
+            if identifier == "NullInt"
+                && matches!(*source, LdtkError::FieldInstanceTypeError { given: "Null", .. })
+    ));
+}
+
+#[test]
+fn get_field() {
+    #[derive(Debug, PartialEq)]
+    struct DoorConfig {
+        speed: f32,
+        locked: bool,
+    }
+
+    impl FromFieldInstance for DoorConfig {
+        fn from_field_instance(field_instance: &FieldInstance) -> LdtkResult<Self> {
+            let payload = String::from_field_instance(field_instance)?;
+            let value: serde_json::Value = serde_json::from_str(&payload)?;
+
+            let speed = value["speed"]
+                .as_f64()
+                .ok_or_else(|| ldtk_import_error!("speed is not a number!"))?
+                as f32;
+            let locked = value["locked"]
+                .as_bool()
+                .ok_or_else(|| ldtk_import_error!("locked is not a bool!"))?;
+
+            Ok(Self { speed, locked })
+        }
+    }
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    assert_eq!(door.get_field::<i64>("Integer").unwrap(), 99);
+    assert_eq!(door.get_field::<u8>("Integer").unwrap(), 99);
+    assert_eq!(door.get_field::<f32>("Float").unwrap(), 1.5);
+    assert_eq!(
+        door.get_field::<Vec<i32>>("ArrayInt").unwrap(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        door.get_field::<Vec<String>>("ArrayMultilines").unwrap(),
+        vec!["one\ntwo", "three"]
+    );
+    assert_eq!(
+        door.get_field::<Vec<EntityRef>>("ArrayEntityRef")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        door.get_field::<Vec<TilesetRectangle>>("ArrayTile")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        door.get_field::<Vec<Option<i64>>>("NullableArrayInt")
+            .unwrap(),
+        vec![Some(4), None, Some(6)]
+    );
+    assert!(matches!(
+        door.get_field::<Vec<i64>>("NullableArrayInt"),
+        Err(LdtkError::FieldInstanceExtractionError { source, .. })
+            if matches!(*source, LdtkError::FieldInstanceTypeError { expected: "Int", given: "Null" })
+    ));
+    assert_eq!(door.get_field::<Option<i64>>("NullInt").unwrap(), None);
+    assert_eq!(door.get_field::<Option<i64>>("Undefined").unwrap(), None);
+
+    assert_eq!(
+        door.get_field::<DoorConfig>("Config").unwrap(),
+        DoorConfig {
+            speed: 2.5,
+            locked: true
+        }
+    );
+    assert_eq!(
+        door.get_field::<Vec<DoorConfig>>("Configs").unwrap(),
+        vec![
+            DoorConfig {
+                speed: 1.0,
+                locked: false
+            },
+            DoorConfig {
+                speed: 3.0,
+                locked: true
+            }
+        ]
+    );
+
+    assert!(matches!(
+        door.get_field::<Vec<i64>>("Integer"),
+        Err(LdtkError::FieldInstanceExtractionError { source, .. })
+            if matches!(*source, LdtkError::FieldInstanceTypeError { expected: "Array", given: "Int" })
+    ));
+    assert!(matches!(
+        door.get_field::<i64>("Undefined"),
+        Err(LdtkError::FieldInstanceNotFound(identifier)) if identifier == "Undefined"
+    ));
+}
+
+#[test]
+fn ldtk_enum() {
+    #[derive(Debug, PartialEq, LdtkEnum)]
+    #[ldtk(rename = "Terrain_Type")]
+    enum Terrain {
+        Sand,
+        Grass,
+        #[ldtk(rename = "Water_0")]
+        Water,
+    }
+
+    #[derive(Debug, LdtkEnum)]
+    #[ldtk(rename = "Terrain_Type")]
+    enum BadTerrain {
+        Sand,
+        Lava,
+    }
+
+    #[derive(Debug, LdtkEnum)]
+    enum Unrelated {
+        Grass,
+    }
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let enum_value = door.get_field_instance("Enum").unwrap().get_enum().unwrap();
+    assert_eq!(enum_value.value(), "Grass");
+    assert_eq!(enum_value.enum_identifier(), "Terrain_Type");
+    assert_eq!(Terrain::try_from(enum_value).unwrap(), Terrain::Grass);
+    assert_eq!(Terrain::Water.ldtk_value(), "Water_0");
+
+    assert_eq!(door.get_field::<Terrain>("Enum").unwrap(), Terrain::Grass);
+    assert_eq!(
+        door.get_field::<Vec<Terrain>>("ArrayEnum").unwrap(),
+        vec![Terrain::Sand, Terrain::Water]
+    );
+    assert_eq!(door.get_field::<Option<Terrain>>("NullEnum").unwrap(), None);
+
+    assert!(matches!(
+        Unrelated::try_from(enum_value),
+        Err(LdtkError::EnumIdentifierMismatch { expected: "Unrelated", given }) if given == "Terrain_Type"
+    ));
+    assert!(matches!(
+        BadTerrain::try_from(enum_value),
+        Err(LdtkError::EnumValueNotFound { identifier: "Terrain_Type", value }) if value == "Grass"
+    ));
+
+    let enum_definition = app
+        .world()
+        .get_resource::<Assets<EnumDefinition>>()
+        .unwrap()
+        .get(enum_value.enum_definition().id())
+        .unwrap();
+
+    assert!(Terrain::validate(enum_definition).is_ok());
+    assert!(matches!(
+        BadTerrain::validate(enum_definition),
+        Err(LdtkError::EnumVariantsMissing { missing, .. }) if missing == vec!["Lava"]
+    ));
+}
+
+#[test]
+fn entity_ref_dangling() {
+    let mut app = prepare_app();
+    let asset_server = app.get_asset_server().clone();
+    let project_handle = asset_server.load::<Project>("ldtk/dangling_entity_ref.ldtk");
+
+    let error = loop {
+        match asset_server.load_state(project_handle.id()) {
+            LoadState::Loading => app.update(),
+            LoadState::Failed(asset_load_error) => break asset_load_error.to_string(),
+            state => panic!("unexpected load state: {state:?}"),
+        }
+    };
+    assert!(
+        error.contains("EntityRef to an entity which does not exist!"),
+        "{error}"
+    );
+
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/dangling_entity_ref.ldtk",
+        ProjectLoaderSettings {
+            dangling_entity_refs: DanglingEntityRefs::Warn,
+            ..Default::default()
+        },
+    );
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/dangling_entity_ref.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let door = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap();
+
+    let entity_ref = door.get_field::<EntityRef>("EntityRef").unwrap();
+    assert_eq!(
+        entity_ref.entity_iid,
+        iid!("0badc0de-ac70-11f0-9854-a5862cb5a481")
+    );
+    assert!(entity_ref.ids.is_none());
+}
+
+#[test]
+fn iid_index() {
+    let (project_handle, mut app, asset_server) =
+        perpare_and_wait_on_project("ldtk/field_types.ldtk");
+
+    let root = app.world_mut().spawn(LdtkProjectRoot(project_handle)).id();
+
+    app.update();
+
+    let door = app
+        .world_mut()
+        .query::<(bevy_ecs::entity::Entity, &LdtkEntity, &LdtkIid)>()
+        .single(app.world())
+        .map(|(entity, _, ldtk_iid)| {
+            assert_eq!(
+                *ldtk_iid,
+                LdtkIid(iid!("de1e0e30-ac70-11f0-9854-a5862cb5a481"))
+            );
+            entity
+        })
+        .unwrap();
+
+    let door_handle = asset_server.load::<EntityInstance>(
+        "ldtk/field_types.ldtk#world:World/Level_0/Things/Door@de1e0e30-ac70-11f0-9854-a5862cb5a481",
+    );
+    let entity_ref: EntityRef = app
+        .world()
+        .get_resource::<Assets<EntityInstance>>()
+        .unwrap()
+        .get(door_handle.id())
+        .unwrap()
+        .get_field("EntityRef")
+        .unwrap();
+
+    let iid_index = app.world().get_resource::<LdtkIidIndex>().unwrap();
+    assert_eq!(iid_index.get_entity_ref(&entity_ref), Some(door));
+    assert!(iid_index.contains(iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6")));
+    assert!(iid_index.contains(iid!("b9a6ba70-ac70-11f0-9854-f397063a23ed")));
+    // world, level, three layers and the door
+    assert_eq!(iid_index.len(), 6);
+
+    app.world_mut().entity_mut(root).despawn();
+
+    assert!(
+        app.world()
+            .get_resource::<LdtkIidIndex>()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn loader_settings_filters() {
+    let (project_handle, app, _asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/multi_world.ldtk",
+        ProjectLoaderSettings {
+            worlds: LoadFilter::Identifiers(vec!["Overworld".to_string()]),
+            level_predicate: Some(LevelPredicate::new(|level: &LevelSummary| {
+                assert_eq!(level.world_identifier, "Overworld");
+                level.identifier != "Level2"
+            })),
+            ..Default::default()
+        },
+    );
+
+    let project = app
+        .world()
+        .get_resource::<Assets<Project>>()
+        .unwrap()
+        .get(project_handle.id())
+        .unwrap();
+    assert_eq!(project.worlds.len(), 1);
+
+    let world_assets = app.world().get_resource::<Assets<World>>().unwrap();
+    let overworld = world_assets
+        .get(project.worlds.values().next().unwrap().id())
+        .unwrap();
+    assert_eq!(overworld.identifier, "Overworld");
+
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    let level_identifiers: Vec<_> = overworld
+        .levels
+        .values()
+        .map(|handle| level_assets.get(handle.id()).unwrap().identifier.as_str())
+        .collect();
+    assert_eq!(level_identifiers, vec!["Level1"]);
+    assert_eq!(level_assets.len(), 1);
+}
+
+#[test]
+fn loader_settings_skip_images_and_entities() {
+    let (_project_handle, app, _asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/single_world.ldtk",
+        ProjectLoaderSettings {
+            levels: LoadFilter::Identifiers(vec!["Level_0".to_string()]),
+            load_images: false,
+            load_entities: false,
+            ..Default::default()
+        },
+    );
+
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    assert_eq!(level_assets.len(), 1);
+    assert_eq!(level_assets.iter().next().unwrap().1.identifier, "Level_0");
+
+    assert!(
+        app.world()
+            .get_resource::<Assets<TilesetDefinition>>()
+            .unwrap()
+            .iter()
+            .all(|(_, tileset_definition)| tileset_definition.tileset_image.is_none())
+    );
+
+    let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
+    assert!(
+        layer_assets
+            .iter()
+            .all(|(_, layer)| match &layer.layer_type {
+                LayerType::Entities(entities_layer) => entities_layer.entities.is_empty(),
+                LayerType::Tiles(tiles_layer)
+                | LayerType::IntGrid(tiles_layer)
+                | LayerType::AutoLayer(tiles_layer) => tiles_layer.tileset_image.is_none(),
+            })
+    );
+
+    assert!(
+        app.world()
+            .get_resource::<Assets<EntityInstance>>()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn standalone_external_level() {
+    let mut app = prepare_app();
+    let asset_server = app.get_asset_server().clone();
+
+    let level_handle: Handle<Level> = asset_server.load("ldtk/single_world/Level_1.ldtkl");
+    wait_on_asset_loaded!(asset_server, app, level_handle);
+
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    let level = level_assets.get(level_handle.id()).unwrap();
+    assert_eq!(level.identifier, "Level_1");
+    assert_eq!(level.iid, iid!("fc64e1e0-ac70-11f0-b744-630b936a2808"));
+
+    let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
+    do_the_iid_check!(
+        asset_server,
+        "ldtk/single_world/Level_1.ldtkl#Terrain",
+        LayerInstance,
+        layer_assets,
+        iid!("fc6508f1-ac70-11f0-b744-b7538a3a8112")
+    );
+    assert!(
+        level
+            .layers
+            .values()
+            .all(|layer| layer_assets.contains(layer.id()))
+    );
+
+    // An explicit project path gives the same level.
+    let explicit_handle: Handle<Level> = asset_server.load_with_settings(
+        "ldtk/single_world/Level_0.ldtkl",
+        |settings: &mut LevelLoaderSettings| {
+            settings.project_path = Some("ldtk/single_world.ldtk".into());
+        },
+    );
+    wait_on_asset_loaded!(asset_server, app, explicit_handle);
+
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    let level = level_assets.get(explicit_handle.id()).unwrap();
+    assert_eq!(level.iid, iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6"));
+}
+
+#[test]
+fn external_level_paths() {
+    let (_project_handle, app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    let world_assets = app.world().get_resource::<Assets<World>>().unwrap();
+    let (_, world) = world_assets.iter().next().unwrap();
+
+    let mut level_paths: Vec<String> = world
+        .level_paths
+        .values()
+        .map(ToString::to_string)
+        .collect();
+    level_paths.sort();
+    assert_eq!(
+        level_paths,
+        [
+            "ldtk/single_world/Level_0.ldtkl",
+            "ldtk/single_world/Level_1.ldtkl"
+        ]
+    );
+
+    let (_, multi_app, _) = perpare_and_wait_on_project("ldtk/multi_world.ldtk");
+    let world_assets = multi_app.world().get_resource::<Assets<World>>().unwrap();
+    assert!(
+        world_assets
+            .iter()
+            .all(|(_, world)| world.level_paths.is_empty())
+    );
+}
+
+#[test]
+fn level_streaming() {
+    fn spawned_levels(app: &mut App) -> Vec<String> {
+        let mut names: Vec<String> = app
+            .world_mut()
+            .query::<(&LdtkLevel, &Name)>()
+            .iter(app.world())
+            .map(|(_, name)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // Moves the focus, and returns the iids of the levels streamed in and out.
+    fn move_focus(app: &mut App, focus: Entity, x: f32, y: f32) -> (Vec<Iid>, Vec<Iid>) {
+        // Bevy's y-axis is inverted relative to LDtk's.
+        *app.world_mut().get_mut::<GlobalTransform>(focus).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(x, -y, 0.0));
+        app.update();
+
+        let streamed_in = app
+            .world_mut()
+            .resource_mut::<Messages<LdtkLevelStreamedIn>>()
+            .drain()
+            .map(|message| message.iid)
+            .collect();
+        let streamed_out = app
+            .world_mut()
+            .resource_mut::<Messages<LdtkLevelStreamedOut>>()
+            .drain()
+            .map(|message| message.iid)
+            .collect();
+        (streamed_in, streamed_out)
+    }
+
+    let level_0 = iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6");
+    let level_1 = iid!("fc64e1e0-ac70-11f0-b744-630b936a2808");
+
+    let (project_handle, mut app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    // Level_0 covers x in 0..256, and Level_1 covers x in 256..512.
+    let focus = app.world_mut().spawn(GlobalTransform::default()).id();
+    let root = app
+        .world_mut()
+        .spawn((
+            LdtkProjectRoot(project_handle.clone()),
+            LdtkStreaming {
+                focus,
+                range: LdtkStreamingRange::Radius {
+                    spawn: 64.0,
+                    despawn: 128.0,
+                },
+            },
+        ))
+        .id();
+
+    assert_eq!(
+        move_focus(&mut app, focus, 128.0, 128.0),
+        (vec![level_0], vec![])
+    );
+    assert_eq!(
+        app.world_mut()
+            .query::<&LdtkWorld>()
+            .iter(app.world())
+            .count(),
+        1
+    );
+    assert_eq!(spawned_levels(&mut app), ["Level_0"]);
+
+    assert_eq!(
+        move_focus(&mut app, focus, 200.0, 128.0),
+        (vec![level_1], vec![])
+    );
+    assert_eq!(spawned_levels(&mut app), ["Level_0", "Level_1"]);
+
+    // Between the spawn and despawn radius of Level_0, so nothing changes.
+    assert_eq!(move_focus(&mut app, focus, 360.0, 128.0), (vec![], vec![]));
+    assert_eq!(spawned_levels(&mut app), ["Level_0", "Level_1"]);
+
+    assert_eq!(
+        move_focus(&mut app, focus, 400.0, 128.0),
+        (vec![], vec![level_0])
+    );
+    assert_eq!(spawned_levels(&mut app), ["Level_1"]);
+
+    // Back within the despawn radius, but not the spawn radius.
+    assert_eq!(move_focus(&mut app, focus, 330.0, 128.0), (vec![], vec![]));
+    assert_eq!(spawned_levels(&mut app), ["Level_1"]);
+
+    let iid_index = app.world().get_resource::<LdtkIidIndex>().unwrap();
+    assert!(!iid_index.contains(level_0));
+    assert!(iid_index.contains(level_1));
+
+    // Neighbour depth, counted from the level nearest the focus.
+    app.world_mut().entity_mut(root).despawn();
+    app.world_mut().spawn((
+        LdtkProjectRoot(project_handle),
+        LdtkStreaming {
+            focus,
+            range: LdtkStreamingRange::Neighbours {
+                spawn: 0,
+                despawn: 1,
+            },
+        },
+    ));
+
+    assert_eq!(
+        move_focus(&mut app, focus, 128.0, 128.0),
+        (vec![level_0], vec![])
+    );
+    assert_eq!(spawned_levels(&mut app), ["Level_0"]);
+
+    // Level_0 is now a neighbour of the nearest level, so it is kept.
+    assert_eq!(
+        move_focus(&mut app, focus, 300.0, 128.0),
+        (vec![level_1], vec![])
+    );
+    assert_eq!(spawned_levels(&mut app), ["Level_0", "Level_1"]);
+}
+
+#[test]
+fn json_version_migration() {
+    use crate::ldtk::migration::{check_json_version, parse_project};
+
+    assert!(check_json_version("1.5.3").is_ok());
+    assert!(check_json_version("1.5.0").is_ok());
+    assert!(check_json_version("1.4.3").is_ok());
+    // Newer patch versions are accepted with a warning.
+    assert!(check_json_version("1.5.9").is_ok());
+    assert!(check_json_version("1.6.0").is_err());
+    assert!(check_json_version("2.0.0").is_err());
+    assert!(check_json_version("1.3.4").is_err());
+    assert!(check_json_version("1.5").is_err());
+    assert!(check_json_version("1.5.3.1").is_err());
+    assert!(check_json_version("one.five.three").is_err());
+
+    // `ldtk_1_4_3.ldtk` is `field_types.ldtk` in the shape written by LDtk 1.4.3, with one auto
+    // layer rule picking from two tiles, and another stamping four tiles.
+    let current = parse_project(&std::fs::read("assets/ldtk/field_types.ldtk").unwrap()).unwrap();
+    let legacy = std::fs::read("assets/ldtk/ldtk_1_4_3.ldtk").unwrap();
+    assert!(serde_json::from_slice::<crate::ldtk::LdtkProject>(&legacy).is_err());
+
+    let migrated = parse_project(&legacy).unwrap();
+    assert_eq!(migrated.json_version, "1.4.3");
+
+    let rules = |project: &crate::ldtk::LdtkProject| -> Vec<crate::ldtk::AutoLayerRuleDefinition> {
+        project
+            .defs
+            .layers
+            .iter()
+            .flat_map(|layer| layer.auto_rule_groups.iter())
+            .flat_map(|group| group.rules.iter())
+            .cloned()
+            .collect()
+    };
+    let migrated_rules = rules(&migrated);
+    let current_rules = rules(&current);
+    assert_eq!(migrated_rules.len(), current_rules.len());
+    assert_eq!(migrated_rules[0].tile_rects_ids, vec![vec![301], vec![302]]);
+    assert_eq!(
+        migrated_rules[1].tile_rects_ids,
+        vec![vec![344, 345, 364, 365]]
+    );
+    assert!(migrated_rules.iter().all(|rule| {
+        rule.tile_random_x_min == 0
+            && rule.tile_random_x_max == 0
+            && rule.tile_random_y_min == 0
+            && rule.tile_random_y_max == 0
+    }));
+    // Every other rule is unchanged, once the deprecated `tileIds` are set aside.
+    migrated_rules
+        .iter()
+        .zip(current_rules.iter())
+        .skip(2)
+        .for_each(|(migrated, current)| {
+            assert_eq!(
+                crate::ldtk::AutoLayerRuleDefinition {
+                    tile_ids: None,
+                    ..migrated.clone()
+                },
+                *current
+            );
+        });
+
+    assert_eq!(migrated.toc.len(), current.toc.len());
+    migrated
+        .toc
+        .iter()
+        .zip(current.toc.iter())
+        .for_each(|(migrated, current)| {
+            let iids = |entry: &crate::ldtk::LdtkTableOfContentEntry| -> Vec<_> {
+                entry
+                    .instances_data
+                    .iter()
+                    .map(|instance_data| instance_data.iids.clone())
+                    .collect()
+            };
+            assert_eq!(iids(migrated), iids(current));
+        });
+    assert_eq!(migrated.levels, current.levels);
+
+    // And it loads through the asset server like any other project.
+    let (project_handle, app, _asset_server) = perpare_and_wait_on_project("ldtk/ldtk_1_4_3.ldtk");
+    let project = app
+        .world()
+        .get_resource::<Assets<Project>>()
+        .unwrap()
+        .get(project_handle.id())
+        .unwrap();
+    assert_eq!(project.ldtk_version, "1.4.3");
+    assert_eq!(project.worlds.len(), 1);
+
+    let mut value: serde_json::Value = serde_json::from_slice(&legacy).unwrap();
+    value["jsonVersion"] = "1.6.0".into();
+    let too_new = serde_json::to_vec(&value).unwrap();
+    assert!(matches!(
+        parse_project(&too_new),
+        Err(LdtkError::LdtkImportError(_))
+    ));
+}
+
+#[test]
+fn embedded_atlas_substitute() {
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/embedded_assets.ldtk",
+        ProjectLoaderSettings {
+            ldtk_icons: Some("tilesets/terrain.png".into()),
+            ..Default::default()
+        },
+    );
+
+    let tileset_definition_assets = app
+        .world()
+        .get_resource::<Assets<TilesetDefinition>>()
+        .unwrap();
+    let internal_icons = tileset_definition_assets
+        .get(
+            asset_server
+                .load::<TilesetDefinition>(
+                    "ldtk/embedded_assets.ldtk#tileset_definition:Internal_Icons",
+                )
+                .id(),
+        )
+        .unwrap();
+    let image_path = internal_icons
+        .tileset_image
+        .as_ref()
+        .and_then(|image| image.path())
+        .unwrap();
+    assert_eq!(image_path.to_string(), "tilesets/terrain.png");
+
+    // The atlas image is only required when images are loaded.
+    let (_project_handle, app, asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/embedded_assets.ldtk",
+        ProjectLoaderSettings {
+            load_images: false,
+            ..Default::default()
+        },
+    );
+
+    let tileset_definition_assets = app
+        .world()
+        .get_resource::<Assets<TilesetDefinition>>()
+        .unwrap();
+    let internal_icons = tileset_definition_assets
+        .get(
+            asset_server
+                .load::<TilesetDefinition>(
+                    "ldtk/embedded_assets.ldtk#tileset_definition:Internal_Icons",
+                )
+                .id(),
+        )
+        .unwrap();
+    assert!(internal_icons.tileset_image.is_none());
+}
+
+#[test]
+fn baked_project() {
+    // The identifier, Iid and layers of every level, and the entities of every layer.
+    type LevelSummary = (String, Iid, [i64; 2], Vec<(String, Iid, Vec<Iid>)>);
+
+    fn summarize(app: &App, project_handle: &Handle<Project>) -> Vec<LevelSummary> {
+        let world = app.world();
+        let projects = world.get_resource::<Assets<Project>>().unwrap();
+        let worlds = world.get_resource::<Assets<World>>().unwrap();
+        let levels = world.get_resource::<Assets<Level>>().unwrap();
+        let layers = world.get_resource::<Assets<LayerInstance>>().unwrap();
+        let entities = world.get_resource::<Assets<EntityInstance>>().unwrap();
+
+        let mut summary: Vec<LevelSummary> = projects
+            .get(project_handle.id())
+            .unwrap()
+            .worlds
+            .values()
+            .flat_map(|handle| worlds.get(handle.id()).unwrap().levels.values())
+            .map(|handle| {
+                let level = levels.get(handle.id()).unwrap();
+                let mut level_layers: Vec<_> = level
+                    .layers
+                    .values()
+                    .map(|handle| {
+                        let layer = layers.get(handle.id()).unwrap();
+                        let mut layer_entities: Vec<_> = layer
+                            .get_children()
+                            .map(|handle| entities.get(handle.id()).unwrap().iid)
+                            .collect();
+                        layer_entities.sort();
+                        (layer.identifier.clone(), layer.iid, layer_entities)
+                    })
+                    .collect();
+                level_layers.sort();
+                (
+                    level.identifier.clone(),
+                    level.iid,
+                    level.location.to_array(),
+                    level_layers,
+                )
+            })
+            .collect();
+        summary.sort();
+        summary
+    }
+
+    let (project_handle, json_app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let expected = summarize(&json_app, &project_handle);
+    assert_eq!(expected.len(), 2);
+
+    let mut app = prepare_app();
+    let asset_server = app.get_asset_server().clone();
+
+    let project_json_handle: Handle<ProjectJson> = asset_server.load("ldtk/single_world.ldtk");
+    wait_on_asset_loaded!(asset_server, app, project_json_handle);
+
+    let project_json = app
+        .world()
+        .get_resource::<Assets<ProjectJson>>()
+        .unwrap()
+        .get(project_json_handle.id())
+        .unwrap();
+    assert!(project_json.project.external_levels);
+
+    let baked = bake(&project_json.project).unwrap();
+    assert_eq!(parse_project_bytes(&baked).unwrap(), project_json.project);
+    let json_len = std::fs::metadata("assets/ldtk/single_world.ldtk")
+        .unwrap()
+        .len();
+    assert!((baked.len() as u64) < json_len);
+
+    // A baked project from a future version of this crate is rejected.
+    let mut future = baked.clone();
+    future[8] = 2;
+    assert!(parse_project_bytes(&future).is_err());
+
+    // Levels are baked on their own, and a level which was not baked is still read as JSON.
+    let level_0_json = std::fs::read("assets/ldtk/single_world/Level_0.ldtkl").unwrap();
+    let level_0 = parse_level_bytes(&level_0_json, SCHEMA_VERSION).unwrap();
+    let baked_level_0 = bake_level(&level_0).unwrap();
+    assert_eq!(
+        parse_level_bytes(&baked_level_0, SCHEMA_VERSION).unwrap(),
+        level_0
+    );
+    assert!(parse_project_bytes(&baked_level_0).is_err());
+
+    let registry = app
+        .world()
+        .get_resource::<bevy_asset::io::embedded::EmbeddedAssetRegistry>()
+        .unwrap();
+    registry.insert_asset(
+        PathBuf::new(),
+        std::path::Path::new("ldtk/single_world.ldtk"),
+        baked,
+    );
+    registry.insert_asset(
+        PathBuf::new(),
+        std::path::Path::new("ldtk/single_world/Level_0.ldtkl"),
+        baked_level_0,
+    );
+    registry.insert_asset(
+        PathBuf::new(),
+        std::path::Path::new("ldtk/single_world/Level_1.ldtkl"),
+        std::fs::read("assets/ldtk/single_world/Level_1.ldtkl").unwrap(),
+    );
+
+    let baked_handle: Handle<Project> = asset_server.load("embedded://ldtk/single_world.ldtk");
+    wait_on_asset_loaded!(asset_server, app, baked_handle);
+    wait_on_external_levels(&mut app, &asset_server, &baked_handle);
+
+    assert_eq!(summarize(&app, &baked_handle), expected);
+
+    // The levels keep the paths they have in an unbaked project.
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    do_the_iid_check!(
+        asset_server,
+        "embedded://ldtk/single_world/Level_0.ldtkl",
+        Level,
+        level_assets,
+        iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6")
+    );
+    do_the_iid_check!(
+        asset_server,
+        "embedded://ldtk/single_world/Level_1.ldtkl",
+        Level,
+        level_assets,
+        iid!("fc64e1e0-ac70-11f0-b744-630b936a2808")
+    );
+
+    // And the same through the asset processor, with both bakers chosen as the defaults.
+    let processed_directory =
+        std::env::temp_dir().join(format!("bevy_ldtk_asset_processed_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&processed_directory);
+
+    let mut app = App::new();
+    app.add_plugins(TaskPoolPlugin::default());
+    app.add_plugins(AssetPlugin {
+        mode: AssetMode::Processed,
+        processed_file_path: processed_directory.to_string_lossy().into_owned(),
+        use_asset_processor_override: Some(true),
+        ..Default::default()
+    });
+    app.add_plugins(ImagePlugin::default());
+    app.add_plugins(BevyLdtkAssetPlugin);
+    app.set_default_asset_processor::<ProjectBaker>("ldtk")
+        .set_default_asset_processor::<LevelBaker>("ldtkl");
+    let asset_server = app.get_asset_server().clone();
+
+    let processed_handle: Handle<Project> = asset_server.load("ldtk/single_world.ldtk");
+    wait_on_asset_loaded!(asset_server, app, processed_handle);
+    wait_on_external_levels(&mut app, &asset_server, &processed_handle);
+
+    assert_eq!(summarize(&app, &processed_handle), expected);
+    assert!(
+        std::fs::read(processed_directory.join("ldtk/single_world.ldtk"))
+            .unwrap()
+            .starts_with(b"LDTKBAKE")
+    );
+    let processed_level_0 =
+        std::fs::read(processed_directory.join("ldtk/single_world/Level_0.ldtkl")).unwrap();
+    assert_eq!(
+        parse_level_bytes(&processed_level_0, MINIMUM_VERSION).unwrap(),
+        level_0
+    );
+
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    do_the_iid_check!(
+        asset_server,
+        "ldtk/single_world/Level_0.ldtkl",
+        Level,
+        level_assets,
+        iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6")
+    );
+
+    let _ = std::fs::remove_dir_all(&processed_directory);
+}
+
+#[test]
+fn project_saver() {
+    fn save(
+        app: &mut App,
+        project_handle: &Handle<Project>,
+        settings: &ProjectSaverSettings,
+    ) -> LdtkResult<SavedProject> {
+        let mut system_state = SystemState::<LdtkAssets>::new(app.world_mut());
+        let ldtk_assets = system_state.get(app.world());
+        save_project(project_handle, &ldtk_assets, settings)
+    }
+
+    fn reload(app: &mut App, path: &'static str, saved: &SavedProject) -> Handle<Project> {
+        let registry = app
+            .world()
+            .get_resource::<bevy_asset::io::embedded::EmbeddedAssetRegistry>()
+            .unwrap();
+        let project_path = std::path::Path::new(path);
+        registry.insert_asset(PathBuf::new(), project_path, saved.project.clone());
+        saved.levels.iter().for_each(|level| {
+            registry.insert_asset(
+                PathBuf::new(),
+                &project_path.parent().unwrap().join(&level.path),
+                level.json.clone(),
+            );
+        });
+
+        let asset_server = app.get_asset_server().clone();
+        let project_handle: Handle<Project> = asset_server.load(format!("embedded://{path}"));
+        wait_on_asset_loaded!(asset_server, app, project_handle);
+        wait_on_external_levels(app, &asset_server, &project_handle);
+
+        project_handle
+    }
+
+    // Saving a freshly loaded project reproduces the files LDtk wrote.
+    let external_levels = ProjectSaverSettings {
+        external_levels: true,
+    };
+    let (project_handle, mut app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let saved = save(&mut app, &project_handle, &external_levels).unwrap();
+
+    assert_eq!(
+        parse_project(&saved.project).unwrap(),
+        parse_project(&std::fs::read("assets/ldtk/single_world.ldtk").unwrap()).unwrap()
+    );
+    assert_eq!(saved.levels.len(), 2);
+    saved.levels.iter().for_each(|level| {
+        let original = std::fs::read(PathBuf::from("assets/ldtk").join(&level.path)).unwrap();
+        assert_eq!(
+            parse_level(&level.json, SCHEMA_VERSION).unwrap(),
+            parse_level(&original, SCHEMA_VERSION).unwrap()
+        );
+    });
+    assert_eq!(
+        saved.levels[0].path,
+        PathBuf::from("single_world/Level_0.ldtkl")
+    );
+
+    // And the saved project loads back into the same assets.
+    let reloaded_handle = reload(&mut app, "ldtk/single_world.ldtk", &saved);
+    let resaved = save(&mut app, &reloaded_handle, &external_levels).unwrap();
+    assert_eq!(resaved.project, saved.project);
+    assert!(
+        resaved
+            .levels
+            .iter()
+            .zip(&saved.levels)
+            .all(|(resaved, saved)| resaved.path == saved.path && resaved.json == saved.json)
+    );
+
+    // Every field type survives, along with the values the LDtk editor reads back.
+    let (project_handle, mut app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/field_types.ldtk");
+    let saved = save(&mut app, &project_handle, &ProjectSaverSettings::default()).unwrap();
+    assert!(saved.levels.is_empty());
+
+    let project_json = parse_project(&saved.project).unwrap();
+    let field_instances = &project_json.levels[0].layer_instances.as_ref().unwrap()[0]
+        .entity_instances[0]
+        .field_instances;
+    let real_editor_values = |identifier: &str| {
+        field_instances
+            .iter()
+            .find(|field_instance| field_instance.identifier == identifier)
+            .unwrap()
+            .real_editor_values
+            .clone()
+    };
+    assert_eq!(
+        real_editor_values("Integer"),
+        vec![Some(serde_json::json!({ "id": "V_Int", "params": [99] }))]
+    );
+    assert_eq!(
+        real_editor_values("Color"),
+        vec![Some(
+            serde_json::json!({ "id": "V_Int", "params": [0xFF8000] })
+        )]
+    );
+    assert_eq!(
+        real_editor_values("ArrayPoint"),
+        vec![
+            Some(serde_json::json!({ "id": "V_String", "params": ["1,2"] })),
+            Some(serde_json::json!({ "id": "V_String", "params": ["5,6"] })),
+        ]
+    );
+    assert_eq!(
+        real_editor_values("NullableArrayInt"),
+        vec![
+            Some(serde_json::json!({ "id": "V_Int", "params": [4] })),
+            None,
+            Some(serde_json::json!({ "id": "V_Int", "params": [6] })),
+        ]
+    );
+
+    let reloaded_handle = reload(&mut app, "ldtk/field_types.ldtk", &saved);
+    let resaved = save(&mut app, &reloaded_handle, &ProjectSaverSettings::default()).unwrap();
+    assert_eq!(resaved.project, saved.project);
+
+    // Entities keep the order LDtk listed them in, whatever their iids.
+    let mut value: serde_json::Value =
+        serde_json::from_slice(&std::fs::read("assets/ldtk/field_types.ldtk").unwrap()).unwrap();
+    let entity_instances = value["levels"][0]["layerInstances"][0]["entityInstances"]
+        .as_array_mut()
+        .unwrap();
+    let door = entity_instances[0].clone();
+    entity_instances.extend(
+        [
+            "ffffffff-ac70-11f0-9854-a5862cb5a481",
+            "00000000-ac70-11f0-9854-a5862cb5a481",
+        ]
+        .map(|iid| {
+            let mut entity_instance = door.clone();
+            entity_instance["iid"] = iid.into();
+            entity_instance
+        }),
+    );
+    let unordered = SavedProject {
+        project: serde_json::to_vec(&value).unwrap(),
+        levels: Vec::new(),
+    };
+    let reloaded_handle = reload(&mut app, "ldtk/entity_order.ldtk", &unordered);
+    let resaved = save(&mut app, &reloaded_handle, &ProjectSaverSettings::default()).unwrap();
+    let entity_iids = parse_project(&resaved.project).unwrap().levels[0]
+        .layer_instances
+        .as_ref()
+        .unwrap()[0]
+        .entity_instances
+        .iter()
+        .map(|entity_instance| entity_instance.iid.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entity_iids,
+        [
+            "de1e0e30-ac70-11f0-9854-a5862cb5a481",
+            "ffffffff-ac70-11f0-9854-a5862cb5a481",
+            "00000000-ac70-11f0-9854-a5862cb5a481",
+        ]
+    );
+
+    // A partially loaded project can't be saved without losing whatever was left out.
+    let (project_handle, mut app, _asset_server) = perpare_and_wait_on_project_with_settings(
+        "ldtk/single_world.ldtk",
+        ProjectLoaderSettings {
+            load_entities: false,
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        save(&mut app, &project_handle, &ProjectSaverSettings::default()),
+        Err(LdtkError::LdtkExportError(_))
+    ));
+}
+
+#[test]
+fn project_builder() {
+    let mut builder = ProjectBuilder::new("ldtk/generated.ldtk");
+    let terrain = builder.add_tileset("Terrain", "tilesets/terrain.png", I64Vec2::new(128, 64), 16);
+    let walls = builder.add_int_grid_layer(
+        "Walls",
+        16,
+        &[
+            (1, "Wall", Color::BLACK),
+            (2, "Water", Color::srgb_u8(0, 0, 255)),
+        ],
+    );
+    let ground = builder.add_tiles_layer("Ground", 16, terrain);
+    let things = builder.add_entities_layer("Things", 16);
+    let player = builder.add_entity("Player", I64Vec2::new(16, 32), Color::WHITE);
+
+    let mut level_0 = LevelBuilder::new("Level_0", I64Vec2::new(64, 48));
+    let mut walls_layer = LayerBuilder::new(walls);
+    walls_layer.set_int_grid(I64Vec2::new(1, 1), 1);
+    walls_layer.set_int_grid(I64Vec2::new(3, 2), 2);
+    let walls_iid = walls_layer.iid();
+    level_0.add_layer(walls_layer);
+    let mut ground_layer = LayerBuilder::new(ground);
+    ground_layer.add_tile(I64Vec2::new(0, 0), 9);
+    ground_layer.add_flipped_tile(I64Vec2::new(2, 1), 3, true, false);
+    level_0.add_layer(ground_layer);
+    let mut things_layer = LayerBuilder::new(things);
+    let player_iid = things_layer.add_entity(player, I64Vec2::new(2, 1));
+    level_0.add_layer(things_layer);
+    let level_0_iid = level_0.iid();
+    let level_0_uid = builder.add_level(level_0);
+
+    let mut level_1 = LevelBuilder::new("Level_1", I64Vec2::new(64, 48));
+    level_1.location = I64Vec2::new(64, 0);
+    level_1.bg_color = Some(Color::srgb_u8(0x10, 0x20, 0x30));
+    builder.add_level(level_1);
+
+    // Uids are shared between definitions and levels.
+    let mut uids = vec![terrain, walls, ground, things, player, level_0_uid];
+    uids.sort();
+    uids.dedup();
+    assert_eq!(uids.len(), 6);
+
+    let mut app = prepare_app();
+    let asset_server = app.get_asset_server().clone();
+    let project_handle = builder
+        .load(
+            app.world()
+                .get_resource::<bevy_asset::io::embedded::EmbeddedAssetRegistry>()
+                .unwrap(),
+            &asset_server,
+        )
+        .unwrap();
+    wait_on_asset_loaded!(asset_server, app, project_handle);
+
+    let world = app.world();
+    let level_assets = world.get_resource::<Assets<Level>>().unwrap();
+    let layer_assets = world.get_resource::<Assets<LayerInstance>>().unwrap();
+    let entity_assets = world.get_resource::<Assets<EntityInstance>>().unwrap();
+    let entity_definitions = world.get_resource::<Assets<EntityDefinition>>().unwrap();
+
+    let project = world
+        .get_resource::<Assets<Project>>()
+        .unwrap()
+        .get(project_handle.id())
+        .unwrap();
+    assert_eq!(project.iid, builder.iid());
+
+    let level_0 = level_assets
+        .get(
+            asset_server
+                .load::<Level>("embedded://ldtk/generated.ldtk#world:World/Level_0")
+                .id(),
+        )
+        .unwrap();
+    assert_eq!(level_0.iid, level_0_iid);
+    assert_eq!(level_0.uid, level_0_uid);
+    // Every layer definition has a layer, in every level.
+    assert_eq!(level_0.layers.len(), 3);
+    let layer_named = |identifier: &str| {
+        level_0
+            .layers
+            .values()
+            .map(|handle| layer_assets.get(handle.id()).unwrap())
+            .find(|layer| layer.identifier == identifier)
+            .unwrap()
+    };
+
+    let walls_layer = layer_named("Walls");
+    assert_eq!(walls_layer.iid, walls_iid);
+    assert_eq!(walls_layer.grid_size, I64Vec2::new(4, 3));
+    let walls_tiles = walls_layer.layer_type.get_tiles_layer().unwrap();
+    assert_eq!(walls_tiles.int_grid, [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 2]);
+
+    let ground_layer = layer_named("Ground");
+    let ground_tiles = ground_layer.layer_type.get_tiles_layer().unwrap();
+    assert_eq!(
+        ground_tiles
+            .tiles
+            .iter()
+            .map(|tile| (tile.offset, tile.source, tile.flip_x))
+            .collect::<Vec<_>>(),
+        [
+            (I64Vec2::new(0, 0), I64Vec2::new(16, 16), false),
+            (I64Vec2::new(32, 16), I64Vec2::new(48, 0), true),
+        ]
+    );
+
+    let things_layer = layer_named("Things");
+    let player_entity = things_layer
+        .get_children()
+        .map(|handle| entity_assets.get(handle.id()).unwrap())
+        .next()
+        .unwrap();
+    assert_eq!(player_entity.iid, player_iid);
+    assert_eq!(player_entity.identifier, "Player");
+    assert_eq!(player_entity.location, I64Vec2::new(32, 16));
+    assert_eq!(player_entity.size, I64Vec2::new(16, 32));
+    assert_eq!(
+        entity_definitions
+            .get(player_entity.entity_definition.id())
+            .unwrap()
+            .uid,
+        player
+    );
+
+    let level_1 = level_assets
+        .get(
+            asset_server
+                .load::<Level>("embedded://ldtk/generated.ldtk#world:World/Level_1")
+                .id(),
+        )
+        .unwrap();
+    assert_eq!(level_1.location, I64Vec2::new(64, 0));
+    assert_eq!(level_1.bg_color, Color::srgb_u8(0x10, 0x20, 0x30));
+    assert_eq!(level_1.index, 1);
+
+    // The JSON writer gives the same project as saving the loaded assets.
+    let saved = builder.save(&ProjectSaverSettings::default()).unwrap();
+    let mut system_state = SystemState::<LdtkAssets>::new(app.world_mut());
+    let ldtk_assets = system_state.get(app.world());
+    let resaved = save_project(
+        &project_handle,
+        &ldtk_assets,
+        &ProjectSaverSettings::default(),
+    )
+    .unwrap();
+    assert_eq!(resaved.project, saved.project);
+
+    // Loading again rebuilds the project in place.
+    builder.add_level(LevelBuilder::new("Level_2", I64Vec2::new(64, 48)));
+    let rebuilt_handle = builder
+        .load(
+            app.world()
+                .get_resource::<bevy_asset::io::embedded::EmbeddedAssetRegistry>()
+                .unwrap(),
+            &asset_server,
+        )
+        .unwrap();
+    assert_eq!(rebuilt_handle, project_handle);
+    let level_count = |app: &App| {
+        let project = app
+            .world()
+            .get_resource::<Assets<Project>>()
+            .unwrap()
+            .get(project_handle.id())
+            .unwrap();
+        let world_assets = app.world().get_resource::<Assets<World>>().unwrap();
+        project
+            .worlds
+            .values()
+            .filter_map(|handle| world_assets.get(handle.id()))
+            .map(|world| world.levels.len())
+            .sum::<usize>()
+    };
+    assert_eq!(level_count(&app), 2);
+    for _ in 0..1000 {
+        if level_count(&app) == 3 {
+            break;
+        }
+        app.update();
+    }
+    assert_eq!(level_count(&app), 3);
+
+    // Layers must only hold what their layer definition allows.
+    let mut bad_level = LevelBuilder::new("Bad", I64Vec2::new(64, 48));
+    let mut bad_layer = LayerBuilder::new(walls);
+    bad_layer.add_tile(I64Vec2::ZERO, 0);
+    bad_level.add_layer(bad_layer);
+    builder.add_level(bad_level);
+    assert!(matches!(
+        builder.save(&ProjectSaverSettings::default()),
+        Err(LdtkError::LdtkExportError(_))
+    ));
+}
+
+#[test]
+fn ldtk_data() {
+    let data = LdtkData::from_path("assets/ldtk/single_world.ldtk").unwrap();
+
+    // The same model as loading the project as an asset.
+    let (project_handle, app, _asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let project = app
+        .world()
+        .get_resource::<Assets<Project>>()
+        .unwrap()
+        .get(project_handle.id())
+        .unwrap();
+    let world_assets = app.world().get_resource::<Assets<World>>().unwrap();
+    let level_assets = app.world().get_resource::<Assets<Level>>().unwrap();
+    let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
+
+    assert_eq!(data.iid, project.iid);
+    assert_eq!(data.worlds.len(), project.worlds.len());
+    assert_eq!(
+        data.level_field_definitions.len(),
+        project.level_field_definitions.len()
+    );
+
+    let world = &data.worlds[0];
+    let world_asset = world_assets
+        .get(project.worlds.get(&world.iid).unwrap().id())
+        .unwrap();
+    assert_eq!(world.identifier, world_asset.identifier);
+    assert_eq!(world.levels.len(), world_asset.levels.len());
+
+    for level in data.levels() {
+        let level_asset = level_assets
+            .get(world_asset.levels.get(&level.iid).unwrap().id())
+            .unwrap();
+        assert_eq!(level.identifier, level_asset.identifier);
+        assert_eq!(level.location, level_asset.location);
+        assert_eq!(level.index, level_asset.index);
+        assert_eq!(
+            level.field_instances.len(),
+            level_asset.field_instances.len()
+        );
+        assert_eq!(
+            level.external_path,
+            Some(PathBuf::from(format!(
+                "assets/ldtk/single_world/{}.ldtkl",
+                level.identifier
+            )))
+        );
+
+        assert_eq!(level.layers.len(), level_asset.layers.len());
+        for layer in &level.layers {
+            let layer_asset = layer_assets
+                .get(level_asset.layers.get(&layer.iid).unwrap().id())
+                .unwrap();
+            assert_eq!(layer.identifier, layer_asset.identifier);
+            assert_eq!(layer.index, layer_asset.index);
+            assert_eq!(layer.grid_size, layer_asset.grid_size);
+            assert!(data.layer_definitions.contains_key(&layer.layer_definition));
+
+            if let (Some(tiles_layer), Some(tiles_layer_asset)) = (
+                layer.layer_type.get_tiles_layer(),
+                layer_asset.layer_type.get_tiles_layer(),
+            ) {
+                assert_eq!(tiles_layer.int_grid, tiles_layer_asset.int_grid);
+                assert_eq!(tiles_layer.tiles.len(), tiles_layer_asset.tiles.len());
+            }
+        }
+    }
+
+    let full_tileset = data
+        .tileset_definitions
+        .values()
+        .find(|tileset_definition| tileset_definition.identifier == "FullTileset")
+        .unwrap();
+    assert_eq!(
+        full_tileset.tileset_image_path,
+        Some(PathBuf::from("assets/tilesets/full.png"))
+    );
+
+    // External levels are read through the given reader, with paths relative to the given
+    // directory.
+    let bytes = std::fs::read("assets/ldtk/single_world.ldtk").unwrap();
+    let read_paths = std::sync::Mutex::new(Vec::new());
+    let reader = |path: &std::path::Path| {
+        read_paths.lock().unwrap().push(path.to_path_buf());
+        Ok(std::fs::read(PathBuf::from("assets/ldtk").join(path))?)
+    };
+    let from_bytes = LdtkData::from_bytes(&bytes, "", &reader).unwrap();
+    assert_eq!(
+        *read_paths.lock().unwrap(),
+        [
+            PathBuf::from("single_world/Level_0.ldtkl"),
+            PathBuf::from("single_world/Level_1.ldtkl"),
+        ]
+    );
+    assert_eq!(from_bytes.levels().count(), data.levels().count());
+
+    let failing_reader = |_: &std::path::Path| Err(ldtk_import_error!("not found"));
+    assert!(LdtkData::from_bytes(&bytes, "", &failing_reader).is_err());
+
+    // Field values refer to other parts of the project by id.
+    let data = LdtkData::from_path("assets/ldtk/field_types.ldtk").unwrap();
+    let door_iid = iid!("de1e0e30-ac70-11f0-9854-a5862cb5a481");
+    let door = data.entity(door_iid).unwrap();
+    let field = |identifier: &str| {
+        &door
+            .field_instances
+            .get(identifier)
+            .unwrap()
+            .field_instance_type
+    };
+
+    assert!(matches!(field("Integer"), FieldInstanceTypeData::Int(99)));
+    let FieldInstanceTypeData::EntityRef(entity_ref) = field("EntityRef") else {
+        panic!("not an entity ref!");
+    };
+    assert_eq!(data.entity(entity_ref.entity_iid).unwrap().iid, door_iid);
+    let FieldInstanceTypeData::Enum(enum_value) = field("Enum") else {
+        panic!("not an enum!");
+    };
+    assert!(
+        data.enum_definitions
+            .get(&enum_value.enum_identifier)
+            .unwrap()
+            .values
+            .contains_key(&enum_value.value)
+    );
+    let FieldInstanceTypeData::Tile(tile) = field("Tile") else {
+        panic!("not a tile!");
+    };
+    assert_eq!(tile.corner, I64Vec2::new(16, 0));
+    assert!(
+        data.tileset_definitions
+            .contains_key(&tile.tileset_definition)
+    );
+    assert!(matches!(
+        field("FilePath"),
+        FieldInstanceTypeData::FilePath(path) if path == &PathBuf::from("assets/tilesets/terrain.png")
+    ));
+}
+
+#[test]
+fn tile_mesh() {
+    let tileset_definition = TilesetDefinition {
+        tile_grid_size: I64Vec2::new(4, 2),
+        custom_data: Default::default(),
+        enum_tags: Default::default(),
+        identifier: "Tiles".to_string(),
+        uid: 1,
+        padding: 0,
+        spacing: 0,
+        tileset_image_size: I64Vec2::new(64, 32),
+        tileset_image: None,
+        tags: Vec::new(),
+        tags_source_enum_uid: None,
+        tile_grid_pixel_size: 16,
+        texture_atlas_layout: Handle::default(),
+    };
+    let tile = |offset: I64Vec2, source: I64Vec2, flip_x: bool, flip_y: bool| TileInstance {
+        opacity: 0.5,
+        flip_x,
+        flip_y,
+        offset,
+        source,
+    };
+
+    let mut builder = TileMeshBuilder::new(&tileset_definition);
+    builder.add_tile(&tile(I64Vec2::new(0, 0), I64Vec2::new(16, 0), false, false));
+    builder.add_tile(&tile(
+        I64Vec2::new(16, 32),
+        I64Vec2::new(32, 16),
+        true,
+        true,
+    ));
+    assert_eq!(builder.len(), 2);
+    let mesh = builder.build();
+
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("no positions!");
+    };
+    // The y-axis is inverted, with the origin at the layer's top left corner.
+    assert_eq!(
+        positions[4..],
+        [
+            [16.0, -32.0, 0.0],
+            [32.0, -32.0, 0.0],
+            [32.0, -48.0, 0.0],
+            [16.0, -48.0, 0.0]
+        ]
+    );
+
+    let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) else {
+        panic!("no uvs!");
+    };
+    assert_eq!(uvs[..4], [[0.25, 0.0], [0.5, 0.0], [0.5, 0.5], [0.25, 0.5]]);
+    // Flipped on both axes.
+    assert_eq!(uvs[4..], [[0.75, 1.0], [0.5, 1.0], [0.5, 0.5], [0.75, 0.5]]);
+
+    let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+    else {
+        panic!("no colors!");
+    };
+    assert!(colors.iter().all(|color| color[3] == 0.5));
+    assert_eq!(mesh.indices().unwrap().len(), 12);
+
+    // A loaded layer gives one quad per tile.
+    let (_project_handle, app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
+    let tileset_definitions = app
+        .world()
+        .get_resource::<Assets<TilesetDefinition>>()
+        .unwrap();
+
+    let terrain = layer_assets
+        .get(
+            asset_server
+                .load::<LayerInstance>("ldtk/single_world/Level_0.ldtkl#Terrain")
+                .id(),
+        )
+        .unwrap();
+    let tiles_layer = terrain.layer_type.get_tiles_layer().unwrap();
+    let tileset_definition = tileset_definitions
+        .get(tiles_layer.tileset_definition.as_ref().unwrap().id())
+        .unwrap();
+    let mesh = tiles_layer_mesh(terrain, tileset_definition).unwrap();
+    assert_eq!(mesh.count_vertices(), tiles_layer.tiles.len() * 4);
+
+    let material = TilesLayerMaterial::new(terrain).unwrap();
+    assert_eq!(Some(&material.texture), tiles_layer.tileset_image.as_ref());
+    assert_eq!(material.color.alpha(), terrain.opacity as f32);
+
+    let things = layer_assets
+        .get(
+            asset_server
+                .load::<LayerInstance>("ldtk/single_world/Level_0.ldtkl#Things")
+                .id(),
+        )
+        .unwrap();
+    assert!(matches!(
+        tiles_layer_mesh(things, tileset_definition),
+        Err(LdtkError::NotATilesLayer(_))
+    ));
+    assert!(TilesLayerMaterial::new(things).is_none());
+}
+
+#[test]
+fn tile_chunks() {
+    let (_project_handle, mut app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let layer_assets = app.world().get_resource::<Assets<LayerInstance>>().unwrap();
+    let tileset_definitions = app
+        .world()
+        .get_resource::<Assets<TilesetDefinition>>()
+        .unwrap();
+
+    let terrain = layer_assets
+        .get(
+            asset_server
+                .load::<LayerInstance>("ldtk/single_world/Level_0.ldtkl#Terrain")
+                .id(),
+        )
+        .unwrap();
+    let tiles_layer = terrain.layer_type.get_tiles_layer().unwrap();
+    let tileset_definition = tileset_definitions
+        .get(tiles_layer.tileset_definition.as_ref().unwrap().id())
+        .unwrap();
+
+    assert!(matches!(
+        TileChunkLayout::new(terrain, I64Vec2::new(4, 0)),
+        Err(LdtkError::BadTileChunkLayout { .. })
+    ));
+
+    let layout = TileChunkLayout::new(terrain, I64Vec2::splat(4)).unwrap();
+    assert_eq!(layout.chunk_size(), I64Vec2::splat(4));
+    assert_eq!(layout.grid_cell_size(), terrain.grid_cell_size);
+    let chunk_pixel_size = layout.chunk_pixel_size();
+    assert_eq!(chunk_pixel_size, I64Vec2::splat(4 * terrain.grid_cell_size));
+    assert_eq!(
+        layout.chunk_for_cell(I64Vec2::new(3, 4)),
+        I64Vec2::new(0, 1)
+    );
+    assert_eq!(
+        layout.chunk_for_offset(chunk_pixel_size - I64Vec2::ONE),
+        I64Vec2::ZERO
+    );
+    assert_eq!(layout.chunk_for_offset(chunk_pixel_size), I64Vec2::ONE);
+    assert_eq!(layout.chunk_count(I64Vec2::new(8, 9)), I64Vec2::new(2, 3));
+
+    let chunks = tiles_layer_chunks(terrain, tileset_definition, layout).unwrap();
+    assert!(chunks.len() > 1);
+    assert_eq!(
+        chunks.iter().map(|chunk| chunk.tile_count).sum::<usize>(),
+        tiles_layer.tiles.len()
+    );
+    assert!(chunks.is_sorted_by_key(|chunk| (chunk.chunk.y, chunk.chunk.x)));
+
+    let tile_size = tileset_definition.tile_grid_pixel_size as f32;
+    for chunk in &chunks {
+        assert_eq!(chunk.location, layout.chunk_location(chunk.chunk));
+        assert_eq!(chunk.mesh.count_vertices(), chunk.tile_count * 4);
+
+        // Relative to the chunk's top left corner, with the y-axis inverted.
+        let (min, max) = (chunk.aabb.min(), chunk.aabb.max());
+        assert!(min.x >= 0.0 && max.y <= 0.0);
+        assert!(max.x <= chunk_pixel_size.x as f32 + tile_size);
+        assert!(min.y >= -(chunk_pixel_size.y as f32 + tile_size));
+
+        let rebuilt = tiles_layer_chunk(terrain, tileset_definition, layout, chunk.chunk)
+            .unwrap()
+            .unwrap();
+        assert_eq!(rebuilt.tile_count, chunk.tile_count);
+        assert_eq!(rebuilt.aabb, chunk.aabb);
+    }
+
+    assert!(
+        tiles_layer_chunk(terrain, tileset_definition, layout, I64Vec2::splat(-1))
+            .unwrap()
+            .is_none()
+    );
+
+    let things = layer_assets
+        .get(
+            asset_server
+                .load::<LayerInstance>("ldtk/single_world/Level_0.ldtkl#Things")
+                .id(),
+        )
+        .unwrap();
+    assert!(matches!(
+        tiles_layer_chunks(things, tileset_definition, layout),
+        Err(LdtkError::NotATilesLayer(_))
+    ));
+
+    // Spawned chunks carry their bounds, and are placed at their location in the layer.
+    let chunk = chunks.into_iter().last().unwrap();
+    let (chunk_coords, location, aabb) = (chunk.chunk, chunk.location, chunk.aabb);
+    let mut meshes = Assets::<Mesh>::default();
+    let bundle = chunk.into_bundle(&mut meshes);
+    let entity = app.world_mut().spawn(bundle).id();
+
+    let entity_ref = app.world().entity(entity);
+    assert_eq!(
+        entity_ref.get::<LdtkTileChunk>().unwrap().chunk,
+        chunk_coords
+    );
+    assert_eq!(entity_ref.get::<Aabb>(), Some(&aabb));
+    assert!(entity_ref.contains::<NoAutoAabb>());
+    assert!(meshes.contains(&entity_ref.get::<Mesh2d>().unwrap().0));
+    assert_eq!(
+        entity_ref.get::<Transform>().unwrap().translation,
+        Vec3::new(location.x as f32, -location.y as f32, 0.0)
+    );
+}
+
+fn test_image(size: UVec2, color: impl Fn(u32, u32) -> Srgba) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    for y in 0..size.y {
+        for x in 0..size.x {
+            image.set_color_at(x, y, color(x, y).into()).unwrap();
+        }
+    }
+    image
+}
+
+#[test]
+fn rasterize() {
+    let (_project_handle, mut app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+
+    let load_layer = |label: &str| {
+        asset_server.load::<LayerInstance>(format!("ldtk/single_world/Level_0.ldtkl#{label}"))
+    };
+    let (terrain, objects, things) = (
+        load_layer("Terrain"),
+        load_layer("Objects"),
+        load_layer("Things"),
+    );
+    let level_handle = asset_server.load::<Level>("ldtk/single_world/Level_0.ldtkl");
+
+    let layer_assets = app.world().resource::<Assets<LayerInstance>>();
+    let tiles_layer = layer_assets
+        .get(objects.id())
+        .unwrap()
+        .layer_type
+        .get_tiles_layer()
+        .unwrap();
+    let tileset_image = tiles_layer.tileset_image.clone().unwrap();
+    let tileset_definition = app
+        .world()
+        .resource::<Assets<TilesetDefinition>>()
+        .get(tiles_layer.tileset_definition.as_ref().unwrap().id())
+        .unwrap();
+    let (tileset_image_size, tile_size) = (
+        tileset_definition.tileset_image_size.as_uvec2(),
+        tileset_definition.tile_grid_pixel_size,
+    );
+
+    // No image decoders are enabled for tests, so stand in for the tileset image.
+    let gradient = |x: u32, y: u32| Srgba::rgb_u8((x % 256) as u8, (y % 256) as u8, 128);
+    let replace_tileset_image = |app: &mut App, image: Image| {
+        app.world_mut()
+            .resource_mut::<Assets<Image>>()
+            .insert(tileset_image.id(), image)
+            .unwrap();
+    };
+
+    // With a transparent tileset, only the int grid cells are drawn.
+    replace_tileset_image(&mut app, test_image(tileset_image_size, |_, _| Srgba::NONE));
+    let rasterize = |app: &App, layer: &Handle<LayerInstance>| {
+        let world = app.world();
+        let rasterizer = LdtkRasterizer {
+            images: world.resource(),
+            levels: world.resource(),
+            layers: world.resource(),
+            layer_definitions: world.resource(),
+            tileset_definitions: world.resource(),
+        };
+        let layer_instance = rasterizer.layers.get(layer.id()).unwrap();
+        let layer_image = rasterizer.rasterize_layer(layer_instance);
+        let level = world
+            .resource::<Assets<Level>>()
+            .get(level_handle.id())
+            .unwrap();
+        (layer_image, rasterizer.rasterize_level(level).unwrap())
+    };
+
+    let (terrain_image, level_image) = rasterize(&app, &terrain);
+    let terrain_image = terrain_image.unwrap();
+    let world = app.world();
+    let terrain_instance = world
+        .resource::<Assets<LayerInstance>>()
+        .get(terrain.id())
+        .unwrap();
+    let terrain_definition = world
+        .resource::<Assets<LayerDefinition>>()
+        .get(terrain_instance.layer_definition.id())
+        .unwrap();
+    let int_grid = &terrain_instance
+        .layer_type
+        .get_tiles_layer()
+        .unwrap()
+        .int_grid;
+    assert_eq!(
+        terrain_image.size().as_i64vec2(),
+        terrain_instance.grid_size * terrain_instance.grid_cell_size
+    );
+    for (cell, value) in [
+        (0, int_grid[0]),
+        (int_grid.len() - 1, int_grid[int_grid.len() - 1]),
+    ] {
+        let cell = cell as u32;
+        let width = terrain_instance.grid_size.x as u32;
+        let cell_size = terrain_instance.grid_cell_size as u32;
+        let (x, y) = ((cell % width) * cell_size, (cell / width) * cell_size);
+        let color = terrain_definition
+            .int_grid_values
+            .get(&value)
+            .unwrap()
+            .color;
+        assert_eq!(terrain_image.get_color_at(x, y).unwrap(), color);
+        assert_eq!(
+            terrain_image
+                .get_color_at(x + cell_size - 1, y + cell_size - 1)
+                .unwrap(),
+            color
+        );
+        // Every cell of the terrain is filled, hiding the level's background color.
+        assert_eq!(level_image.get_color_at(x, y).unwrap(), color);
+    }
+
+    // Tiles sample the tileset image, honoring their flips.
+    replace_tileset_image(&mut app, test_image(tileset_image_size, gradient));
+    let (objects_image, level_image) = rasterize(&app, &objects);
+    let objects_image = objects_image.unwrap();
+    let objects_instance = app
+        .world()
+        .resource::<Assets<LayerInstance>>()
+        .get(objects.id())
+        .unwrap();
+    for tile in &objects_instance.layer_type.get_tiles_layer().unwrap().tiles {
+        let source = tile.source
+            + I64Vec2::new(
+                if tile.flip_x { tile_size - 1 } else { 0 },
+                if tile.flip_y { tile_size - 1 } else { 0 },
+            );
+        let expected: Color = gradient(source.x as u32, source.y as u32).into();
+        let (x, y) = (tile.offset.x as u32, tile.offset.y as u32);
+        assert_eq!(objects_image.get_color_at(x, y).unwrap(), expected);
+        // The objects layer is drawn over the terrain.
+        assert_eq!(level_image.get_color_at(x, y).unwrap(), expected);
+    }
+
+    let (things_image, _) = rasterize(&app, &things);
+    assert!(matches!(things_image, Err(LdtkError::NotATilesLayer(_))));
+
+    // The background is cropped, scaled and placed at its corner over the background color.
+    let background_image = app
+        .world_mut()
+        .resource_mut::<Assets<Image>>()
+        .add(test_image(UVec2::splat(4), gradient));
+    let level = Level {
+        bg_color: Color::srgb(1.0, 0.0, 0.0),
+        neighbours: Vec::new(),
+        background: Some(LevelBackground {
+            image: Some(background_image),
+            crop_corner: DVec2::new(1.0, 1.0),
+            crop_size: DVec2::new(2.0, 2.0),
+            scale: DVec2::new(2.0, 1.0),
+            corner: I64Vec2::new(1, 2),
+        }),
+        field_instances: Default::default(),
+        identifier: "Background".to_string(),
+        iid: Iid::new_v4(),
+        size: I64Vec2::new(8, 8),
+        uid: 0,
+        world_depth: 0,
+        location: I64Vec2::ZERO,
+        layers: Default::default(),
+        index: 0,
+        source: None,
+    };
+    let world = app.world();
+    let level_image = LdtkRasterizer {
+        images: world.resource(),
+        levels: world.resource(),
+        layers: world.resource(),
+        layer_definitions: world.resource(),
+        tileset_definitions: world.resource(),
+    }
+    .rasterize_level(&level)
+    .unwrap();
+    assert_eq!(level_image.size(), UVec2::splat(8));
+    assert_eq!(level_image.get_color_at(0, 0).unwrap(), level.bg_color);
+    assert_eq!(level_image.get_color_at(5, 3).unwrap(), level.bg_color);
+    assert_eq!(
+        level_image.get_color_at(1, 2).unwrap(),
+        gradient(1, 1).into()
+    );
+    assert_eq!(
+        level_image.get_color_at(2, 2).unwrap(),
+        gradient(1, 1).into()
+    );
+    assert_eq!(
+        level_image.get_color_at(3, 2).unwrap(),
+        gradient(2, 1).into()
+    );
+    assert_eq!(
+        level_image.get_color_at(4, 3).unwrap(),
+        gradient(2, 2).into()
+    );
+}
+
+#[test]
+fn world_map() {
+    let (_project_handle, mut app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let world_handle = asset_server.load::<World>("ldtk/single_world.ldtk#world:World");
+    let level_0 = iid!("ea1c1e10-ac70-11f0-b03c-5f243de911d6");
+
+    // No image decoders are enabled for tests, so stand in for the tileset image.
+    let tileset_image = app
+        .world()
+        .resource::<Assets<TilesetDefinition>>()
+        .iter()
+        .find_map(|(_, tileset_definition)| tileset_definition.tileset_image.clone())
+        .unwrap();
+    app.world_mut()
+        .resource_mut::<Assets<Image>>()
+        .insert(
+            tileset_image.id(),
+            test_image(UVec2::splat(320), |_, _| Srgba::NONE),
+        )
+        .unwrap();
+
+    let world = app.world();
+    let rasterizer = LdtkRasterizer {
+        images: world.resource(),
+        levels: world.resource(),
+        layers: world.resource(),
+        layer_definitions: world.resource(),
+        tileset_definitions: world.resource(),
+    };
+    let world_asset = world
+        .resource::<Assets<World>>()
+        .get(world_handle.id())
+        .unwrap();
+    let level_0_asset = rasterizer
+        .levels
+        .iter()
+        .find(|(_, level)| level.iid == level_0)
+        .unwrap()
+        .1;
+    let color_at =
+        |image: &Image, x: u32, y: u32| image.get_color_at(x, y).unwrap().to_srgba().to_u8_array();
+    let bg_color = level_0_asset.bg_color.to_srgba().to_u8_array();
+
+    // Level_0 covers (0, 0) to (256, 256), and Level_1 covers (256, 1) to (512, 257).
+    let settings = WorldMapSettings {
+        level_style: WorldMapLevelStyle::BgColor,
+        ..Default::default()
+    };
+    let map = rasterizer
+        .rasterize_world_map(world_asset, &settings)
+        .unwrap();
+    assert_eq!(map.size(), UVec2::new(32, 17));
+    assert_eq!(color_at(&map, 0, 0), bg_color);
+    assert_eq!(color_at(&map, 31, 16), bg_color);
+    assert_eq!(color_at(&map, 0, 16), [0; 4]);
+
+    let settings = WorldMapSettings {
+        visited: Some(IidSet::from_iter([level_0])),
+        ..settings
+    };
+    let map = rasterizer
+        .rasterize_world_map(world_asset, &settings)
+        .unwrap();
+    assert_eq!(color_at(&map, 0, 0), bg_color);
+    assert_eq!(
+        color_at(&map, 16, 0),
+        settings.unvisited_color.to_srgba().to_u8_array()
+    );
+
+    // Each map pixel is the average of a cell of the rasterized level. The terrain fills every
+    // cell of Level_0 with a single int grid color.
+    let level_image = rasterizer.rasterize_level(level_0_asset).unwrap();
+    let settings = WorldMapSettings {
+        level_style: WorldMapLevelStyle::Rasterize,
+        visited: None,
+        ..settings
+    };
+    let map = rasterizer
+        .rasterize_world_map(world_asset, &settings)
+        .unwrap();
+    assert_eq!(color_at(&map, 0, 0), color_at(&level_image, 0, 0));
+    assert_eq!(color_at(&map, 15, 15), color_at(&level_image, 255, 255));
+
+    // At one map pixel per world pixel, the map matches the rasterized level.
+    let settings = WorldMapSettings {
+        pixels_per_cell: 16,
+        ..settings
+    };
+    let map = rasterizer
+        .rasterize_world_map(world_asset, &settings)
+        .unwrap();
+    assert_eq!(map.size(), UVec2::new(512, 257));
+    for (x, y) in [(0, 0), (17, 130), (255, 255)] {
+        assert_eq!(color_at(&map, x, y), color_at(&level_image, x, y));
+    }
+}
+
+#[test]
+fn texture_atlas() {
+    // Three by two tiles of 16 pixels, with a padding of 2 and a spacing of 1.
+    let tileset_definition = TilesetDefinition {
+        tile_grid_size: I64Vec2::new(3, 2),
+        custom_data: Default::default(),
+        enum_tags: Default::default(),
+        identifier: "Spaced".to_string(),
+        uid: 1,
+        padding: 2,
+        spacing: 1,
+        tileset_image_size: I64Vec2::new(54, 37),
+        tileset_image: None,
+        tags: Vec::new(),
+        tags_source_enum_uid: None,
+        tile_grid_pixel_size: 16,
+        texture_atlas_layout: Handle::default(),
+    };
+
+    let texture_atlas_layout = tileset_definition.build_texture_atlas_layout().unwrap();
+    assert_eq!(texture_atlas_layout.size, UVec2::new(54, 37));
+    assert_eq!(texture_atlas_layout.textures.len(), 6);
+
+    for (source, index) in [
+        (I64Vec2::new(2, 2), Some(0)),
+        (I64Vec2::new(19, 2), Some(1)),
+        (I64Vec2::new(36, 19), Some(5)),
+        (I64Vec2::new(3, 2), None),
+        (I64Vec2::new(53, 2), None),
+        (I64Vec2::new(2, 36), None),
+        (I64Vec2::new(-15, 2), None),
+    ] {
+        assert_eq!(tileset_definition.atlas_index(source), index, "{source}");
+        if let Some(index) = index {
+            assert_eq!(
+                texture_atlas_layout.textures[index].min.as_i64vec2(),
+                source
+            );
+        }
+    }
+
+    let tile = TileInstance {
+        opacity: 1.0,
+        flip_x: true,
+        flip_y: false,
+        offset: I64Vec2::ZERO,
+        source: I64Vec2::new(36, 2),
+    };
+    assert_eq!(tile.atlas_index(&tileset_definition), Some(2));
+
+    let rectangle = |size: I64Vec2| TilesetRectangle {
+        corner: I64Vec2::new(19, 19),
+        size,
+        tileset_definition: Handle::default(),
+    };
+    assert_eq!(
+        rectangle(I64Vec2::splat(16)).atlas_index(&tileset_definition),
+        Some(4)
+    );
+    assert_eq!(
+        rectangle(I64Vec2::new(33, 16)).atlas_index(&tileset_definition),
+        None
+    );
+
+    // Every loaded tileset has a labeled layout, which indexes the tiles of its layers.
+    let (_project_handle, app, asset_server) =
+        perpare_and_wait_on_project("ldtk/single_world.ldtk");
+    let tileset_definition = app
+        .world()
+        .resource::<Assets<TilesetDefinition>>()
+        .get(
+            asset_server
+                .load::<TilesetDefinition>("ldtk/single_world.ldtk#tileset_definition:FullTileset")
+                .id(),
+        )
+        .unwrap();
+    assert_eq!(
+        tileset_definition.texture_atlas_layout,
+        asset_server
+            .load::<TextureAtlasLayout>("ldtk/single_world.ldtk#texture_atlas_layout:FullTileset")
+    );
+    let texture_atlas_layout = app
+        .world()
+        .resource::<Assets<TextureAtlasLayout>>()
+        .get(tileset_definition.texture_atlas_layout.id())
+        .unwrap();
+    assert_eq!(
+        texture_atlas_layout.size.as_i64vec2(),
+        tileset_definition.tileset_image_size
+    );
+    assert_eq!(
+        texture_atlas_layout.textures.len() as i64,
+        tileset_definition.tile_grid_size.element_product()
+    );
+
+    let objects = app
+        .world()
+        .resource::<Assets<LayerInstance>>()
+        .get(
+            asset_server
+                .load::<LayerInstance>("ldtk/single_world/Level_0.ldtkl#Objects")
+                .id(),
+        )
+        .unwrap();
+    for tile in &objects.layer_type.get_tiles_layer().unwrap().tiles {
+        let index = tile.atlas_index(tileset_definition).unwrap();
+        assert_eq!(
+            texture_atlas_layout.textures[index].min.as_i64vec2(),
+            tile.source
+        );
+
+        let texture_atlas = tileset_definition.texture_atlas(index);
+        assert_eq!(texture_atlas.index, index);
+        assert_eq!(
+            texture_atlas.layout,
+            tileset_definition.texture_atlas_layout
+        );
+    }
+}
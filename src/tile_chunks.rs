@@ -0,0 +1,219 @@
+//! Splitting a tiles layer into fixed size chunks, each with a mesh of its own.
+//!
+//! A single mesh from [crate::tile_mesh::tiles_layer_mesh] is fine for most levels, but for very
+//! large ones it can't be frustum culled, and has to be rebuilt whole whenever a single tile
+//! changes. [tiles_layer_chunks] instead groups the tiles by a [TileChunkLayout], and builds one
+//! [TileChunk] per group which holds any tiles.
+//!
+//! Each chunk is meant to be spawned as its own entity, beneath the [crate::spawn::LdtkLayer]
+//! entity, with [TileChunk::into_bundle]. The bundle carries the chunk's [Aabb], computed from its
+//! mesh, so the renderer can cull chunks outside of the view. After editing a tile, only the chunk
+//! found by [TileChunkLayout::chunk_for_offset] needs to be rebuilt, with [tiles_layer_chunk].
+//!
+//! Chunk meshes have no material, so add the same one as for
+//! [crate::tile_mesh::TilesLayerMaterial] to each chunk entity.
+
+use bevy_asset::Assets;
+use bevy_camera::primitives::{Aabb, MeshAabb};
+use bevy_camera::visibility::{NoAutoAabb, Visibility};
+use bevy_ecs::bundle::Bundle;
+use bevy_ecs::component::Component;
+use bevy_math::I64Vec2;
+use bevy_mesh::{Mesh, Mesh2d};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::Reflect;
+use bevy_transform::components::Transform;
+
+use crate::error::LdtkError;
+use crate::layer::LayerInstance;
+use crate::result::LdtkResult;
+use crate::spawn::ldtk_location_to_translation;
+use crate::tile_mesh::{TileMeshBuilder, get_tiles_layer};
+use crate::tileset_definition::TilesetDefinition;
+
+/// How the cells of a layer are grouped into chunks.
+///
+/// Built with [Self::new], which guarantees that both sizes are positive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileChunkLayout {
+    chunk_size: I64Vec2,
+    grid_cell_size: i64,
+}
+
+impl TileChunkLayout {
+    /// The chunk size used when none is given: 32 by 32 cells.
+    pub const DEFAULT_CHUNK_SIZE: I64Vec2 = I64Vec2::splat(32);
+
+    /// A layout for the given layer, with chunks of `chunk_size` cells.
+    ///
+    /// Returns [LdtkError::BadTileChunkLayout] if either component of `chunk_size`, or the
+    /// layer's [LayerInstance::grid_cell_size], is not positive.
+    pub fn new(layer_instance: &LayerInstance, chunk_size: I64Vec2) -> LdtkResult<Self> {
+        let grid_cell_size = layer_instance.grid_cell_size;
+
+        if chunk_size.cmple(I64Vec2::ZERO).any() || grid_cell_size <= 0 {
+            return Err(LdtkError::BadTileChunkLayout {
+                chunk_size,
+                grid_cell_size,
+            });
+        }
+
+        Ok(Self {
+            chunk_size,
+            grid_cell_size,
+        })
+    }
+
+    /// The size of a chunk, in grid cells.
+    pub const fn chunk_size(&self) -> I64Vec2 {
+        self.chunk_size
+    }
+
+    /// The size of a grid cell, in pixels. See [LayerInstance::grid_cell_size].
+    pub const fn grid_cell_size(&self) -> i64 {
+        self.grid_cell_size
+    }
+
+    /// The size of a chunk, in pixels.
+    pub const fn chunk_pixel_size(&self) -> I64Vec2 {
+        I64Vec2::new(
+            self.chunk_size.x * self.grid_cell_size,
+            self.chunk_size.y * self.grid_cell_size,
+        )
+    }
+
+    /// The chunk holding the given grid cell.
+    pub fn chunk_for_cell(&self, cell: I64Vec2) -> I64Vec2 {
+        cell.div_euclid(self.chunk_size)
+    }
+
+    /// The chunk holding a tile at the given offset, in pixels, such as [TileInstance::offset].
+    ///
+    /// [TileInstance::offset]: crate::tile_instance::TileInstance::offset
+    pub fn chunk_for_offset(&self, offset: I64Vec2) -> I64Vec2 {
+        offset.div_euclid(self.chunk_pixel_size())
+    }
+
+    /// The location of a chunk's top left corner in its layer, in pixels.
+    pub fn chunk_location(&self, chunk: I64Vec2) -> I64Vec2 {
+        chunk * self.chunk_pixel_size()
+    }
+
+    /// The number of chunks needed to cover a layer of the given [LayerInstance::grid_size].
+    pub fn chunk_count(&self, grid_size: I64Vec2) -> I64Vec2 {
+        (grid_size + self.chunk_size - I64Vec2::ONE).div_euclid(self.chunk_size)
+    }
+}
+
+/// Marks an entity drawing one chunk of a tiles layer, spawned from a [TileChunk].
+#[derive(Clone, Component, Debug, Reflect)]
+#[require(Transform, Visibility)]
+pub struct LdtkTileChunk {
+    /// The chunk's coordinates in its [TileChunkLayout].
+    pub chunk: I64Vec2,
+}
+
+/// The mesh for a single chunk of a tiles layer.
+#[derive(Debug)]
+pub struct TileChunk {
+    /// The chunk's coordinates in its [TileChunkLayout].
+    pub chunk: I64Vec2,
+    /// The location of the chunk's top left corner in its layer, in pixels.
+    ///
+    /// The mesh is relative to this location.
+    pub location: I64Vec2,
+    /// The number of tiles in the mesh.
+    pub tile_count: usize,
+    /// The chunk's tiles, built as with [crate::tile_mesh::tiles_layer_mesh].
+    pub mesh: Mesh,
+    /// The bounds of [Self::mesh]. This may reach past the chunk's cells, when the tileset's tiles
+    /// are larger than the layer's grid cells.
+    pub aabb: Aabb,
+}
+
+impl TileChunk {
+    fn new(chunk: I64Vec2, location: I64Vec2, builder: TileMeshBuilder) -> Option<Self> {
+        let tile_count = builder.len();
+        let mesh = builder.build();
+        let aabb = mesh.compute_aabb()?;
+
+        Some(Self {
+            chunk,
+            location,
+            tile_count,
+            mesh,
+            aabb,
+        })
+    }
+
+    /// Adds the mesh to `meshes`, and returns the components of an entity drawing this chunk.
+    ///
+    /// The entity should be a child of the [crate::spawn::LdtkLayer] entity, and is placed at
+    /// [Self::location] relative to it.
+    pub fn into_bundle(self, meshes: &mut Assets<Mesh>) -> impl Bundle + use<> {
+        (
+            LdtkTileChunk { chunk: self.chunk },
+            Mesh2d(meshes.add(self.mesh)),
+            Transform::from_translation(ldtk_location_to_translation(self.location, 0.0)),
+            self.aabb,
+            NoAutoAabb,
+        )
+    }
+}
+
+/// Builds a [TileChunk] for every chunk of the layer which holds at least one tile, ordered by
+/// row and then by column.
+///
+/// The `tileset_definition` should be the one pointed to by the layer's
+/// [crate::layer::TilesLayer::tileset_definition]. Returns [crate::LdtkError::NotATilesLayer] for
+/// an entities layer.
+pub fn tiles_layer_chunks(
+    layer_instance: &LayerInstance,
+    tileset_definition: &TilesetDefinition,
+    layout: TileChunkLayout,
+) -> LdtkResult<Vec<TileChunk>> {
+    let tiles_layer = get_tiles_layer(layer_instance)?;
+
+    let mut builders: HashMap<I64Vec2, TileMeshBuilder> = HashMap::default();
+    for tile in &tiles_layer.tiles {
+        let chunk = layout.chunk_for_offset(tile.offset);
+        builders
+            .entry(chunk)
+            .or_insert_with(|| {
+                TileMeshBuilder::new(tileset_definition).with_origin(layout.chunk_location(chunk))
+            })
+            .add_tile(tile);
+    }
+
+    let mut chunks: Vec<_> = builders
+        .into_iter()
+        .filter_map(|(chunk, builder)| TileChunk::new(chunk, layout.chunk_location(chunk), builder))
+        .collect();
+    chunks.sort_by_key(|chunk| (chunk.chunk.y, chunk.chunk.x));
+
+    Ok(chunks)
+}
+
+/// Builds the [TileChunk] for a single chunk of the layer, or `None` if it holds no tiles.
+///
+/// Use this to rebuild only the chunk holding a changed tile.
+pub fn tiles_layer_chunk(
+    layer_instance: &LayerInstance,
+    tileset_definition: &TilesetDefinition,
+    layout: TileChunkLayout,
+    chunk: I64Vec2,
+) -> LdtkResult<Option<TileChunk>> {
+    let tiles_layer = get_tiles_layer(layer_instance)?;
+
+    let location = layout.chunk_location(chunk);
+    let mut builder = TileMeshBuilder::new(tileset_definition).with_origin(location);
+    tiles_layer
+        .tiles
+        .iter()
+        .filter(|tile| layout.chunk_for_offset(tile.offset) == chunk)
+        .for_each(|tile| builder.add_tile(tile));
+
+    Ok((!builder.is_empty())
+        .then(|| TileChunk::new(chunk, location, builder))
+        .flatten())
+}
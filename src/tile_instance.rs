@@ -6,6 +6,7 @@ use bevy_reflect::Reflect;
 use crate::ldtk;
 use crate::ldtk_import_error;
 use crate::result::LdtkResult;
+use crate::tileset_definition::TilesetDefinition;
 
 /// An individual tile in a [crate::layer::TilesLayer] instance.
 ///
@@ -63,4 +64,10 @@ impl TileInstance {
             source,
         })
     }
+
+    /// The index of this tile in the [TilesetDefinition::texture_atlas_layout] of the layer's
+    /// tileset. See [TilesetDefinition::atlas_index].
+    pub fn atlas_index(&self, tileset_definition: &TilesetDefinition) -> Option<usize> {
+        tileset_definition.atlas_index(self.source)
+    }
 }
@@ -0,0 +1,199 @@
+//! Building a single [Mesh] for all of the tiles in a [TilesLayer].
+//!
+//! Drawing a layer with one sprite per tile quickly becomes expensive for large levels.
+//! [tiles_layer_mesh] instead builds one mesh holding a quad per [TileInstance], which can be drawn
+//! with a single [Mesh2d](bevy_mesh::Mesh2d) and a material using the layer's tileset image. See
+//! [TilesLayerMaterial] for the texture and tint to give that material.
+//!
+//! The mesh is in the space of the [LayerInstance], with its origin at the layer's top left corner.
+//! As with the [crate::spawn] module, the y-axis is inverted, so the mesh lines up with the
+//! [crate::spawn::LdtkLayer] entity it is inserted onto:
+//!
+//! ```rust,ignore
+//! let mesh = tiles_layer_mesh(layer_instance, tileset_definition)?;
+//! let material = TilesLayerMaterial::new(layer_instance).unwrap();
+//! commands.entity(layer_entity).insert((
+//!     Mesh2d(meshes.add(mesh)),
+//!     MeshMaterial2d(materials.add(ColorMaterial {
+//!         color: material.color,
+//!         texture: Some(material.texture),
+//!         ..Default::default()
+//!     })),
+//! ));
+//! ```
+//!
+//! Building the mesh happens entirely on the CPU, and needs neither a GPU nor a running app.
+
+use bevy_asset::{Handle, RenderAssetUsages};
+use bevy_color::{Alpha, Color};
+use bevy_image::Image;
+use bevy_math::{I64Vec2, Vec2};
+use bevy_mesh::{Indices, Mesh, PrimitiveTopology};
+
+use crate::error::LdtkError;
+use crate::layer::{LayerInstance, TilesLayer};
+use crate::result::LdtkResult;
+use crate::tile_instance::TileInstance;
+use crate::tileset_definition::TilesetDefinition;
+
+/// Accumulates [TileInstance]s into a [Mesh], one quad per tile.
+///
+/// Each quad has the size of a tile in the [TilesetDefinition], and takes its UVs from
+/// [TileInstance::source]. LDtk already accounts for the tileset's padding and spacing when
+/// calculating the source, so only the tileset image's size is needed to map it into UV space.
+/// The UVs are mirrored according to [TileInstance::flip_x] and [TileInstance::flip_y], and the
+/// tile's [TileInstance::opacity] is stored as the alpha of its vertex colors.
+///
+/// Quads are added in order, so a tile added later is drawn over the tiles before it.
+#[derive(Debug)]
+pub struct TileMeshBuilder {
+    // Size of a tile, in pixels.
+    tile_size: Vec2,
+    // Size of the tileset image, in pixels.
+    image_size: Vec2,
+    // Subtracted from the offset of every tile, see [Self::with_origin].
+    origin: I64Vec2,
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl TileMeshBuilder {
+    /// Creates an empty builder for tiles taken from the given tileset.
+    pub fn new(tileset_definition: &TilesetDefinition) -> Self {
+        Self {
+            tile_size: Vec2::splat(tileset_definition.tile_grid_pixel_size as f32),
+            image_size: tileset_definition.tileset_image_size.as_vec2(),
+            origin: I64Vec2::ZERO,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            colors: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    /// Places the mesh's origin at the given location in the layer, rather than at the layer's top
+    /// left corner.
+    pub const fn with_origin(mut self, origin: I64Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Adds a quad for the given tile.
+    pub fn add_tile(&mut self, tile: &TileInstance) {
+        let base = self.positions.len() as u32;
+
+        let corner = (tile.offset - self.origin).as_vec2();
+        let (left, right) = (corner.x, corner.x + self.tile_size.x);
+        let (top, bottom) = (-corner.y, -corner.y - self.tile_size.y);
+
+        let uv_corner = tile.source.as_vec2() / self.image_size;
+        let uv_size = self.tile_size / self.image_size;
+        let (mut u_left, mut u_right) = (uv_corner.x, uv_corner.x + uv_size.x);
+        let (mut v_top, mut v_bottom) = (uv_corner.y, uv_corner.y + uv_size.y);
+        if tile.flip_x {
+            std::mem::swap(&mut u_left, &mut u_right);
+        }
+        if tile.flip_y {
+            std::mem::swap(&mut v_top, &mut v_bottom);
+        }
+
+        self.positions.extend([
+            [left, top, 0.0],
+            [right, top, 0.0],
+            [right, bottom, 0.0],
+            [left, bottom, 0.0],
+        ]);
+        self.normals.extend([[0.0, 0.0, 1.0]; 4]);
+        self.uvs.extend([
+            [u_left, v_top],
+            [u_right, v_top],
+            [u_right, v_bottom],
+            [u_left, v_bottom],
+        ]);
+        self.colors.extend([[1.0, 1.0, 1.0, tile.opacity]; 4]);
+        // Counter clockwise, when viewed from the positive z-axis.
+        self.indices
+            .extend([base, base + 3, base + 2, base, base + 2, base + 1]);
+    }
+
+    /// The number of tiles added so far.
+    pub const fn len(&self) -> usize {
+        self.positions.len() / 4
+    }
+
+    /// Returns `true` if no tiles have been added.
+    pub const fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Builds the [Mesh], with positions, normals, UVs and vertex colors.
+    pub fn build(self) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, self.colors)
+        .with_inserted_indices(Indices::U32(self.indices))
+    }
+}
+
+/// Builds a single [Mesh] holding every tile of a tiles layer.
+///
+/// The `tileset_definition` should be the one pointed to by the layer's
+/// [TilesLayer::tileset_definition]. Returns [LdtkError::NotATilesLayer] for an entities layer.
+pub fn tiles_layer_mesh(
+    layer_instance: &LayerInstance,
+    tileset_definition: &TilesetDefinition,
+) -> LdtkResult<Mesh> {
+    let tiles_layer = get_tiles_layer(layer_instance)?;
+
+    let mut builder = TileMeshBuilder::new(tileset_definition);
+    tiles_layer
+        .tiles
+        .iter()
+        .for_each(|tile| builder.add_tile(tile));
+
+    Ok(builder.build())
+}
+
+pub(crate) fn get_tiles_layer(layer_instance: &LayerInstance) -> LdtkResult<&TilesLayer> {
+    layer_instance
+        .layer_type
+        .get_tiles_layer()
+        .ok_or_else(|| LdtkError::NotATilesLayer(layer_instance.identifier.clone()))
+}
+
+/// The texture and tint for a material drawing a mesh from [tiles_layer_mesh].
+///
+/// This crate doesn't depend on Bevy's renderer, so it can't create the material itself. These
+/// fields map directly onto a `ColorMaterial`, or onto the base color and texture of a
+/// `StandardMaterial`. The material should use alpha blending, and multiply its texture by the
+/// mesh's vertex colors, to honor the opacity of each tile.
+#[derive(Clone, Debug)]
+pub struct TilesLayerMaterial {
+    /// The tileset image, from [TilesLayer::tileset_image].
+    pub texture: Handle<Image>,
+    /// White, with the alpha of [LayerInstance::opacity].
+    pub color: Color,
+}
+
+impl TilesLayerMaterial {
+    /// Returns `None` for entities layers, and for tiles layers without a tileset image.
+    pub fn new(layer_instance: &LayerInstance) -> Option<Self> {
+        let texture = layer_instance
+            .layer_type
+            .get_tiles_layer()?
+            .tileset_image
+            .clone()?;
+        let color = Color::WHITE.with_alpha(layer_instance.opacity as f32);
+
+        Some(Self { texture, color })
+    }
+}
@@ -5,8 +5,8 @@
 //! See [Tileset Definition: LDtk docs](https://ldtk.io/json/#ldtk-TilesetDefJson)
 
 use bevy_asset::{Asset, Handle};
-use bevy_image::Image;
-use bevy_math::I64Vec2;
+use bevy_image::{Image, TextureAtlas, TextureAtlasLayout};
+use bevy_math::{I64Vec2, UVec2};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::Reflect;
 
@@ -33,8 +33,13 @@ pub struct TilesetDefinition {
     pub enum_tags: HashMap<String, Vec<i64>>,
     /// User defined unique identifier.
     pub identifier: String,
+    /// The unique identifier of this definition, referenced by layer instances and
+    /// [crate::tileset_rectangle::TilesetRectangle]s.
+    pub uid: Uid,
     /// Distance in pixels from image borders.
     pub padding: i64,
+    /// Space in pixels between the tiles.
+    pub spacing: i64,
     /// Size of the tileset image, in pixels.
     ///
     /// From the `pixHei` and `pixWid` LDtk JSON fields.
@@ -45,7 +50,13 @@ pub struct TilesetDefinition {
     /// that the image actually exists, or can be successfully loaded. If the
     /// asset fails to load, then we will hold onto the failed asset handle.
     ///
-    /// From the `relPath` LDtk JSON fields.
+    /// This is always `None` if the project was loaded with
+    /// [crate::project_loader::ProjectLoaderSettings::load_images] disabled.
+    ///
+    /// For a tileset using LDtk's internal atlas, this is the image given by
+    /// [crate::project_loader::ProjectLoaderSettings::ldtk_icons].
+    ///
+    /// From the `relPath` and `embedAtlas` LDtk JSON fields.
     pub tileset_image: Option<Handle<Image>>,
     /// An array of user defined tags.
     pub tags: Vec<String>,
@@ -55,47 +66,53 @@ pub struct TilesetDefinition {
     ///
     /// From the `tileGridSize` LDtk JSON fields.
     pub tile_grid_pixel_size: i64,
+    /// Handle to a [TextureAtlasLayout] with one texture per tile, indexed as by
+    /// [Self::atlas_index].
+    ///
+    /// This is a labeled sub asset of the project, labeled as `texture_atlas_layout:` followed by
+    /// the tileset's identifier.
+    pub texture_atlas_layout: Handle<TextureAtlasLayout>,
 }
 
 impl TilesetDefinition {
     pub(crate) async fn new(
         value: ldtk::TilesetDefinition,
         tileset_definition_images: &HashMap<String, Handle<Image>>,
+        ldtk_icons_image: Option<&Handle<Image>>,
+        load_images: bool,
     ) -> LdtkResult<Self> {
         // see https://github.com/stinkytoe/bevy_ldtk_asset/issues/35
-        if value.embed_atlas.is_some() {
+        if value.embed_atlas.is_some() && load_images && ldtk_icons_image.is_none() {
             return Err(ldtk_import_error!(
-                "This LDtk project contains an embedded atlas!\
-                 Licensing prevents us from loading this asset. At this time we are considering\
-                 it to be an error to attempt to load an LDtk project with an embedded atlas!\
-                 See https://github.com/stinkytoe/bevy_ldtk_asset/issues/35 for a discussion\
+                "This LDtk project contains an embedded atlas! \
+                 Licensing prevents us from distributing this image. Supply a copy of it through \
+                 the ldtk_icons loader setting to load this project. \
+                 See https://github.com/stinkytoe/bevy_ldtk_asset/issues/35 for a discussion \
                  relating to this decision."
             ));
         }
 
         let identifier = value.identifier;
+        let uid = value.uid;
         let tile_grid_size = (value.c_wid, value.c_hei).into();
-        let custom_data = value
-            .custom_data
-            .into_iter()
-            .map(|tile| (tile.tile_id, tile.data))
-            .collect();
-        let enum_tags = value
-            .enum_tags
-            .into_iter()
-            .map(|enum_tag| (enum_tag.enum_value_id, enum_tag.tile_ids))
-            .collect();
+        let custom_data = Self::parse_custom_data(value.custom_data);
+        let enum_tags = Self::parse_enum_tags(value.enum_tags);
         let padding = value.padding;
+        let spacing = value.spacing;
         let tileset_image_size = (value.px_wid, value.px_hei).into();
-        let tileset_image = value
-            .rel_path
-            .map(|rel_path| {
-                tileset_definition_images
-                    .get(&rel_path)
-                    .ok_or_else(|| ldtk_import_error!("Bad rel path! {rel_path}"))
-            })
-            .transpose()?
-            .cloned();
+        let tileset_image = match value.embed_atlas {
+            Some(ldtk::EmbedAtlas::LdtkIcons) => ldtk_icons_image.filter(|_| load_images).cloned(),
+            None => value
+                .rel_path
+                .filter(|_| load_images)
+                .map(|rel_path| {
+                    tileset_definition_images
+                        .get(&rel_path)
+                        .ok_or_else(|| ldtk_import_error!("Bad rel path! {rel_path}"))
+                })
+                .transpose()?
+                .cloned(),
+        };
 
         let tags = value.tags;
         let tags_source_enum_uid = value.tags_source_enum_uid;
@@ -103,17 +120,92 @@ impl TilesetDefinition {
 
         Ok(Self {
             identifier,
+            uid,
             tile_grid_size,
             custom_data,
             enum_tags,
             padding,
+            spacing,
             tileset_image_size,
             tileset_image,
             tags,
             tags_source_enum_uid,
             tile_grid_pixel_size,
+            texture_atlas_layout: Handle::default(),
         })
     }
+
+    // Shared with [crate::ldtk_data].
+    pub(crate) fn parse_custom_data(
+        custom_data: Vec<ldtk::TileCustomMetadata>,
+    ) -> HashMap<i64, String> {
+        custom_data
+            .into_iter()
+            .map(|tile| (tile.tile_id, tile.data))
+            .collect()
+    }
+
+    // Shared with [crate::ldtk_data].
+    pub(crate) fn parse_enum_tags(enum_tags: Vec<ldtk::EnumTagValue>) -> HashMap<String, Vec<i64>> {
+        enum_tags
+            .into_iter()
+            .map(|enum_tag| (enum_tag.enum_value_id, enum_tag.tile_ids))
+            .collect()
+    }
+
+    /// Builds a [TextureAtlasLayout] for the tileset image, with one texture per tile.
+    ///
+    /// Textures are ordered from the top left tile, row by row, in the same way as LDtk's tile ids.
+    pub fn build_texture_atlas_layout(&self) -> LdtkResult<TextureAtlasLayout> {
+        let tile_size = u32::try_from(self.tile_grid_pixel_size)?;
+        let padding = u32::try_from(self.padding)?;
+        let spacing = u32::try_from(self.spacing)?;
+
+        let mut texture_atlas_layout = TextureAtlasLayout::from_grid(
+            UVec2::splat(tile_size),
+            u32::try_from(self.tile_grid_size.x)?,
+            u32::try_from(self.tile_grid_size.y)?,
+            Some(UVec2::splat(spacing)),
+            Some(UVec2::splat(padding)),
+        );
+        // The grid alone doesn't account for the padding, or for any unused space at the right and
+        // bottom edges.
+        texture_atlas_layout.size = UVec2::new(
+            u32::try_from(self.tileset_image_size.x)?,
+            u32::try_from(self.tileset_image_size.y)?,
+        );
+
+        Ok(texture_atlas_layout)
+    }
+
+    /// The index into [Self::texture_atlas_layout] of the tile whose top left corner is at
+    /// `source`, in pixels within the tileset image.
+    ///
+    /// Returns `None` if `source` isn't the corner of a tile of this tileset.
+    pub fn atlas_index(&self, source: I64Vec2) -> Option<usize> {
+        let stride = self.tile_grid_pixel_size + self.spacing;
+        if stride <= 0 {
+            return None;
+        }
+
+        let source = source - I64Vec2::splat(self.padding);
+        let cell = source.div_euclid(I64Vec2::splat(stride));
+        let in_grid = cell.cmpge(I64Vec2::ZERO).all() && cell.cmplt(self.tile_grid_size).all();
+        let aligned = source.rem_euclid(I64Vec2::splat(stride)) == I64Vec2::ZERO;
+
+        (in_grid && aligned)
+            .then(|| usize::try_from(cell.y * self.tile_grid_size.x + cell.x).ok())
+            .flatten()
+    }
+
+    /// A [TextureAtlas] pointing at the tile with the given index, for use with
+    /// `Sprite::from_atlas_image` along with [Self::tileset_image].
+    pub fn texture_atlas(&self, index: usize) -> TextureAtlas {
+        TextureAtlas {
+            layout: self.texture_atlas_layout.clone(),
+            index,
+        }
+    }
 }
 
 impl LdtkAssetWithTags for TilesetDefinition {
@@ -44,4 +44,16 @@ impl TilesetRectangle {
             tileset_definition,
         })
     }
+
+    /// The index of this rectangle in the [TilesetDefinition::texture_atlas_layout] of its
+    /// tileset. See [TilesetDefinition::atlas_index].
+    ///
+    /// The `tileset_definition` should be the one pointed to by [Self::tileset_definition]. Returns
+    /// `None` if the rectangle doesn't cover exactly one tile, as an atlas only holds single
+    /// tiles. Such a rectangle can instead be drawn with a sprite's `rect`.
+    pub fn atlas_index(&self, tileset_definition: &TilesetDefinition) -> Option<usize> {
+        (self.size == I64Vec2::splat(tileset_definition.tile_grid_pixel_size))
+            .then(|| tileset_definition.atlas_index(self.corner))
+            .flatten()
+    }
 }
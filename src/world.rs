@@ -3,11 +3,10 @@
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
-use bevy_asset::{Asset, Handle, LoadContext};
+use bevy_asset::{Asset, AssetPath, Handle, LoadContext};
 use bevy_log::debug;
 use bevy_math::I64Vec2;
 use bevy_reflect::Reflect;
-use either::Either;
 use futures::future::try_join_all;
 use futures::lock::Mutex;
 
@@ -15,8 +14,8 @@ use crate::iid::{Iid, IidMap};
 use crate::ldtk;
 use crate::ldtk_asset_trait::{LdtkAsset, LdtkAssetWithChildren};
 use crate::ldtk_import_error;
-use crate::ldtk_path::ldtk_path_to_bevy_path;
 use crate::level::Level;
+use crate::level_loader::LevelLoaderSettings;
 use crate::project::ProjectContext;
 use crate::result::LdtkResult;
 
@@ -41,7 +40,7 @@ pub enum WorldLayout {
 }
 
 impl WorldLayout {
-    fn new(
+    pub(crate) fn new(
         layout: &Option<ldtk::WorldLayout>,
         world_grid_width: i64,
         world_grid_height: i64,
@@ -67,6 +66,9 @@ pub struct World {
     pub iid: Iid,
     pub world_layout: WorldLayout,
     pub levels: IidMap<Handle<Level>>,
+    /// For projects with external levels, the path of every level's `.ldtkl` file, including
+    /// levels left out by the [crate::project_loader::ProjectLoaderSettings]. Empty otherwise.
+    pub level_paths: IidMap<AssetPath<'static>>,
 }
 
 impl World {
@@ -86,45 +88,32 @@ impl World {
             world_json.world_grid_height,
         )?;
 
-        let external_levels = project_context.read()?.external_levels;
-
-        let levels_json = if external_levels {
-            let levels_json_iter =
-                world_json
-                    .levels
-                    .into_iter()
-                    .enumerate()
-                    .map(|(index, level_json)| {
-                        let project_context = project_context.clone();
-                        let load_context = load_context.clone();
-
-                        async move {
-                            let ldtk_path = level_json.external_rel_path.ok_or_else(|| {
-                                ldtk_import_error!(
-                                    "external_rel_path is `None` in an external_levels project?"
-                                )
-                            })?;
-
-                            let path = ldtk_path_to_bevy_path(
-                                &project_context.read()?.project_directory,
-                                ldtk_path,
-                            );
-
-                            let external_level_json =
-                                load_context.lock().await.read_asset_bytes(path).await?;
-                            let external_level_json: ldtk::Level =
-                                serde_json::from_slice(&external_level_json)?;
-
-                            LdtkResult::Ok((index, external_level_json))
-                        }
-                    });
-
-            let levels_json_iter = try_join_all(levels_json_iter).await?.into_iter();
-            Either::Left(levels_json_iter)
-        } else {
-            let levels_json_iter = world_json.levels.into_iter().enumerate();
-            Either::Right(levels_json_iter)
-        };
+        let level_paths = project_context
+            .read()?
+            .external_level_paths
+            .get(&iid)
+            .cloned()
+            .unwrap_or_default();
+
+        if !level_paths.is_empty() {
+            let levels = Self::load_external_levels(
+                world_json.levels,
+                &level_paths,
+                &project_context,
+                &load_context,
+            )
+            .await?;
+
+            return Ok(Self {
+                identifier,
+                iid,
+                world_layout,
+                levels,
+                level_paths,
+            });
+        }
+
+        let levels_json = world_json.levels.into_iter().enumerate();
 
         let levels_iter = levels_json.map(|(index, level_json)| {
             let load_context = load_context.clone();
@@ -157,8 +146,51 @@ impl World {
             iid,
             world_layout,
             levels,
+            level_paths,
         })
     }
+
+    // Each external level is loaded as its own asset by the [LevelLoader], rather than being built
+    // here.
+    async fn load_external_levels(
+        levels_json: Vec<ldtk::Level>,
+        level_paths: &IidMap<AssetPath<'static>>,
+        project_context: &RwLock<ProjectContext<'_>>,
+        load_context: &Mutex<&mut LoadContext<'_>>,
+    ) -> LdtkResult<IidMap<Handle<Level>>> {
+        let mut load_context = load_context.lock().await;
+
+        let settings = {
+            let project_context = project_context.read()?;
+            LevelLoaderSettings {
+                project_path: Some(load_context.path().clone_owned()),
+                dangling_entity_refs: project_context.dangling_entity_refs,
+                load_images: project_context.load_images,
+                load_entities: project_context.load_entities,
+                ldtk_icons: project_context.ldtk_icons.clone(),
+                project: project_context.level_project_json.clone(),
+            }
+        };
+
+        levels_json
+            .into_iter()
+            .map(|level_json| {
+                let iid = Iid::from_str(&level_json.iid)?;
+                let path = level_paths
+                    .get(&iid)
+                    .ok_or_else(|| ldtk_import_error!("No path for external level {iid}!"))?;
+
+                debug!("loading external level asset: {path}");
+                let settings = settings.clone();
+                let handle = load_context
+                    .loader()
+                    .with_settings(move |s: &mut LevelLoaderSettings| *s = settings.clone())
+                    .load(path.clone());
+
+                Ok((iid, handle))
+            })
+            .collect()
+    }
 }
 
 impl LdtkAsset for World {
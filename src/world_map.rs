@@ -0,0 +1,165 @@
+//! Drawing a downscaled overview map of an entire [World].
+//!
+//! [LdtkRasterizer::rasterize_world_map] draws every level of a world into one image, placed by
+//! [crate::level::Level::location] and [crate::level::Level::size], with levels of a greater
+//! [crate::level::Level::world_depth] drawn over the others. The scale is given as the number of
+//! map pixels drawn for each cell of [WorldMapSettings::cell_size] world pixels, so a pause screen
+//! map can be regenerated whenever the project changes:
+//!
+//! ```rust,ignore
+//! let settings = WorldMapSettings {
+//!     cell_size: I64Vec2::splat(256),
+//!     pixels_per_cell: 8,
+//!     visited: Some(visited_levels.clone()),
+//!     ..Default::default()
+//! };
+//! let map = LdtkRasterizer::from_ldtk_assets(&ldtk_assets, &images)
+//!     .rasterize_world_map(world, &settings)?;
+//! ```
+//!
+//! Levels which haven't been visited yet can be drawn in a flat color, or hidden altogether, by
+//! supplying the set of visited levels in [WorldMapSettings::visited].
+
+use bevy_color::Color;
+use bevy_image::Image;
+use bevy_math::{DVec2, I64Vec2};
+
+use crate::iid::IidSet;
+use crate::rasterize::{Canvas, LdtkRasterizer, get_asset};
+use crate::result::LdtkResult;
+use crate::world::World;
+
+/// How each visited level is drawn on the map.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorldMapLevelStyle {
+    /// Rasterize the level with all of its layers, as with [LdtkRasterizer::rasterize_level], and
+    /// average it down to the map's scale.
+    #[default]
+    Rasterize,
+    /// Fill the level with its [crate::level::Level::bg_color]. This doesn't need any of the
+    /// level's layers or images to be loaded.
+    BgColor,
+}
+
+/// Settings for [LdtkRasterizer::rasterize_world_map].
+#[derive(Clone, Debug)]
+pub struct WorldMapSettings {
+    /// The size of a map cell, in world pixels.
+    ///
+    /// For a [crate::world::WorldLayout::GridVania] world, this is usually its grid size.
+    pub cell_size: I64Vec2,
+    /// The number of map pixels drawn across each side of a cell.
+    pub pixels_per_cell: u32,
+    /// How visited levels are drawn.
+    pub level_style: WorldMapLevelStyle,
+    /// The [Iid](crate::iid::Iid)s of the levels which have been visited. When `None`, every level
+    /// is drawn as visited.
+    pub visited: Option<IidSet>,
+    /// The color of levels which haven't been visited. Use [Color::NONE] to leave them off of the
+    /// map.
+    pub unvisited_color: Color,
+    /// The color of the parts of the map not covered by any level.
+    pub background_color: Color,
+}
+
+impl Default for WorldMapSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: I64Vec2::splat(16),
+            pixels_per_cell: 1,
+            level_style: WorldMapLevelStyle::default(),
+            visited: None,
+            unvisited_color: Color::srgb(0.25, 0.25, 0.25),
+            background_color: Color::NONE,
+        }
+    }
+}
+
+impl LdtkRasterizer<'_> {
+    /// Draws an overview map of the world's levels, see the [module](crate::world_map) docs.
+    ///
+    /// The map covers the bounding box of every level in the world, with its top left corner at
+    /// the top left corner of that box. Its size is rounded up to whole pixels.
+    pub fn rasterize_world_map(
+        &self,
+        world: &World,
+        settings: &WorldMapSettings,
+    ) -> LdtkResult<Image> {
+        let mut levels = world
+            .levels
+            .values()
+            .map(|handle| get_asset(self.levels, handle))
+            .collect::<LdtkResult<Vec<_>>>()?;
+        levels.sort_by_key(|level| level.world_depth);
+
+        let min = levels
+            .iter()
+            .map(|level| level.location)
+            .reduce(I64Vec2::min)
+            .unwrap_or_default();
+        let max = levels
+            .iter()
+            .map(|level| level.location + level.size)
+            .reduce(I64Vec2::max)
+            .unwrap_or_default();
+
+        // Map pixels per world pixel.
+        let scale = settings.pixels_per_cell as f64 / settings.cell_size.as_dvec2();
+        let to_map = |location: I64Vec2| (location - min).as_dvec2() * scale;
+
+        let mut canvas = Canvas::new(
+            to_map(max).ceil().as_i64vec2(),
+            settings.background_color.to_srgba(),
+        )?;
+
+        for level in levels {
+            let map_min = to_map(level.location).floor().as_i64vec2();
+            let map_max = to_map(level.location + level.size).ceil().as_i64vec2();
+
+            let visited = settings
+                .visited
+                .as_ref()
+                .is_none_or(|visited| visited.contains(&level.iid));
+
+            if !visited {
+                canvas.fill_rect(
+                    map_min,
+                    map_max - map_min,
+                    settings.unvisited_color.to_srgba(),
+                );
+                continue;
+            }
+
+            match settings.level_style {
+                WorldMapLevelStyle::BgColor => {
+                    canvas.fill_rect(map_min, map_max - map_min, level.bg_color.to_srgba());
+                }
+                WorldMapLevelStyle::Rasterize => {
+                    let level_canvas = self.level_canvas(level)?;
+                    // The region of the level covered by a map pixel, in level pixels.
+                    let to_level = |map_location: I64Vec2| {
+                        map_location.as_dvec2() / scale + min.as_dvec2() - level.location.as_dvec2()
+                    };
+
+                    for y in map_min.y..map_max.y {
+                        for x in map_min.x..map_max.x {
+                            let map_location = I64Vec2::new(x, y);
+                            let level_min = to_level(map_location).floor();
+                            let level_max = to_level(map_location + I64Vec2::ONE)
+                                .ceil()
+                                .max(level_min + DVec2::ONE);
+
+                            if let Some(color) =
+                                level_canvas.average(level_min.as_i64vec2(), level_max.as_i64vec2())
+                            {
+                                canvas.blend(map_location, color, 1.0);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(canvas.into_image())
+    }
+}